@@ -0,0 +1 @@
+from ._rocksdbpy import *  # noqa: F401,F403
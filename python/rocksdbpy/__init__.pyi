@@ -0,0 +1,2266 @@
+import os
+from typing import Any, BinaryIO, ByteString, Callable, Dict, Iterable, Iterator, List, Mapping, Optional, Tuple, Union
+
+Path = Union[str, os.PathLike]
+KeyLike = Union[ByteString, str]
+Codec = Union[str, Tuple[Callable[[Any], bytes], Callable[[bytes], Any]], Any]
+
+
+def open_default(path: Path, codec: Optional[Codec] = None, text: bool = False, wait_for_lock: Optional[float] = None) -> RocksDB:
+    """
+    Opens a database with default options.
+
+    :param str or os.PathLike path: The database path
+    :param codec: The value codec, "utf-8", "json", "pickle", an (encode, decode) tuple or an object with encode and decode methods
+    :param bool text: Accept str keys as UTF-8, values are str unless a codec is given
+    :param float or None wait_for_lock: Seconds to retry while the database is locked by another instance
+    :return: active database
+    :rtype: rocksdbpy.DB
+    :raises DBLockedError: If the database is locked by another instance
+    """
+    ...
+
+
+def open(path: Path, opts: Optional[Option], codec: Optional[Codec] = None, text: bool = False, wait_for_lock: Optional[float] = None) -> RocksDB:
+    """
+    Opens the database with the specified options.
+
+    :param str or os.PathLike path: The database path
+    :param rocksdbpy.Option or None opts: The options
+    :param codec: The value codec, "utf-8", "json", "pickle", an (encode, decode) tuple or an object with encode and decode methods
+    :param bool text: Accept str keys as UTF-8, values are str unless a codec is given
+    :param float or None wait_for_lock: Seconds to retry while the database is locked by another instance
+    :return: active database
+    :rtype: rocksdbpy.DB
+    :raises DBLockedError: If the database is locked by another instance
+    """
+    ...
+
+
+def open_with_ttl(path: Path, ttl: int, opts: Optional[Option], codec: Optional[Codec] = None, text: bool = False, cf_ttls: Optional[Dict[str, int]] = None, wait_for_lock: Optional[float] = None) -> RocksDB:
+    """
+    Opens the database with TTL compaction filter.
+
+    :param str or os.PathLike path: The database path
+    :param int ttl: The TTL duration in seconds
+    :param rocksdbpy.Option or None opts: The options
+    :param codec: The value codec, "utf-8", "json", "pickle", an (encode, decode) tuple or an object with encode and decode methods
+    :param bool text: Accept str keys as UTF-8, values are str unless a codec is given
+    :param dict or None cf_ttls: The TTL in seconds of column families, missing column families are created
+    :param float or None wait_for_lock: Seconds to retry while the database is locked by another instance
+    :return: active database
+    :rtype: rocksdbpy.DB
+    :raises DBLockedError: If the database is locked by another instance
+
+    """
+    ...
+
+
+def open_for_readonly(path: Path, option: Optional[Option] = None, error: Optional[bool] = None, codec: Optional[Codec] = None, text: bool = False) -> RocksDB:
+    """
+    Opens the database for read only with the specified options.
+
+    :param str or os.PathLike path: The database path
+    :param rocksdbpy.Option or None option: The options
+    :param bool or None error: Raise an error if write ahead log exists
+    :param codec: The value codec, "utf-8", "json", "pickle", an (encode, decode) tuple or an object with encode and decode methods
+    :param bool text: Accept str keys as UTF-8, values are str unless a codec is given
+    :return: active database
+    :rtype: rocksdbpy.DB
+
+    """
+    ...
+
+
+def open_as_secondary(primary: Path, secondary: Path, option: Optional[Option] = None, codec: Optional[Codec] = None, text: bool = False, catch_up_interval: Optional[float] = None) -> RocksDB:
+    """
+    Opens the database as a secondary.
+
+    :param str or os.PathLike primary: The database primary path
+    :param str or os.PathLike secondary: The directory where the secondary instance stores its info log
+    :param rocksdbpy.Option or None option: The options
+    :param codec: The value codec, "utf-8", "json", "pickle", an (encode, decode) tuple or an object with encode and decode methods
+    :param bool text: Accept str keys as UTF-8, values are str unless a codec is given
+    :param float or None catch_up_interval: Seconds between the catch-ups with the primary on a background thread
+    :return: active database
+    :rtype: rocksdbpy.DB
+
+    """
+    ...
+
+
+def destroy(path: Path, option: Optional[Option] = None) -> None:
+    """
+    Destroy database and it's files.
+
+    :param str or os.PathLike path: The database path
+    :param rocksdbpy.Option or None option: The options
+    """
+    ...
+
+
+
+def list_column_families(path: Path, option: Optional[Option] = None) -> List[str]:
+    """
+    Returns the names of the column families of the database.
+
+    :param str or os.PathLike path: The database path
+    :param rocksdbpy.Option or None option: The options
+    :rtype: list[str]
+    """
+    ...
+
+
+class RocksDB:
+    @staticmethod
+    def open_for_read_only(
+        path: Path,
+        error_if_log_file_exists: bool = False,
+        option: Optional[Option] = None,
+        codec: Optional[Codec] = None,
+        text: bool = False,
+    ) -> RocksDB:
+        """
+        Opens the database for read only without locking it, so it can be opened while another process writes to it.
+
+        :param path: The path of the database
+        :param bool error_if_log_file_exists: Raise if the write-ahead log is not empty
+        :param option: The database options
+        :param codec: The value codec
+        :param bool text: Accept str keys
+        :rtype: rocksdbpy.RocksDB
+        """
+        ...
+
+    @staticmethod
+    def open_as_secondary(
+        primary_path: Path,
+        secondary_path: Path,
+        catch_up_interval: Optional[float] = None,
+        option: Optional[Option] = None,
+        codec: Optional[Codec] = None,
+        text: bool = False,
+    ) -> RocksDB:
+        """
+        Opens the database as a secondary instance of the primary.
+
+        :param primary_path: The path of the primary database
+        :param secondary_path: The directory where the secondary instance stores its logs
+        :param float or None catch_up_interval: Seconds between the catch-ups with the primary on a background thread
+        :param option: The database options
+        :param codec: The value codec
+        :param bool text: Accept str keys
+        :rtype: rocksdbpy.RocksDB
+        """
+        ...
+
+    @staticmethod
+    def open_with_ttl(
+        path: Path,
+        ttl_seconds: int,
+        option: Optional[Option] = None,
+        cf_ttls: Optional[Dict[str, int]] = None,
+        codec: Optional[Codec] = None,
+        text: bool = False,
+        wait_for_lock: Optional[float] = None,
+    ) -> RocksDB:
+        """
+        Opens the database with TTL, the records which are older than the TTL are removed by the compactions.
+
+        :param path: The path of the database
+        :param int ttl_seconds: The TTL of the records in seconds
+        :param option: The database options, the database is created if it is missing without options
+        :param dict or None cf_ttls: The TTL in seconds of column families, missing column families are created
+        :param codec: The value codec
+        :param bool text: Accept str keys
+        :param float or None wait_for_lock: Seconds to retry while the database is locked by another instance
+        :rtype: rocksdbpy.RocksDB
+        :raises DBLockedError: If the database is locked by another instance
+        """
+        ...
+
+    @property
+    def path(self) -> str:
+        """
+        The path of the database.
+        """
+        ...
+
+    @property
+    def is_open(self) -> bool:
+        """
+        True until the database is closed.
+        """
+        ...
+
+    @property
+    def read_only(self) -> bool:
+        """
+        True if the database is opened for read only or as a secondary.
+        """
+        ...
+
+    def get(self, key: KeyLike, cf: Optional[str] = None) -> Optional[Any]:
+        """
+        Return the value associated with a "key". The value is decoded if the database is opened with a codec.
+
+        :param bytes-like key: The entry key
+        :param str cf: The column family name, the default column family if omitted
+        :return: The entry value if exists, NULL otherwise
+        :rtype: bytes, decoded object or None
+        """
+        ...
+
+    def get_pinned(self, key: KeyLike) -> Optional[memoryview]:
+        """
+        Return the value associated with a "key" as a memoryview without copying the value.
+
+        :param bytes-like key: The entry key
+        :return: The entry value if exists, NULL otherwise
+        :rtype: memoryview or None
+        """
+        ...
+
+    def set(self, key: KeyLike, value: Any, ttl: Optional[int] = None, cf: Optional[str] = None) -> None:
+        """
+        Sets records by "key" and "value". The value is encoded if the database is opened with a codec.
+
+        :param bytes-like key: The entry key
+        :param value: The entry value, bytes-like unless the database is opened with a codec
+        :param int ttl: Seconds until the record expires, requires key TTL enabled by the options
+        :param str cf: The column family name, the default column family if omitted
+        """
+        ...
+
+    def delete(self, key: KeyLike, cf: Optional[str] = None) -> None:
+        """
+        Removes existing records by "key".
+
+        :param bytes-like key: The entry key
+        :param str cf: The column family name, the default column family if omitted
+        """
+        ...
+
+    def set_with_ts(self, key: KeyLike, value: Any, ts: int, cf: Optional[str] = None) -> None:
+        """
+        Sets records by "key" and "value" at the user timestamp, requires opts.enable_user_timestamp().
+
+        :param bytes-like key: The entry key
+        :param value: The entry value
+        :param int ts: The user timestamp of the record
+        :param str or None cf: The column family
+        """
+        ...
+
+    def get_with_ts(self, key: KeyLike, ts: int, cf: Optional[str] = None) -> Optional[Any]:
+        """
+        Returns the value of the "key" as of the user timestamp, the newest value set at or before it.
+
+        :param bytes-like key: The entry key
+        :param int ts: The user timestamp of the read
+        :param str or None cf: The column family
+        :return: The value if exists as of the timestamp, None otherwise
+        """
+        ...
+
+    def delete_with_ts(self, key: KeyLike, ts: int, cf: Optional[str] = None) -> None:
+        """
+        Removes existing records by "key" at the user timestamp.
+
+        :param bytes-like key: The entry key
+        :param int ts: The user timestamp of the removal
+        :param str or None cf: The column family
+        """
+        ...
+
+    def increase_full_history_ts_low(self, ts: int, cf: Optional[str] = None) -> None:
+        """
+        Raises the lowest user timestamp whose history is kept, older versions may be dropped by compactions.
+
+        :param int ts: The new lowest timestamp, it must not be lower than the current one
+        :param str or None cf: The column family
+        """
+        ...
+
+    def get_full_history_ts_low(self, cf: Optional[str] = None) -> int:
+        """
+        Returns the lowest user timestamp whose history is kept, 0 if it has not been raised.
+
+        :param str or None cf: The column family
+        :rtype: int
+        """
+        ...
+
+    def pop(self, key: KeyLike, default: Optional[Any] = None) -> Optional[Any]:
+        """
+        Removes the "key" and returns its value atomically.
+
+        :param bytes-like key: The entry key
+        :param default: The value which is returned if the key is missing
+        :return: The removed value if exists, the default otherwise
+        """
+        ...
+
+    def get_or_set(self, key: KeyLike, default: Any) -> Any:
+        """
+        Returns the value of the "key" if it exists, otherwise stores the default and returns it atomically.
+
+        :param bytes-like key: The entry key
+        :param default: The value which is stored if the key is missing
+        :return: The existing value or the default
+        """
+        ...
+
+    def setdefault(self, key: KeyLike, default: Any) -> Any:
+        """
+        Alias of `get_or_set`.
+        """
+        ...
+
+    def compare_and_swap(
+        self,
+        key: KeyLike,
+        expected: Optional[Any] = None,
+        new: Optional[Any] = None,
+        ttl: Optional[int] = None,
+        cf: Optional[str] = None,
+    ) -> Tuple[bool, Optional[Any]]:
+        """
+        Replaces the value of the "key" atomically only if the current value equals the expected value.
+
+        :param bytes-like key: The entry key
+        :param expected: The expected value, None means the key must be missing
+        :param new: The new value, None removes the key
+        :param int ttl: Seconds until the new value expires, requires key TTL enabled by the options
+        :param str cf: The column family name, the default column family if omitted
+        :return: Whether the value is replaced and the value which was found
+        :rtype: tuple
+        """
+        ...
+
+    def update(
+        self,
+        key: KeyLike,
+        func: Callable[[Optional[Any]], Optional[Any]],
+        ttl: Optional[int] = None,
+        cf: Optional[str] = None,
+    ) -> Optional[Any]:
+        """
+        Replaces the value of the "key" with the result of the function. The function is called again if another write changes the key before the result is written, so concurrent updates are not lost.
+
+        :param bytes-like key: The entry key
+        :param func: Called with the current value or None, returning None removes the key
+        :param int ttl: Seconds until the new value expires, requires key TTL enabled by the options
+        :param str cf: The column family name, the default column family if omitted
+        :return: The new value
+        """
+        ...
+
+    def incr(self, key: KeyLike, delta: Optional[int] = 1) -> int:
+        """
+        Adds the delta to the counter of the "key" by a merge and returns the new value. Counters are stored as 8 little-endian bytes.
+
+        :param bytes-like key: The counter key
+        :param int or None delta: The delta
+        :return: The new value of the counter
+        :rtype: int
+        """
+        ...
+
+    def decr(self, key: KeyLike, delta: Optional[int] = 1) -> int:
+        """
+        Subtracts the delta from the counter of the "key" and returns the new value.
+
+        :param bytes-like key: The counter key
+        :param int or None delta: The delta
+        :return: The new value of the counter
+        :rtype: int
+        """
+        ...
+
+    def append(self, key: KeyLike, data: ByteString) -> None:
+        """
+        Appends the data to the value of the "key" by a merge without reading the value.
+
+        :param bytes-like key: The entry key
+        :param bytes-like data: The appended data
+        """
+        ...
+
+    def put_entity(
+        self,
+        key: KeyLike,
+        columns: Mapping[Union[ByteString, str], ByteString],
+        ttl: Optional[int] = None,
+        cf: Optional[str] = None,
+    ) -> None:
+        """
+        Sets the key to an entity of named columns, the columns are stored without the codec in the
+        column family "rocksdbpy.entities.<cf>". The plain value of the key is removed.
+
+        :param bytes-like key: The entry key
+        :param dict columns: The values of the columns by their names
+        :param int or None ttl: The TTL of the key in seconds if key TTL is enabled by the options
+        :param str or None cf: The column family
+        """
+        ...
+
+    def get_entity(self, key: KeyLike, cf: Optional[str] = None) -> Optional[Dict[bytes, bytes]]:
+        """
+        Returns the columns of the entity of the key, a plain value is returned as the default column with an empty name.
+
+        :param bytes-like key: The entry key
+        :param str or None cf: The column family
+        :rtype: dict or None
+        """
+        ...
+
+    def entities(
+        self,
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        reverse: Optional[bool] = False,
+    ) -> EntityIterator:
+        """
+        Returns an iterator over the (key, columns) entries of the range.
+
+        :param bytes or None start: The inclusive start key of the range
+        :param bytes or None stop: The exclusive stop key of the range
+        :param bool or None reverse: Iterate from the end of the range to the start
+        :rtype: rocksdbpy.EntityIterator
+        """
+        ...
+
+    async def aget(self, key: KeyLike, cf: Optional[str] = None) -> Optional[Any]:
+        """
+        Awaitable get which runs on the default executor of the running event loop.
+
+        :param bytes-like key: The entry key
+        :param str or None cf: The column family name
+        :return: The entry value or None
+        """
+        ...
+
+    async def aset(self, key: KeyLike, value: Any, ttl: Optional[int] = None, cf: Optional[str] = None) -> None:
+        """
+        Awaitable set which runs on the default executor of the running event loop.
+
+        :param bytes-like key: The entry key
+        :param value: The entry value
+        :param int or None ttl: The time to live of the entry in seconds
+        :param str or None cf: The column family name
+        """
+        ...
+
+    async def adelete(self, key: KeyLike, cf: Optional[str] = None) -> None:
+        """
+        Awaitable delete which runs on the default executor of the running event loop.
+
+        :param bytes-like key: The entry key
+        :param str or None cf: The column family name
+        """
+        ...
+
+    async def amulti_get(self, keys: List[KeyLike], skip_missings: Optional[bool] = None) -> List[Any]:
+        """
+        Awaitable multi_get which runs on the default executor of the running event loop.
+
+        :param list keys: The entry keys
+        :param bool or None skip_missings: Keep None for the missing keys
+        :rtype: list
+        """
+        ...
+
+    async def awrite(
+        self,
+        batch: Union[WriteBatch, WriteBatchWithIndex],
+        sync: bool = False,
+        disable_wal: bool = False,
+        no_slowdown: bool = False,
+    ) -> None:
+        """
+        Awaitable write which runs on the default executor of the running event loop.
+
+        :param batch: The batch writer
+        :type batch: rocksdbpy.WriteBatch or rocksdbpy.WriteBatchWithIndex
+        :param bool sync: Fsync the write-ahead log before the call returns
+        :param bool disable_wal: Skip the write-ahead log
+        :param bool no_slowdown: Fail instead of waiting if the writes are stalled
+        """
+        ...
+
+    def __getitem__(self, key: KeyLike) -> Any:
+        """
+        Return the value associated with a "key".
+
+        :param bytes key: The entry key
+        :return: The entry value
+        :rtype: bytes
+        :raises KeyError: If the key does not exist
+        """
+        ...
+
+    def __setitem__(self, key: KeyLike, value: Any) -> None:
+        """
+        Sets records by "key" and "value".
+
+        :param bytes key: The entry key
+        :param bytes value: The entry value
+        """
+        ...
+
+    def __delitem__(self, key: KeyLike) -> None:
+        """
+        Removes existing records by "key".
+
+        :param bytes key: The entry key
+        """
+        ...
+
+    def __contains__(self, key: KeyLike) -> bool:
+        """
+        Returns true if the "key" exists.
+
+        :param bytes key: The entry key
+        :rtype: bool
+        """
+        ...
+
+    def __len__(self) -> int:
+        """
+        Returns the estimated number of keys by the "rocksdb.estimate-num-keys" property.
+
+        :rtype: int
+        """
+        ...
+
+    def exact_len(self) -> int:
+        """
+        Returns the exact number of keys by scanning the database.
+
+        :rtype: int
+        """
+        ...
+
+    def write(
+        self,
+        batch: Union[WriteBatch, WriteBatchWithIndex],
+        sync: bool = False,
+        disable_wal: bool = False,
+        no_slowdown: bool = False,
+    ) -> None:
+        """
+        Sets database entries for list of key and values as a batch.
+
+        :param batch: The batch writer
+        :type batch: rocksdbpy.WriteBatch or rocksdbpy.WriteBatchWithIndex
+        :param bool sync: Fsync the write-ahead log before the call returns
+        :param bool disable_wal: Skip the write-ahead log, the batch is lost if the process crashes before a flush
+        :param bool no_slowdown: Fail instead of waiting if the writes are stalled
+        """
+        ...
+
+    def put_many(self, items: Union[Mapping[KeyLike, Any], Iterable[Tuple[KeyLike, Any]]]) -> None:
+        """
+        Sets database entries from a mapping or pairs atomically as a batch.
+
+        :param dict or iterable items: The entry keys and values
+        """
+        ...
+
+    def delete_many(self, keys: Iterable[KeyLike]) -> None:
+        """
+        Removes the given keys atomically as a batch.
+
+        :param iterable[bytes] keys: The entry keys
+        """
+        ...
+
+    def delete_prefix(self, prefix: KeyLike) -> None:
+        """
+        Removes every key which starts with the given prefix.
+
+        :param bytes prefix: The key prefix
+        """
+        ...
+
+    def write_batch(self) -> WriteBatch:
+        """
+        Returns a batch which is written atomically when the "with" block is completed.
+
+        :return: The batch writer which is bound to the database
+        :rtype: rocksdbpy.WriteBatch
+        """
+        ...
+
+    def multi_get(self, keys: List[KeyLike], skip_missings: Optional[bool] = None) -> List[Any]:
+        """
+        Returns entries according to given list of key and values.
+
+        :param list[bytes] keys: The list of entry keys
+        :param bool or None skip_missings: Skips missing records if it's True
+        :return: The list of entry values
+        :rtype: list[bytes]
+        """
+        ...
+
+    def multi_get_dict(self, keys: List[KeyLike]) -> Dict[KeyLike, Optional[Any]]:
+        """
+        Returns a dictionary which maps every given key to its value.
+
+        :param list[bytes] keys: The list of entry keys
+        :return: The entry values by keys, None if the key does not exist
+        :rtype: dict[bytes, bytes or None]
+        """
+        ...
+
+    def iterator(
+        self,
+        mode: Optional[str] = None,
+        key: Optional[KeyLike] = None,
+        direction: Optional[int] = 1,
+        *,
+        lower_bound: Optional[KeyLike] = None,
+        upper_bound: Optional[KeyLike] = None,
+        tailing: Optional[bool] = False,
+        readahead_size: Optional[int] = None,
+        auto_readahead_size: Optional[bool] = None,
+        async_io: Optional[bool] = False,
+        keys: Optional[bool] = True,
+        values: Optional[bool] = True,
+        pinned: Optional[bool] = False,
+        timestamp: Optional[int] = None,
+    ) -> Iterator[DBIterator]:
+        """
+        Returns a heap-allocated iterator over the contents of the database.
+
+        :param str or None mode: The iteration mode. Accepted options are "from", "for_prev", "end" and "start"
+        :param bytes or None key: The iterator start key
+        :param int or None direction: The iteration direction. Default is forward
+        :param bytes or None lower_bound: The inclusive lower bound of the iteration
+        :param bytes or None upper_bound: The exclusive upper bound of the iteration
+        :param bool or None tailing: Continue from the last entry to pick up new entries
+        :param int or None readahead_size: The number of bytes to read ahead on sequential reads
+        :param bool or None auto_readahead_size: Tune the readahead size by the upper bound
+        :param bool or None async_io: Prefetch data asynchronously on sequential reads
+        :param bool or None keys: Return the keys, only values are returned if it's False
+        :param bool or None values: Return the values, only keys are returned if it's False
+        :param bool or None pinned: Return values as memoryview objects which are valid until the next entry, not supported with a codec
+        :param int or None timestamp: Read the records as of the user timestamp
+        :return: The database iterator
+        :rtype: iterator[rocksdbpy.DBIterator]
+        """
+        ...
+
+    def items(
+        self,
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        reverse: Optional[bool] = False,
+    ) -> Iterator[Tuple[bytes, bytes]]:
+        """
+        Returns an iterator over the entries of the range.
+
+        :param bytes or None start: The inclusive start key of the range
+        :param bytes or None stop: The exclusive stop key of the range
+        :param bool or None reverse: Iterate from the end of the range to the start
+        :return: The database iterator
+        :rtype: iterator[(bytes, bytes)]
+        """
+        ...
+
+    def keys(
+        self,
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        reverse: Optional[bool] = False,
+    ) -> Iterator[bytes]:
+        """
+        Returns an iterator over the keys of the range.
+
+        :param bytes or None start: The inclusive start key of the range
+        :param bytes or None stop: The exclusive stop key of the range
+        :param bool or None reverse: Iterate from the end of the range to the start
+        :return: The database iterator
+        :rtype: iterator[bytes]
+        """
+        ...
+
+    def values(
+        self,
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        reverse: Optional[bool] = False,
+    ) -> Iterator[bytes]:
+        """
+        Returns an iterator over the values of the range.
+
+        :param bytes or None start: The inclusive start key of the range
+        :param bytes or None stop: The exclusive stop key of the range
+        :param bool or None reverse: Iterate from the end of the range to the start
+        :return: The database iterator
+        :rtype: iterator[bytes]
+        """
+        ...
+
+    def aiter(
+        self,
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        reverse: Optional[bool] = False,
+        batch_size: Optional[int] = 1000,
+    ) -> AsyncDBIterator:
+        """
+        Returns an async iterator over the (key, value) entries of the range. The entries are read in batches on the default executor of the running event loop.
+
+        :param bytes or None start: The inclusive start key of the range
+        :param bytes or None stop: The exclusive stop key of the range
+        :param bool or None reverse: Iterate from the end of the range to the start
+        :param int or None batch_size: The number of entries read by one executor call
+        :rtype: rocksdbpy.AsyncDBIterator
+        """
+        ...
+
+    def parallel_scan(self, start: Optional[KeyLike] = None, stop: Optional[KeyLike] = None, workers: Optional[int] = None) -> ParallelScan:
+        """
+        Returns an iterator over the (key, value) entries of the range which is read by many threads without holding the GIL. The entries of a chunk are in order, but the chunks are interleaved.
+
+        :param bytes-like or None start: The inclusive start key of the range
+        :param bytes-like or None stop: The exclusive stop key of the range
+        :param int or None workers: The number of the threads, the number of the CPUs by default
+        :rtype: rocksdbpy.ParallelScan
+        :raises ValueError: If workers is 0
+        """
+        ...
+
+    def count(
+        self,
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        approximate: Optional[bool] = False,
+    ) -> int:
+        """
+        Returns the number of keys in the range, the keys are counted without holding the GIL.
+
+        :param bytes or None start: The inclusive start key of the range
+        :param bytes or None stop: The exclusive stop key of the range
+        :param bool or None approximate: Estimate the number by the SST file metadata
+        :return: The number of keys
+        :rtype: int
+        """
+        ...
+
+    def prefix_iterator(self, prefix: KeyLike) -> Iterator[DBIterator]:
+        """
+        Returns an iterator over the entries whose keys start with the given prefix.
+
+        :param bytes prefix: The key prefix
+        :return: The database iterator
+        :rtype: iterator[rocksdbpy.DBIterator]
+        """
+        ...
+
+    def namespace(self, prefix: KeyLike) -> Namespace:
+        """
+        Returns a view over the keys which start with the prefix. The prefix is prepended to the given keys and removed from the keys of the iterators.
+
+        :param bytes-like prefix: The key prefix
+        :rtype: rocksdbpy.Namespace
+        """
+        ...
+
+    def create_column_family(self, name: str) -> None:
+        """
+        Creates the column family "name" with the options of the database if it is missing.
+
+        :param str name: The column family name
+        """
+        ...
+
+    def drop_column_family(self, name: str) -> None:
+        """
+        Drops the column family "name" and its records.
+
+        :param str name: The column family name
+        """
+        ...
+
+    def buffered(
+        self,
+        max_ops: Optional[int] = None,
+        max_bytes: Optional[int] = None,
+        flush_interval: Optional[float] = None,
+    ) -> BufferedWriter:
+        """
+        Returns a writer which collects the writes and writes them as a batch on the thresholds, every flush_interval seconds and when the writer is flushed or closed. The buffered writes are not visible to reads until they are written.
+
+        :param int max_ops: Write the batch when it has this many writes
+        :param int max_bytes: Write the batch when it has this many bytes
+        :param float flush_interval: Write the batch periodically in seconds
+        :rtype: rocksdbpy.BufferedWriter
+        """
+        ...
+
+    def queue(self, name: str = 'queue') -> Queue:
+        """
+        Returns a durable queue stored in the column family "name", the column family is created if it is missing.
+
+        :param str name: The column family name
+        :rtype: rocksdbpy.Queue
+        """
+        ...
+
+    def cursor(
+        self,
+        lower_bound: Optional[KeyLike] = None,
+        upper_bound: Optional[KeyLike] = None,
+        readahead_size: Optional[int] = None,
+        auto_readahead_size: Optional[bool] = None,
+        async_io: Optional[bool] = False,
+    ) -> Cursor:
+        """
+        Returns a cursor over the contents of the database which can be repositioned.
+
+        :param bytes or None lower_bound: The inclusive lower bound of the cursor
+        :param bytes or None upper_bound: The exclusive upper bound of the cursor
+        :param int or None readahead_size: The number of bytes to read ahead on sequential reads
+        :param bool or None auto_readahead_size: Tune the readahead size by the upper bound
+        :param bool or None async_io: Prefetch data asynchronously on sequential reads
+        :return: The database cursor
+        :rtype: rocksdbpy.Cursor
+        """
+        ...
+
+    def flush(self) -> None:
+        """
+        Flushes database memtables to SST files on the disk using default options.
+        """
+        ...
+
+    def cancel_all_background_work(self, wait: Optional[bool] = None) -> None:
+        """
+        Request stopping background work, if wait is true wait until it's done.
+
+        :param bool or None wait: Wait until the background work is stopped
+        """
+        ...
+
+    def compact_range(self, start: Optional[ByteString] = None, stop: Optional[ByteString] = None, cf: Optional[str] = None) -> None:
+        """
+        Compacts the keys of the range to the bottommost level, the whole database if the bounds are omitted.
+
+        :param bytes-like or None start: The first key of the range
+        :param bytes-like or None stop: The key after the range
+        :param str or None cf: The column family
+        """
+        ...
+
+    def compact_range_async(
+        self,
+        start: Optional[ByteString] = None,
+        stop: Optional[ByteString] = None,
+        cf: Optional[str] = None,
+        progress: Optional[Callable[[int, int], None]] = None,
+    ) -> Compaction:
+        """
+        Starts a compaction of the range on a background thread. The range is compacted in chunks, so the compaction reports its progress and can be canceled between the chunks.
+
+        :param bytes-like or None start: The first key of the range
+        :param bytes-like or None stop: The key after the range
+        :param str or None cf: The column family
+        :param callable or None progress: Called with the number of the compacted chunks and the number of all chunks
+        :rtype: rocksdbpy.Compaction
+        """
+        ...
+
+    def verify(self, progress: Optional[Callable[[int, int], None]] = None) -> Dict[str, Any]:
+        """
+        Reads every record with the checksum verification on and decodes the values, the errors are collected into a report instead of raised. The scan continues after the key range of a corrupt SST file.
+
+        :param callable or None progress: Called with the number of the verified records and the estimated number of all records
+        :return: ok, keys which is the number of the verified records, corrupt_files with their name, column_family and error, and corrupt_keys with their key, column_family and error
+        :rtype: dict
+        """
+        ...
+
+    def property(self, name: str, cf: Optional[str] = None) -> Optional[str]:
+        """
+        Returns the value of a RocksDB property, e.g. "rocksdb.stats".
+
+        :param str name: The property name
+        :param str or None cf: The column family
+        :return: The value, None if the property is not supported
+        """
+        ...
+
+    def disk_usage(self) -> Dict[str, Any]:
+        """
+        Returns the size and the number of the live SST files.
+
+        :return: A dict with "size", "files" and "column_families", which maps each column
+            family with files to a dict of its "size" and "files"
+        """
+        ...
+
+    def try_catch_up_with_primary(self) -> None:
+        """
+        Tries to catch up with the primary database.
+        """
+        ...
+
+    def catch_up_lag(self) -> Dict[str, int]:
+        """
+        Returns how far a secondary is behind its primary. The primary is opened for read only to read its sequence number.
+
+        :return: primary_sequence, secondary_sequence and lag
+        :rtype: dict
+        :raises RocksDBException: If the database is not a secondary
+        """
+        ...
+
+    def latest_sequence_number(self) -> int:
+        """
+        Returns the sequence number of the most recent write.
+
+        :rtype: int
+        """
+        ...
+
+    def changes_since(self, seq: int) -> Dict[str, Any]:
+        """
+        Returns the keys of the default column family which changed after the sequence number, read from the write ahead log. Every key is returned once with its current value, None if it is deleted. The keys and the values are decoded like the results of get.
+
+        :param int seq: The sequence number of the previous sync
+        :return: sequence which is passed to the next call, changes which is the list of the (key, value) pairs and deleted_ranges which is the list of the (start, end) ranges
+        :rtype: dict
+        :raises RocksDBException: If the write ahead log since the sequence number is deleted
+        """
+        ...
+
+    def create_checkpoint(self, path: Path) -> None:
+        """
+        Creates a consistent checkpoint of the database at the given path, which must not exist. The files are hard-linked on the same filesystem.
+
+        :param str or os.PathLike path: Path to the directory of the checkpoint
+        """
+        ...
+
+    def ingest_external_file(
+        self,
+        paths: List[Path],
+        move_files: bool = True,
+        ingest_behind: bool = False,
+        cf: Optional[str] = None,
+    ) -> None:
+        """
+        Adds the SST files of SstFileWriter or of another database to the database atomically.
+
+        :param list paths: The paths of the files
+        :param bool move_files: Move the files into the database, otherwise they are copied
+        :param bool ingest_behind: Add the files below the existing data, requires set_allow_ingest_behind
+        :param str or None cf: The column family
+        """
+        ...
+
+    def stream_checkpoint(self, fileobj: BinaryIO) -> None:
+        """
+        Writes a consistent checkpoint of the database into the file object as an uncompressed tar stream.
+
+        :param fileobj: A writable binary file object
+        """
+        ...
+
+    @staticmethod
+    def restore_from_stream(fileobj: BinaryIO, path: Path) -> None:
+        """
+        Restores the tar stream of stream_checkpoint into the path, which must be missing or empty.
+
+        :param fileobj: A readable binary file object
+        :param str or os.PathLike path: The database directory
+        """
+        ...
+
+    def dump(
+        self,
+        target: Union[Path, BinaryIO],
+        format: str = 'jsonl',
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        cf: Optional[str] = None,
+    ) -> int:
+        """
+        Writes the records of the range into a file, the stored bytes are written without the codec.
+
+        :param target: The file path or a binary file object
+        :param str format: "jsonl" for a JSON object per line with base64 "key" and "value", or "cbor" for a CBOR sequence of [key, value] arrays
+        :param bytes or None start: The first key of the range
+        :param bytes or None stop: The key after the range
+        :param str or None cf: The column family
+        :return: The number of the records
+        :rtype: int
+        """
+        ...
+
+    def load(self, source: Union[Path, BinaryIO], format: str = 'jsonl', cf: Optional[str] = None) -> int:
+        """
+        Writes the records of a dump into the database, the existing keys are overwritten.
+
+        :param source: The file path or a binary file object
+        :param str format: The format of the dump, "jsonl" or "cbor"
+        :param str or None cf: The column family
+        :return: The number of the records
+        :rtype: int
+        """
+        ...
+
+    def ingest_records(self, records: Iterable[Tuple[ByteString, ByteString]], cf: Optional[str] = None) -> int:
+        """
+        Imports the records of an iterable, e.g. a plyvel iterator, by SST files which are ingested.
+
+        :param iterable records: The (key, value) records in ascending key order
+        :param str or None cf: The column family
+        :return: The number of the records
+        :rtype: int
+        """
+        ...
+
+    def import_from(self, path: Path, cf: Optional[str] = None, source_cf: Optional[str] = None) -> int:
+        """
+        Imports the records of another RocksDB or LevelDB database by SST files which are ingested.
+
+        :param str or os.PathLike path: The path of the source database
+        :param str or None cf: The column family of this database
+        :param str or None source_cf: The column family of the source database
+        :return: The number of the records
+        :rtype: int
+        """
+        ...
+
+    def copy_range(
+        self,
+        dst: Union[RocksDB, str],
+        start: Optional[KeyLike] = None,
+        stop: Optional[KeyLike] = None,
+        cf: Optional[str] = None,
+        dst_cf: Optional[str] = None,
+    ) -> int:
+        """
+        Copies the records of the range into another database or a column family of this database by SST files which are ingested.
+
+        :param RocksDB or str dst: The destination database, or the column family of this database
+        :param bytes-like or None start: The inclusive start key of the range
+        :param bytes-like or None stop: The exclusive stop key of the range
+        :param str or None cf: The column family of this database
+        :param str or None dst_cf: The column family of the destination database
+        :return: The number of the records
+        :rtype: int
+        :raises ValueError: If dst_cf is given with a column family destination
+        """
+        ...
+
+    def create_backup(self, backup_path: Path) -> None:
+        """
+        Creates a consistent backup of the currently opened database at the given path.
+
+        This method flushes memtables and saves a snapshot that can later be restored.
+
+        :param str or os.PathLike backup_path: Path to the directory where the backup will be stored
+        """
+        ...
+
+    @staticmethod
+    def restore_latest_backup(
+        backup_path: Path,
+        restore_path: Path,
+        wal_path: Optional[Path] = None,
+        keep_log_files: bool = False,
+    ) -> None:
+        """
+        Restores the latest backup from a given backup directory into a new RocksDB instance.
+
+        This method must be called before opening the database from the restore path.
+
+        :param str or os.PathLike backup_path: Path to the directory containing backup metadata and files
+        :param str or os.PathLike restore_path: Path where the database will be restored
+        :param str or os.PathLike or None wal_path: Path where the write ahead log will be restored, defaults to restore_path
+        :param bool keep_log_files: Keep the log files of wal_path to recover the writes after the backup
+        """
+        ...
+
+    def stall_info(self) -> Dict[str, Any]:
+        """
+        Returns the current write stall state of the database.
+
+        :return: The stall state with "stopped", "delayed_write_rate" and "stalled" keys
+        :rtype: dict
+        """
+        ...
+
+    def on_write_stall(
+        self,
+        callback: Optional[Callable[[Dict[str, Any]], None]] = None,
+        interval: Optional[float] = 0.1,
+    ) -> None:
+        """
+        Registers a callback which is called with the stall info when writes start or stop stalling.
+
+        :param callable or None callback: The callback, None removes the active callback
+        :param float or None interval: Seconds between the checks
+        """
+        ...
+
+    def auto_flush(self, interval: Optional[float] = None, max_bytes: Optional[int] = None, wal: Optional[bool] = None) -> None:
+        """
+        Flushes the memtables of all column families on a background thread every interval seconds, or when the memtables have max_bytes of writes. Calling it without limits stops the background flush.
+
+        :param float or None interval: Seconds between the flushes
+        :param int or None max_bytes: The size of the memtables which triggers a flush
+        :param bool or None wal: Sync the write ahead log as well
+        :raises ValueError: If the interval is not positive
+        """
+        ...
+
+    def clone_handle(self) -> RocksDB:
+        """
+        Returns a new handle of the same database for another thread. The handles share the database, the write lock and the settings.
+
+        :return: The database handle
+        :rtype: rocksdbpy.RocksDB
+        """
+        ...
+
+    def close(self, flush: bool = False, wait: bool = False) -> None:
+        """
+        Close active database, the database stays open until every handle of it is closed.
+
+        :param bool flush: Flush the memtables and the write ahead log first
+        :param bool wait: Wait for the running and the pending compactions first
+        """
+        ...
+
+
+class Option:
+    def create_if_missing(self, create_if_missing: bool) -> None:
+        """
+        If true, the database will be created if it is missing.
+
+        :param bool create_if_missing: Create or not the database
+        """
+        ...
+
+    def set_key_ttl(self, enabled: bool) -> None:
+        """
+        If true, records can be set with a TTL. It must be enabled when the database is created.
+
+        :param bool enabled: Enable or not the per-key TTL
+        """
+        ...
+
+    def enable_user_timestamp(self) -> None:
+        """
+        Keys carry a 64-bit user timestamp. It must be enabled when the database is created and on every open.
+        """
+        ...
+
+    def set_reverse_comparator(self) -> None:
+        """
+        Orders the keys bytewise from the largest to the smallest. It must be set on every open.
+        """
+        ...
+
+    def set_comparator(self, name: str, compare: Callable[[bytes, bytes], int]) -> None:
+        """
+        Orders the keys by a Python function which returns a negative number, zero or a positive number.
+
+        :param str name: The name of the comparator, the same name must be set on every open
+        :param callable compare: The comparison function, exceptions are printed and the keys are compared bytewise
+        """
+        ...
+
+    def set_composite_comparator(self, name: str, components: List[str]) -> None:
+        """
+        Orders the keys by their components without calling Python. A key which is too short for an integer is ordered before the keys which have it.
+
+        :param str name: The name of the comparator, the same name must be set on every open
+        :param list components: "u16be", "u16le", "u32be", "u32le", "u64be" or "u64le" integers, the last one may be "bytes" or "ascii_ci"
+        """
+        ...
+
+    def set_enable_blob_files(self, val: bool) -> None:
+        """
+        Enables the key-value separation, large values are stored in blob files.
+        """
+        ...
+
+    def set_min_blob_size(self, val: int) -> None:
+        """
+        Sets the size of the smallest value which is stored in a blob file.
+        """
+        ...
+
+    def set_blob_file_size(self, val: int) -> None:
+        """
+        Sets the size limit of the blob files.
+        """
+        ...
+
+    def set_wal_recovery_mode(self, mode: str) -> None:
+        """
+        Sets how the corrupted records of the WAL are handled on recovery.
+
+        :param str mode: "tolerate_corrupted_tail_records", "absolute_consistency", "point_in_time" or "skip_any_corrupted_records"
+        :raises ValueError: If the mode is not supported
+        """
+        ...
+
+    def set_blob_compression_type(self, compression: str) -> None:
+        """
+        Sets the compression of the blob files.
+
+        :param str compression: "none", "snappy", "zlib", "bz2", "lz4", "lz4hc" or "zstd"
+        :raises ValueError: If the compression is not supported
+        """
+        ...
+
+    def set_enable_blob_gc(self, val: bool) -> None:
+        """
+        Enables the garbage collection of the blob files.
+        """
+        ...
+
+    def set_blob_gc_age_cutoff(self, val: float) -> None:
+        """
+        Sets the fraction of the oldest blob files which are collected by the garbage collection.
+        """
+        ...
+
+    def set_blob_gc_force_threshold(self, val: float) -> None:
+        """
+        Sets the ratio of the garbage in the oldest blob files which forces a compaction.
+        """
+        ...
+
+    def set_blob_compaction_readahead_size(self, val: int) -> None:
+        """
+        Sets the readahead size of the blob files which are read by the compactions.
+        """
+        ...
+
+    def set_periodic_compaction_seconds(self, secs: int) -> None:
+        """
+        Sets the age in seconds after which the SST files are compacted again, 0 disables the periodic compactions.
+        """
+        ...
+
+    def set_ttl(self, secs: int) -> None:
+        """
+        Sets the compaction TTL in seconds, the SST files with older data are compacted to the next level. It is not related to the key TTL.
+        """
+        ...
+
+    def add_compact_on_deletion_collector_factory(self, window_size: int, num_dels_trigger: int, deletion_ratio: float) -> None:
+        """
+        Marks the SST files with many deletions for compaction, by the deletions in a sliding window of entries or by the ratio of the deletions in the file.
+
+        :param int window_size: The number of the consecutive entries of the window
+        :param int num_dels_trigger: The number of the deletions in the window which marks the file
+        :param float deletion_ratio: The ratio of the deletions in the file which marks the file, 0 disables it
+        """
+        ...
+
+
+class WriteBatch:
+    @staticmethod
+    def from_data(data: ByteString) -> WriteBatch:
+        """
+        Creates a batch from the serialized batch of data().
+
+        :param bytes-like data: The serialized batch
+        :rtype: rocksdbpy.WriteBatch
+        """
+        ...
+
+    def __enter__(self) -> WriteBatch:
+        ...
+
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool:
+        """
+        Writes the batch to the database unless the block raised an exception.
+        """
+        ...
+
+    def add(self, key: KeyLike, value: Any, ttl: Optional[int] = None, cf: Optional[str] = None) -> None:
+        """
+        Append new "key" and "value" in the batch. The value is encoded by the codec of the database
+        if the batch is created by db.write_batch().
+
+        :param bytes key: The entry key
+        :param value: The entry value
+        :param int ttl: Seconds until the record expires, requires a batch of db.write_batch()
+        :param str cf: The column family name, requires a batch of db.write_batch()
+        """
+        ...
+
+    def delete(self, key: KeyLike, cf: Optional[str] = None) -> None:
+        """
+        Remove "key" from the batch.
+
+        :param bytes key: The entry key
+        :param str cf: The column family name, requires a batch of db.write_batch()
+        """
+        ...
+
+    def delete_range(self, start: KeyLike, end: KeyLike, cf: Optional[str] = None) -> None:
+        """
+        Removes the keys in the range from "start" to "end", "end" is excluded.
+
+        :param bytes start: The first key of the range
+        :param bytes end: The key after the range
+        :param str cf: The column family name, requires a batch of db.write_batch()
+        """
+        ...
+
+    def merge(self, key: KeyLike, value: bytes, cf: Optional[str] = None) -> None:
+        """
+        Merges the "value" into the value of the "key", the value is appended like db.append().
+
+        :param bytes key: The entry key
+        :param bytes value: The appended value
+        :param str cf: The column family name, requires a batch of db.write_batch()
+        """
+        ...
+
+    def len(self) -> int:
+        """
+        Returns element count of the batch.
+
+        :return: The number of entries in the batch
+        :rtype: int
+        """
+        ...
+
+    def __len__(self) -> int:
+        """
+        Returns element count of the batch.
+        """
+        ...
+
+    def size_in_bytes(self) -> int:
+        """
+        Returns the size of the serialized batch in bytes.
+
+        :rtype: int
+        """
+        ...
+
+    def set_savepoint(self) -> None:
+        """
+        Records the current state of the batch, rollback_to_savepoint() discards the records which are added after it.
+        """
+        ...
+
+    def rollback_to_savepoint(self) -> None:
+        """
+        Discards the records which are added after the most recent save point and removes the save point.
+        """
+        ...
+
+    def data(self) -> bytes:
+        """
+        Returns the serialized batch, WriteBatch.from_data() creates the same batch from it.
+
+        :rtype: bytes
+        """
+        ...
+
+    def entries(self) -> List[Tuple[str, int, bytes, Optional[bytes]]]:
+        """
+        Returns the records of the batch. The operation is one of 'put', 'delete', 'single_delete', 'delete_range', 'merge' and 'put_entity', the column family is its id which is 0 for the default column family. The values are returned as stored.
+
+        :return: The (operation, column family, key, value) records
+        :rtype: list[(str, int, bytes, bytes or None)]
+        """
+        ...
+
+    def __iter__(self) -> Iterator[Tuple[str, int, bytes, Optional[bytes]]]:
+        """
+        Returns an iterator over the records of the batch.
+        """
+        ...
+
+    def clear(self) -> None:
+        """
+        Clear the batch. A batch which is written by db.write() can be reused after it is cleared.
+        """
+        ...
+
+
+class WriteBatchWithIndex:
+    def add(self, key: bytes, value: bytes) -> None:
+        """
+        Append new "key" and "value" in the batch, it replaces the pending write of the key.
+
+        :param bytes key: The entry key
+        :param bytes value: The entry value
+        """
+        ...
+
+    def delete(self, key: bytes) -> None:
+        """
+        Remove "key" from the batch, it replaces the pending write of the key.
+
+        :param bytes key: The entry key
+        """
+        ...
+
+    def get_from_batch(self, key: bytes) -> Optional[bytes]:
+        """
+        Returns the pending value of the "key" in the batch.
+
+        :param bytes key: The entry key
+        :return: The pending value, None if the key is not written or deleted by the batch
+        :rtype: bytes or None
+        """
+        ...
+
+    def get_from_batch_and_db(self, db: RocksDB, key: bytes) -> Optional[bytes]:
+        """
+        Returns the value of the "key" with the pending writes of the batch applied on the database.
+
+        :param rocksdbpy.RocksDB db: The database
+        :param bytes key: The entry key
+        :rtype: bytes or None
+        """
+        ...
+
+    def iterator(self, db: RocksDB, reverse: bool = False) -> BatchIterator:
+        """
+        Returns an iterator over the entries of the database with the pending writes of the batch applied.
+
+        :param rocksdbpy.RocksDB db: The database
+        :param bool reverse: Iterate from the last key
+        :rtype: rocksdbpy.BatchIterator
+        """
+        ...
+
+    def __len__(self) -> int:
+        """
+        Returns the number of keys written by the batch.
+        """
+        ...
+
+    def clear(self) -> None:
+        """
+        Clear the batch.
+        """
+        ...
+
+
+class BatchIterator:
+    def __iter__(self) -> BatchIterator:
+        ...
+
+    def __next__(self) -> Tuple[bytes, bytes]:
+        """
+        Returns next entry.
+
+        :rtype: (bytes, bytes)
+        """
+        ...
+
+
+class EntityIterator:
+    def __iter__(self) -> EntityIterator: ...
+
+    def __next__(self) -> Tuple[bytes, Dict[bytes, bytes]]: ...
+
+
+class ParallelScan:
+    def __iter__(self) -> ParallelScan: ...
+
+    def __next__(self) -> Tuple[bytes, Any]:
+        """
+        Returns next entry, it waits for the workers without holding the GIL.
+
+        :rtype: (bytes, Any)
+        """
+        ...
+
+    def close(self) -> None:
+        """
+        Stops the workers, the remaining entries are discarded.
+        """
+        ...
+
+
+class Compaction:
+    canceled: bool
+
+    def done(self) -> bool:
+        """
+        Returns true if the compaction is completed or canceled.
+        """
+        ...
+
+    def wait(self, timeout: Optional[float] = None) -> bool:
+        """
+        Waits until the compaction is completed or canceled.
+
+        :param float or None timeout: The maximum number of seconds to wait
+        :rtype: bool
+        """
+        ...
+
+    def cancel(self) -> None:
+        """
+        Cancels the compaction after the running chunk.
+        """
+        ...
+
+    def progress(self) -> Tuple[int, int]:
+        """
+        Returns the number of the compacted chunks and the number of all chunks.
+
+        :rtype: (int, int)
+        """
+        ...
+
+
+class AsyncDBIterator:
+    def __aiter__(self) -> AsyncDBIterator:
+        ...
+
+    async def __anext__(self) -> Tuple[bytes, bytes]:
+        """
+        Returns next database entry, the next batch is read while the current batch is consumed.
+
+        :rtype: (bytes, bytes)
+        """
+        ...
+
+
+class DBIterator:
+    def __next__(self) -> Tuple[bytes, Any]:
+        """
+        Returns next database entry, the value is decoded by the codec of the database.
+
+        :return: The database entry
+        :rtype: (bytes, Any)
+        """
+
+    def next_batch(self, n: int) -> List[Tuple[bytes, Any]]:
+        """
+        Returns up to "n" next database entries, the entries are read without holding the GIL.
+
+        :param int n: The maximum number of entries
+        :return: The database entries, empty if the iterator is exhausted
+        :rtype: list[(bytes, bytes)]
+        """
+
+    def len(self) -> int:
+        """
+        Returns element count of the iterator.
+
+        :return: The element count of the iterator
+        :rtype: int
+        """
+
+    def refresh(self) -> None:
+        """
+        Brings the iterator up to date with the recent writes by keeping its position.
+        """
+
+    def close(self) -> None:
+        """
+        Close and destroy active iterator
+        """
+
+
+class Cursor:
+    def seek(self, key: KeyLike) -> None:
+        """
+        Moves the cursor to the first key at or after the given key.
+
+        :param bytes key: The target key
+        """
+        ...
+
+    def seek_for_prev(self, key: KeyLike) -> None:
+        """
+        Moves the cursor to the last key at or before the given key.
+
+        :param bytes key: The target key
+        """
+        ...
+
+    def seek_to_first(self) -> None:
+        """
+        Moves the cursor to the first key of the database.
+        """
+        ...
+
+    def seek_to_last(self) -> None:
+        """
+        Moves the cursor to the last key of the database.
+        """
+        ...
+
+    def next(self) -> None:
+        """
+        Moves the cursor to the next key.
+        """
+        ...
+
+    def prev(self) -> None:
+        """
+        Moves the cursor to the previous key.
+        """
+        ...
+
+    def valid(self) -> bool:
+        """
+        Returns true if the cursor is positioned at an entry.
+
+        :rtype: bool
+        """
+        ...
+
+    def key(self) -> Optional[Union[bytes, str]]:
+        """
+        Returns the key of the current entry.
+
+        :rtype: bytes, str or None
+        """
+        ...
+
+    def value(self) -> Optional[Any]:
+        """
+        Returns the value of the current entry, decoded by the codec of the database.
+
+        :rtype: Any or None
+        """
+        ...
+
+    def close(self) -> None:
+        """
+        Close and destroy active cursor
+        """
+        ...
+
+
+class Namespace:
+    @property
+    def prefix(self) -> bytes:
+        """
+        The prefix of the namespace.
+        """
+        ...
+
+    def get(self, key: KeyLike) -> Optional[Any]:
+        """
+        Return the value associated with a "key" in the namespace.
+
+        :param bytes-like key: The entry key without the prefix
+        :rtype: bytes, decoded object or None
+        """
+        ...
+
+    def set(self, key: KeyLike, value: Any, ttl: Optional[int] = None) -> None:
+        """
+        Sets records by "key" and "value" in the namespace.
+
+        :param bytes-like key: The entry key without the prefix
+        :param value: The entry value
+        :param int ttl: Seconds until the record expires, requires key TTL enabled by the options
+        """
+        ...
+
+    def delete(self, key: KeyLike) -> None:
+        """
+        Removes existing records by "key" in the namespace.
+
+        :param bytes-like key: The entry key without the prefix
+        """
+        ...
+
+    def __getitem__(self, key: KeyLike) -> Any:
+        ...
+
+    def __setitem__(self, key: KeyLike, value: Any) -> None:
+        ...
+
+    def __delitem__(self, key: KeyLike) -> None:
+        ...
+
+    def __contains__(self, key: KeyLike) -> bool:
+        ...
+
+    def iterator(self, reverse: Optional[bool] = False) -> DBIterator:
+        """
+        Returns an iterator over the (key, value) entries of the namespace, the prefix is removed from the keys.
+
+        :param bool or None reverse: Iterate from the last key
+        :rtype: rocksdbpy.DBIterator
+        """
+        ...
+
+    def items(self, start: Optional[KeyLike] = None, stop: Optional[KeyLike] = None, reverse: Optional[bool] = False) -> DBIterator:
+        """
+        Returns an iterator over the (key, value) entries of the range in the namespace.
+
+        :param bytes or None start: The inclusive start key without the prefix
+        :param bytes or None stop: The exclusive stop key without the prefix
+        :param bool or None reverse: Iterate from the last key before the stop key
+        :rtype: rocksdbpy.DBIterator
+        """
+        ...
+
+    def keys(self, start: Optional[KeyLike] = None, stop: Optional[KeyLike] = None, reverse: Optional[bool] = False) -> DBIterator:
+        """
+        Returns an iterator over the keys of the range in the namespace.
+
+        :rtype: rocksdbpy.DBIterator
+        """
+        ...
+
+    def values(self, start: Optional[KeyLike] = None, stop: Optional[KeyLike] = None, reverse: Optional[bool] = False) -> DBIterator:
+        """
+        Returns an iterator over the values of the range in the namespace.
+
+        :rtype: rocksdbpy.DBIterator
+        """
+        ...
+
+    def namespace(self, prefix: KeyLike) -> Namespace:
+        """
+        Returns a namespace inside the namespace, the prefixes are concatenated.
+
+        :param bytes-like prefix: The key prefix
+        :rtype: rocksdbpy.Namespace
+        """
+        ...
+
+
+class BufferedWriter:
+    def __enter__(self) -> BufferedWriter:
+        ...
+
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool:
+        """
+        Writes the pending writes and closes the writer.
+        """
+        ...
+
+    def set(self, key: KeyLike, value: Any) -> None:
+        """
+        Sets records by "key" and "value" in the buffer.
+
+        :param bytes-like key: The entry key
+        :param value: The entry value, bytes-like unless the database is opened with a codec
+        """
+        ...
+
+    def delete(self, key: KeyLike) -> None:
+        """
+        Removes existing records by "key" in the buffer.
+
+        :param bytes-like key: The entry key
+        """
+        ...
+
+    def flush(self) -> None:
+        """
+        Writes the pending writes to the database.
+        """
+        ...
+
+    def close(self) -> None:
+        """
+        Writes the pending writes and stops the interval flushes.
+        """
+        ...
+
+    def __len__(self) -> int:
+        """
+        Returns the number of pending writes.
+        """
+        ...
+
+
+class BackupEngine:
+    """
+    Manages the backups of a backup directory.
+    """
+
+    def __init__(self, backup_path: Path) -> None:
+        """
+        Opens the backup engine of the directory, the directory is created if it is missing.
+
+        :param str or os.PathLike backup_path: The backup directory
+        """
+        ...
+
+    @property
+    def path(self) -> str:
+        """
+        The path of the backup directory.
+        """
+        ...
+
+    def create_backup(
+        self,
+        db: RocksDB,
+        flush_before_backup: bool = True,
+        progress: Optional[Callable[[int, int], None]] = None,
+    ) -> int:
+        """
+        Creates a backup of the database, only the table files which changed since the previous backup are copied.
+
+        :param rocksdbpy.RocksDB db: The database
+        :param bool flush_before_backup: Flush the memtables before the backup, otherwise the write ahead log is backed up
+        :param progress: Called with the estimated bytes copied and total bytes while the backup runs
+        :return: The id of the backup
+        :rtype: int
+        """
+        ...
+
+    def get_backup_info(self) -> List[Dict[str, int]]:
+        """
+        Returns the backups from the oldest to the newest.
+
+        :return: The backups with "backup_id", "timestamp", "size" and "num_files" keys
+        :rtype: list
+        """
+        ...
+
+    def verify_backup(self, backup_id: int) -> None:
+        """
+        Checks that the files of the backup exist and have the sizes of its metadata.
+
+        :param int backup_id: The id of the backup
+        :raises RocksDBException: If the backup is missing or corrupted
+        """
+        ...
+
+    def purge_old_backups(self, num_to_keep: int) -> None:
+        """
+        Deletes the oldest backups and keeps the newest ones.
+
+        :param int num_to_keep: The number of the newest backups to keep
+        """
+        ...
+
+    def restore_from_backup(
+        self,
+        backup_id: int,
+        db_dir: Path,
+        wal_dir: Optional[Path] = None,
+        keep_log_files: bool = False,
+    ) -> None:
+        """
+        Restores the backup, the database must not be open.
+
+        :param int backup_id: The id of the backup
+        :param str or os.PathLike db_dir: The database directory
+        :param str or os.PathLike or None wal_dir: The write ahead log directory, defaults to db_dir
+        :param bool keep_log_files: Keep the log files of wal_dir to recover the writes after the backup
+        """
+        ...
+
+    def restore_from_latest_backup(
+        self,
+        db_dir: Path,
+        wal_dir: Optional[Path] = None,
+        keep_log_files: bool = False,
+    ) -> None:
+        """
+        Restores the newest backup, the database must not be open.
+
+        :param str or os.PathLike db_dir: The database directory
+        :param str or os.PathLike or None wal_dir: The write ahead log directory, defaults to db_dir
+        :param bool keep_log_files: Keep the log files of wal_dir to recover the writes after the backup
+        """
+        ...
+
+    def close(self) -> None:
+        """
+        Closes the backup engine.
+        """
+        ...
+
+    def __enter__(self) -> BackupEngine: ...
+
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> None: ...
+
+
+class SstFileWriter:
+    """
+    Writes sorted entries into an SST file outside of a database.
+    """
+
+    def __init__(self, option: Optional[Option] = None) -> None:
+        """
+        Creates a writer with the options of the database which ingests the files.
+
+        :param rocksdbpy.Option or None option: The options of the database
+        """
+        ...
+
+    def open(self, path: Path) -> None:
+        """
+        Creates the SST file at the path, the file is overwritten if it exists.
+
+        :param str or os.PathLike path: The path of the file
+        """
+        ...
+
+    def put(self, key: ByteString, value: ByteString, ttl: Optional[int] = None) -> None:
+        """
+        Adds the key with the value, the keys must be added in ascending order.
+
+        :param bytes-like key: The key
+        :param bytes-like value: The value
+        :param int or None ttl: The TTL of the key in seconds if key TTL is enabled by the options
+        """
+        ...
+
+    def merge(self, key: ByteString, value: ByteString) -> None:
+        """
+        Adds a merge of the value into the value of the key, the value is appended.
+
+        :param bytes-like key: The key
+        :param bytes-like value: The appended value
+        """
+        ...
+
+    def delete(self, key: ByteString) -> None:
+        """
+        Adds a deletion of the key.
+
+        :param bytes-like key: The key
+        """
+        ...
+
+    def finish(self) -> None:
+        """
+        Completes the SST file, the writer can open another file afterwards.
+        """
+        ...
+
+    @property
+    def file_size(self) -> int:
+        """
+        The size of the open file in bytes.
+        """
+        ...
+
+    def __enter__(self) -> SstFileWriter: ...
+
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+
+
+class SstFileReader:
+    """
+    Reads a standalone SST file, the file is copied into a scratch database which is removed by close.
+    """
+
+    def __init__(self, path: Path, option: Optional[Option] = None) -> None:
+        """
+        Opens the SST file with the options of the database which wrote it.
+
+        :param str or os.PathLike path: The path of the file
+        :param rocksdbpy.Option or None option: The options of the database
+        """
+        ...
+
+    @property
+    def path(self) -> str:
+        """
+        The path of the SST file.
+        """
+        ...
+
+    def properties(self) -> Dict[str, Any]:
+        """
+        Returns the properties of the file.
+
+        :return: "num_entries", "num_deletions", "size", "smallest_key" and "largest_key"
+        :rtype: dict
+        """
+        ...
+
+    def get(self, key: ByteString) -> Optional[bytes]:
+        """
+        Returns the value of the key in the file.
+
+        :param bytes-like key: The key
+        :rtype: bytes or None
+        """
+        ...
+
+    def items(self, start: Optional[bytes] = None, stop: Optional[bytes] = None, reverse: bool = False) -> DBIterator:
+        """
+        Returns an iterator over the (key, value) entries of the file in the range.
+
+        :param bytes or None start: The first key, included
+        :param bytes or None stop: The last key, excluded
+        :param bool reverse: Iterate from the last key
+        :rtype: rocksdbpy.DBIterator
+        """
+        ...
+
+    def verify_checksum(self) -> None:
+        """
+        Reads every block of the file and raises an exception if a checksum does not match.
+        """
+        ...
+
+    def close(self) -> None:
+        """
+        Closes the reader and removes its scratch database.
+        """
+        ...
+
+    def __enter__(self) -> SstFileReader: ...
+
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> None: ...
+
+
+class ReadPool:
+    def __init__(
+        self,
+        primary: Path,
+        secondary_root: Path,
+        catch_up_interval: Optional[float] = 1.0,
+        option: Optional[Option] = None,
+        codec: Optional[Codec] = None,
+        text: bool = False,
+    ) -> None:
+        """
+        Opens a secondary instance of the primary database in every process which reads from it.
+
+        :param primary: The path of the primary database
+        :param secondary_root: The directory of the secondary instances, every process uses a directory named by its pid
+        :param float or None catch_up_interval: Seconds between the catch-ups with the primary
+        :param option: The database options
+        :param codec: The value codec
+        :param bool text: Accept str keys
+        """
+        ...
+
+    @property
+    def path(self) -> Path:
+        """
+        The directory of the secondary instance of the current process.
+        """
+        ...
+
+    def get(self) -> RocksDB:
+        """
+        Returns the secondary instance of the current process, it is opened by the first call in the process.
+
+        :rtype: rocksdbpy.RocksDB
+        """
+        ...
+
+    def close(self) -> None:
+        """
+        Closes the secondary instance of the current process and removes its directory.
+        """
+        ...
+
+
+class Queue:
+    @property
+    def name(self) -> str:
+        """
+        The name of the column family of the queue.
+        """
+        ...
+
+    def push(self, value: Any) -> None:
+        """
+        Appends the "value" to the back of the queue.
+
+        :param value: The entry value, bytes-like unless the database is opened with a codec
+        """
+        ...
+
+    def push_front(self, value: Any) -> None:
+        """
+        Prepends the "value" to the front of the queue.
+
+        :param value: The entry value, bytes-like unless the database is opened with a codec
+        """
+        ...
+
+    def pop_front(self) -> Any:
+        """
+        Removes and returns the value at the front of the queue. Concurrent pops never return the same entry.
+
+        :rtype: bytes, decoded object or None
+        """
+        ...
+
+    def pop_back(self) -> Any:
+        """
+        Removes and returns the value at the back of the queue.
+
+        :rtype: bytes, decoded object or None
+        """
+        ...
+
+    def peek(self) -> Any:
+        """
+        Returns the value at the front of the queue without removing it.
+
+        :rtype: bytes, decoded object or None
+        """
+        ...
+
+    def __len__(self) -> int:
+        """
+        Returns the number of entries of the queue, the column family is scanned.
+        """
+        ...
+
+
+class Key:
+    @staticmethod
+    def u64_be(value: int) -> bytes:
+        """
+        Encodes a unsigned 64-bit integer as 8 big-endian bytes.
+
+        :param int value: The integer
+        :rtype: bytes
+        """
+        ...
+
+    @staticmethod
+    def decode_u64_be(data: ByteString, offset: Optional[int] = None) -> int:
+        """
+        Decodes a unsigned 64-bit integer encoded by `u64_be`.
+
+        :param bytes-like data: The key
+        :param int or None offset: The position of the integer in the key
+        :rtype: int
+        :raises ValueError: If the key is too short
+        """
+        ...
+
+    @staticmethod
+    def i64_be(value: int) -> bytes:
+        """
+        Encodes a signed 64-bit integer as 8 order-preserving big-endian bytes.
+
+        :param int value: The integer
+        :rtype: bytes
+        """
+        ...
+
+    @staticmethod
+    def decode_i64_be(data: ByteString, offset: Optional[int] = None) -> int:
+        """
+        Decodes a signed 64-bit integer encoded by `i64_be`.
+
+        :param bytes-like data: The key
+        :param int or None offset: The position of the integer in the key
+        :rtype: int
+        :raises ValueError: If the key is too short
+        """
+        ...
+
+    @staticmethod
+    def u32_be(value: int) -> bytes:
+        """
+        Encodes a unsigned 32-bit integer as 4 big-endian bytes.
+
+        :param int value: The integer
+        :rtype: bytes
+        """
+        ...
+
+    @staticmethod
+    def decode_u32_be(data: ByteString, offset: Optional[int] = None) -> int:
+        """
+        Decodes a unsigned 32-bit integer encoded by `u32_be`.
+
+        :param bytes-like data: The key
+        :param int or None offset: The position of the integer in the key
+        :rtype: int
+        :raises ValueError: If the key is too short
+        """
+        ...
+
+    @staticmethod
+    def i32_be(value: int) -> bytes:
+        """
+        Encodes a signed 32-bit integer as 4 order-preserving big-endian bytes.
+
+        :param int value: The integer
+        :rtype: bytes
+        """
+        ...
+
+    @staticmethod
+    def decode_i32_be(data: ByteString, offset: Optional[int] = None) -> int:
+        """
+        Decodes a signed 32-bit integer encoded by `i32_be`.
+
+        :param bytes-like data: The key
+        :param int or None offset: The position of the integer in the key
+        :rtype: int
+        :raises ValueError: If the key is too short
+        """
+        ...
+
+
+class RocksDBException(Exception):
+    ...
+
+
+class DBClosedError(RocksDBException):
+    ...
+
+
+class DBForkedError(RocksDBException):
+    ...
+
+
+class DBLockedError(RocksDBException):
+    lock_path: str
+    held_by_current_process: bool
+
+
+class DBNotFoundError(RocksDBException):
+    code: int
+    subcode: int
+
+
+class DBCorruptionError(RocksDBException):
+    code: int
+    subcode: int
+
+
+class DBIOError(RocksDBException):
+    code: int
+    subcode: int
+
+
+class DBBusyError(RocksDBException):
+    code: int
+    subcode: int
+
+
+class DBTryAgainError(DBBusyError):
+    ...
+
+
+class DBTimedOutError(RocksDBException):
+    code: int
+    subcode: int
+
+
+class DBInvalidArgumentError(RocksDBException):
+    code: int
+    subcode: int
@@ -0,0 +1,211 @@
+"""
+Administration commands for a database, e.g. `python -m rocksdbpy /tmp/rocksdb scan`.
+"""
+
+import argparse
+import binascii
+import contextlib
+import sys
+
+import rocksdbpy
+
+
+def _parse_bytes(args, value):
+    if value is None:
+        return None
+
+    if args.hex:
+        return binascii.unhexlify(value)
+
+    return value.encode()
+
+
+def _format_bytes(args, value):
+    if args.hex:
+        return value.hex()
+
+    return value.decode(errors='backslashreplace')
+
+
+def _open(args, writable=False):
+    opts = rocksdbpy.Option()
+
+    if writable and args.create_if_missing:
+        opts.create_if_missing(True)
+
+    if writable:
+        db = rocksdbpy.open(args.path, opts)
+    else:
+        db = rocksdbpy.open_for_readonly(args.path, opts)
+
+    return contextlib.closing(db)
+
+
+def scan(args):
+    with_values = not args.keys_only
+
+    with _open(args) as db:
+        iterator = db.iterator(
+            lower_bound=_parse_bytes(args, args.start),
+            upper_bound=_parse_bytes(args, args.stop),
+            values=with_values,
+        )
+
+        for n, entry in enumerate(iterator):
+            if args.limit is not None and n >= args.limit:
+                break
+
+            if with_values:
+                key, value = entry
+
+                print('{} ==> {}'.format(_format_bytes(args, key), _format_bytes(args, value)))
+            else:
+                print(_format_bytes(args, entry))
+
+    return 0
+
+
+def get(args):
+    with _open(args) as db:
+        value = db.get(_parse_bytes(args, args.key), cf=args.cf)
+
+    if value is None:
+        print('Key not found', file=sys.stderr)
+
+        return 1
+
+    print(_format_bytes(args, value))
+
+    return 0
+
+
+def put(args):
+    with _open(args, writable=True) as db:
+        db.set(_parse_bytes(args, args.key), _parse_bytes(args, args.value), cf=args.cf)
+
+    return 0
+
+
+def delete(args):
+    with _open(args, writable=True) as db:
+        db.delete(_parse_bytes(args, args.key), cf=args.cf)
+
+    return 0
+
+
+def list_cf(args):
+    for name in rocksdbpy.list_column_families(args.path):
+        print(name)
+
+    return 0
+
+
+def properties(args):
+    names = args.names or ['rocksdb.stats']
+
+    with _open(args) as db:
+        for name in names:
+            value = db.property(name, cf=args.cf)
+
+            print('{}: {}'.format(name, 'not supported' if value is None else value))
+
+    return 0
+
+
+def compact(args):
+    with _open(args, writable=True) as db:
+        db.compact_range(_parse_bytes(args, args.start), _parse_bytes(args, args.stop), cf=args.cf)
+
+    return 0
+
+
+def backup(args):
+    with _open(args, writable=True) as db, rocksdbpy.BackupEngine(args.backup_path) as engine:
+        backup_id = engine.create_backup(db)
+
+    print(backup_id)
+
+    return 0
+
+
+def restore(args):
+    with rocksdbpy.BackupEngine(args.backup_path) as engine:
+        if args.backup_id is None:
+            engine.restore_from_latest_backup(args.path)
+        else:
+            engine.restore_from_backup(args.backup_id, args.path)
+
+    return 0
+
+
+def parser():
+    parser = argparse.ArgumentParser(prog='python -m rocksdbpy', description=__doc__.strip())
+    parser.add_argument('path', help='the database path')
+    parser.add_argument('--hex', action='store_true', help='keys and values are hex encoded')
+
+    commands = parser.add_subparsers(dest='command', metavar='command')
+    commands.required = True
+
+    command = commands.add_parser('scan', help='print the records')
+    command.add_argument('--start', help='the first key')
+    command.add_argument('--stop', help='the key after the last key')
+    command.add_argument('--limit', type=int, help='the maximum number of records')
+    command.add_argument('--keys-only', action='store_true', help='print only the keys')
+    command.set_defaults(func=scan)
+
+    command = commands.add_parser('get', help='print the value of a key')
+    command.add_argument('key')
+    command.add_argument('--cf', help='the column family')
+    command.set_defaults(func=get)
+
+    command = commands.add_parser('put', help='set the value of a key')
+    command.add_argument('key')
+    command.add_argument('value')
+    command.add_argument('--cf', help='the column family')
+    command.add_argument('--create-if-missing', action='store_true', help='create the database')
+    command.set_defaults(func=put)
+
+    command = commands.add_parser('delete', help='remove a key')
+    command.add_argument('key')
+    command.add_argument('--cf', help='the column family')
+    command.set_defaults(func=delete, create_if_missing=False)
+
+    command = commands.add_parser('list_cf', help='print the column families')
+    command.set_defaults(func=list_cf)
+
+    command = commands.add_parser('properties', help='print the RocksDB properties')
+    command.add_argument('names', nargs='*', help='the property names, rocksdb.stats by default')
+    command.add_argument('--cf', help='the column family')
+    command.set_defaults(func=properties)
+
+    command = commands.add_parser('compact', help='compact the keys of a range')
+    command.add_argument('--start', help='the first key')
+    command.add_argument('--stop', help='the key after the last key')
+    command.add_argument('--cf', help='the column family')
+    command.set_defaults(func=compact, create_if_missing=False)
+
+    command = commands.add_parser('backup', help='back up the database')
+    command.add_argument('backup_path', help='the backup directory')
+    command.set_defaults(func=backup, create_if_missing=False)
+
+    command = commands.add_parser('restore', help='restore the database from a backup')
+    command.add_argument('backup_path', help='the backup directory')
+    command.add_argument('--backup-id', type=int, help='the backup, the latest by default')
+    command.set_defaults(func=restore)
+
+    return parser
+
+
+def main(argv=None):
+    args = parser().parse_args(argv)
+
+    try:
+        return args.func(args)
+    except rocksdbpy.RocksDBException as e:
+        print(e, file=sys.stderr)
+
+        return 1
+
+
+if __name__ == '__main__':
+    sys.exit(main())
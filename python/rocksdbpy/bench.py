@@ -0,0 +1,238 @@
+"""
+Benchmarks of the bindings like db_bench of RocksDB, e.g. `python -m rocksdbpy.bench /tmp/bench`.
+The workloads run through the Python API, so the results include the cost of the bindings.
+"""
+
+import argparse
+import os
+import random
+import threading
+import time
+
+import rocksdbpy
+
+BENCHMARKS = ('fillseq', 'fillrandom', 'readrandom', 'readseq')
+
+PERCENTILES = (50, 95, 99, 99.9)
+
+
+def _key(i, key_size):
+    return b'%0*d' % (key_size, i)
+
+
+def _fill(db, indexes, key_size, value_size, latencies):
+    value = os.urandom(value_size)
+
+    for i in indexes:
+        key = _key(i, key_size)
+
+        start = time.perf_counter_ns()
+        db.set(key, value)
+        latencies.append(time.perf_counter_ns() - start)
+
+    return len(indexes)
+
+
+def _read_random(db, indexes, key_size, value_size, latencies):
+    found = 0
+
+    for i in indexes:
+        key = _key(i, key_size)
+
+        start = time.perf_counter_ns()
+        value = db.get(key)
+        latencies.append(time.perf_counter_ns() - start)
+
+        if value is not None:
+            found += 1
+
+    return found
+
+
+def _read_seq(db, indexes, key_size, value_size, latencies):
+    iterator = iter(db.iterator(lower_bound=_key(indexes[0], key_size)) if indexes else ())
+    n = 0
+
+    for _ in indexes:
+        start = time.perf_counter_ns()
+        entry = next(iterator, None)
+        latencies.append(time.perf_counter_ns() - start)
+
+        if entry is None:
+            break
+
+        n += 1
+
+    return n
+
+
+def _workload(name, num, threads, seed):
+    """
+    Returns the workload function and the key indexes of every thread.
+    """
+    indexes = list(range(num))
+
+    if name in ('fillrandom', 'readrandom'):
+        rand = random.Random(seed)
+
+        indexes = [rand.randrange(num) for _ in range(num)]
+
+    func = {
+        'fillseq': _fill,
+        'fillrandom': _fill,
+        'readrandom': _read_random,
+        'readseq': _read_seq,
+    }[name]
+
+    # sequential workloads split the key space into contiguous ranges
+    size = (num + threads - 1) // threads
+
+    return func, [indexes[i * size:(i + 1) * size] for i in range(threads)]
+
+
+def _percentile(latencies, percentile):
+    if not latencies:
+        return 0.0
+
+    index = min(len(latencies) - 1, int(len(latencies) * percentile / 100))
+
+    return latencies[index] / 1000
+
+
+def run_benchmark(db, name, num=100000, key_size=16, value_size=100, threads=1, seed=0):
+    """
+    Runs a workload on the database and returns its result.
+
+    :param rocksdbpy.RocksDB db: The database
+    :param str name: The workload, "fillseq", "fillrandom", "readrandom" or "readseq"
+    :param int num: The number of the operations
+    :param int key_size: The size of the keys
+    :param int value_size: The size of the values
+    :param int threads: The number of the threads which share the operations
+    :param int seed: The seed of the random keys
+    :return: The result with "name", "ops", "found", "seconds", "ops_per_sec" and the "p50", "p95", "p99" and "p99.9" latencies in microseconds
+    :rtype: dict
+    """
+    if name not in BENCHMARKS:
+        raise ValueError('Benchmark is not supported, {}'.format(name))
+
+    func, chunks = _workload(name, num, threads, seed)
+    latencies = [[] for _ in chunks]
+    found = [0] * len(chunks)
+    handles = [db.clone_handle() for _ in chunks]
+
+    def worker(i):
+        found[i] = func(handles[i], chunks[i], key_size, value_size, latencies[i])
+
+    workers = [threading.Thread(target=worker, args=(i,)) for i in range(len(chunks))]
+
+    start = time.perf_counter()
+
+    for thread in workers:
+        thread.start()
+
+    for thread in workers:
+        thread.join()
+
+    seconds = time.perf_counter() - start
+
+    for handle in handles:
+        handle.close()
+
+    merged = sorted(latency for chunk in latencies for latency in chunk)
+
+    result = {
+        'name': name,
+        'ops': len(merged),
+        'found': sum(found),
+        'seconds': seconds,
+        'ops_per_sec': len(merged) / seconds if seconds else 0.0,
+    }
+
+    for percentile in PERCENTILES:
+        result['p{}'.format(percentile)] = _percentile(merged, percentile)
+
+    return result
+
+
+def run(path, benchmarks=BENCHMARKS, num=100000, key_size=16, value_size=100, threads=1, opts=None):
+    """
+    Runs the workloads in order on a new database at the path, the database is destroyed
+    afterwards.
+
+    :param str or os.PathLike path: The database path, it must not exist
+    :param benchmarks: The workloads
+    :param int num: The number of the operations of every workload
+    :param int key_size: The size of the keys
+    :param int value_size: The size of the values
+    :param int threads: The number of the threads
+    :param rocksdbpy.Option or None opts: The options to compare, create_if_missing and error_if_exists are enabled on them
+    :return: The results of the workloads
+    :rtype: list[dict]
+    """
+    if opts is None:
+        opts = rocksdbpy.Option()
+
+    opts.create_if_missing(True)
+    opts.set_error_if_exists(True)
+
+    db = rocksdbpy.open(path, opts)
+
+    try:
+        return [run_benchmark(db, name, num, key_size, value_size, threads) for name in benchmarks]
+    finally:
+        db.close()
+
+        rocksdbpy.destroy(path)
+
+
+def report(result):
+    """
+    Formats a result like db_bench.
+    """
+    micros = 1e6 / result['ops_per_sec'] if result['ops_per_sec'] else 0.0
+    line = '{:<12}: {:>10.3f} micros/op {:>10.0f} ops/sec; {:>8.3f} seconds'.format(
+        result['name'], micros, result['ops_per_sec'], result['seconds'])
+
+    if result['name'].startswith('read'):
+        line += '; ({} of {} found)'.format(result['found'], result['ops'])
+
+    percentiles = ' '.join('P{}: {:.2f}'.format(p, result['p{}'.format(p)]) for p in PERCENTILES)
+
+    return '{}\n{:<12}  {} micros'.format(line, '', percentiles)
+
+
+def main(argv=None):
+    parser = argparse.ArgumentParser(prog='python -m rocksdbpy.bench', description=__doc__.strip())
+    parser.add_argument('path', help='the path of the benchmark database, it must not exist')
+    parser.add_argument('--benchmarks', default=','.join(BENCHMARKS),
+                        help='the comma separated workloads, {}'.format(','.join(BENCHMARKS)))
+    parser.add_argument('--num', type=int, default=100000, help='the number of the operations')
+    parser.add_argument('--key_size', type=int, default=16, help='the size of the keys')
+    parser.add_argument('--value_size', type=int, default=100, help='the size of the values')
+    parser.add_argument('--threads', type=int, default=1, help='the number of the threads')
+
+    args = parser.parse_args(argv)
+
+    benchmarks = [name for name in args.benchmarks.split(',') if name]
+
+    for name in benchmarks:
+        if name not in BENCHMARKS:
+            parser.error('benchmark is not supported, {}'.format(name))
+
+    print('Keys:       {} bytes each'.format(args.key_size))
+    print('Values:     {} bytes each'.format(args.value_size))
+    print('Entries:    {}'.format(args.num))
+    print('Threads:    {}'.format(args.threads))
+    print('------------------------------------------------')
+
+    results = run(args.path, benchmarks, args.num, args.key_size, args.value_size, args.threads)
+
+    for result in results:
+        print(report(result))
+
+    return 0
+
+
+if __name__ == '__main__':
+    raise SystemExit(main())
@@ -0,0 +1,145 @@
+use crate::iterator::*;
+use pyo3::exceptions::PyStopAsyncIteration;
+use pyo3::prelude::*;
+use pyo3::types::{PyCFunction, PyDict, PyList, PyTuple};
+use std::collections::VecDeque;
+
+/// Async iterator which reads the entries in batches on the default executor of the running
+/// event loop. The next batch is fetched while the current batch is consumed.
+#[pyclass(name = "AsyncDBIterator")]
+pub struct AsyncIteratorPy {
+    iterator: Py<IteratorPy>,
+    batch_size: usize,
+    buffer: VecDeque<PyObject>,
+    prefetch: Option<PyObject>,
+    exhausted: bool,
+}
+
+#[pymethods]
+impl AsyncIteratorPy {
+    fn __aiter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    /// Returns an awaitable of the next database entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// entry = await anext(iterator)
+    /// ```
+    fn __anext__(slf: &PyCell<Self>, py: Python) -> PyResult<Option<PyObject>> {
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let output = event_loop.call_method0("create_future")?;
+        let mut this = slf.borrow_mut();
+
+        if let Some(entry) = this.buffer.pop_front() {
+            output.call_method1("set_result", (entry,))?;
+
+            return Ok(Some(output.into()));
+        }
+
+        let prefetch = match this.prefetch.take() {
+            Some(prefetch) => prefetch,
+            None if this.exhausted => return Ok(None),
+            None => this.fetch(py)?,
+        };
+
+        let iterator: Py<Self> = slf.into();
+        let future: PyObject = output.into();
+
+        let callback = PyCFunction::new_closure(
+            py,
+            None,
+            None,
+            move |args: &PyTuple, _kwargs: Option<&PyDict>| -> PyResult<()> {
+                let py = args.py();
+
+                iterator
+                    .borrow_mut(py)
+                    .receive(py, args.get_item(0)?, future.as_ref(py))
+            },
+        )?;
+
+        prefetch.call_method1(py, "add_done_callback", (callback,))?;
+
+        Ok(Some(output.into()))
+    }
+}
+
+impl AsyncIteratorPy {
+    pub fn new(iterator: Py<IteratorPy>, batch_size: usize) -> AsyncIteratorPy {
+        AsyncIteratorPy {
+            iterator,
+            batch_size,
+            buffer: VecDeque::new(),
+            prefetch: None,
+            exhausted: false,
+        }
+    }
+
+    /// Starts reading the next batch on the executor.
+    fn fetch(&self, py: Python) -> PyResult<PyObject> {
+        let next_batch = self.iterator.as_ref(py).getattr("next_batch")?;
+
+        Ok(run_in_executor(py, next_batch, vec![self.batch_size.into_py(py)])?.into())
+    }
+
+    /// Buffers the fetched batch and completes the awaited entry, the batch is kept if the
+    /// awaiting task is cancelled.
+    fn receive(&mut self, py: Python, fetched: &PyAny, output: &PyAny) -> PyResult<()> {
+        let batch = match fetched.call_method0("result") {
+            Ok(batch) => batch.downcast::<PyList>()?,
+            Err(e) => {
+                self.exhausted = true;
+
+                if !output.call_method0("done")?.is_true()? {
+                    output.call_method1("set_exception", (e.value(py),))?;
+                }
+
+                return Ok(());
+            }
+        };
+
+        // a short batch is the last one
+        if batch.len() < self.batch_size {
+            self.exhausted = true;
+        } else {
+            self.prefetch = Some(self.fetch(py)?);
+        }
+
+        self.buffer.extend(batch.iter().map(|entry| entry.into_py(py)));
+
+        if output.call_method0("done")?.is_true()? {
+            return Ok(());
+        }
+
+        match self.buffer.pop_front() {
+            Some(entry) => output.call_method1("set_result", (entry,))?,
+            None => {
+                let stop = PyStopAsyncIteration::new_err(());
+
+                output.call_method1("set_exception", (stop.value(py),))?
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// Calls the function with the arguments on the default executor of the running event loop
+/// and returns the awaitable result. The methods release the GIL during RocksDB calls, so
+/// the calls of the executor threads run in parallel.
+pub fn run_in_executor<'py>(
+    py: Python<'py>,
+    func: &'py PyAny,
+    args: Vec<PyObject>,
+) -> PyResult<&'py PyAny> {
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+
+    let mut call = vec![py.None(), func.into_py(py)];
+
+    call.extend(args);
+
+    event_loop.call_method1("run_in_executor", PyTuple::new(py, call))
+}
@@ -0,0 +1,569 @@
+use crate::base::*;
+use crate::db::*;
+use crate::lock::*;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
+use rocksdb::{Env, Error};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// Interval of the progress callback of a backup.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Size of the chunks which are read from a stream.
+const STREAM_CHUNK: usize = 1 << 20;
+
+/// Number of the scratch directories of the process, it names them.
+static SCRATCHES: AtomicUsize = AtomicUsize::new(0);
+
+/// Manages the backups of a backup directory. The engine keeps the metadata of the backups,
+/// so the backups of the same directory should be managed by one engine. The table files are
+/// shared by the backups, so a backup only copies the files which changed since the previous
+/// one.
+#[pyclass(name = "BackupEngine")]
+pub struct BackupEnginePy {
+    path: PathBuf,
+    engine: Mutex<Option<BackupEngine>>,
+}
+
+#[pymethods]
+impl BackupEnginePy {
+    /// Opens the backup engine of the directory, the directory is created if it is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// engine = BackupEngine('/tmp/backup')
+    /// ```
+    #[new]
+    fn new(py: Python, backup_path: PathBuf) -> PyResult<Self> {
+        let opened = py.allow_threads(|| {
+            let opts = BackupEngineOptions::new(&backup_path)?;
+            let env = Env::new()?;
+
+            BackupEngine::open(&opts, &env)
+        });
+
+        match opened {
+            Ok(engine) => Ok(BackupEnginePy {
+                path: backup_path,
+                engine: Mutex::new(Some(engine)),
+            }),
+            Err(e) => Err(rocksdb_error(&e, format!("Backup engine cannot be open. {}", e))),
+        }
+    }
+
+    /// The path of the backup directory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// path = engine.path
+    /// ```
+    #[getter]
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Creates a backup of the database and returns its id. The memtables are flushed before
+    /// the backup unless `flush_before_backup` is false, the write ahead log is backed up
+    /// instead then.
+    ///
+    /// The `progress` callback is called with the bytes copied and the total bytes while the
+    /// backup runs and once when it completes. The total is the size of the table files which
+    /// are not in the previous backups, the copied bytes are the growth of the backup
+    /// directory, so both are estimates until the last call. An exception of the callback is
+    /// raised after the backup completes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// backup_id = engine.create_backup(db)
+    ///
+    /// backup_id = engine.create_backup(db, flush_before_backup=False)
+    ///
+    /// def progress(copied, total):
+    ///     print(f'{copied} / {total}')
+    ///
+    /// backup_id = engine.create_backup(db, progress=progress)
+    /// ```
+    #[pyo3(signature = (db, flush_before_backup=None, progress=None))]
+    fn create_backup(
+        &self,
+        py: Python,
+        db: &DBPy,
+        flush_before_backup: Option<bool>,
+        progress: Option<PyObject>,
+    ) -> PyResult<u32> {
+        let db = match db.inner()? {
+            Some(db) => db,
+            None => return Err(DBClosedError::new_err("Backup cannot create, database is closed")),
+        };
+
+        let flush = flush_before_backup.unwrap_or(true);
+        let mut engine = self.engine(py, "Backup cannot create")?;
+        let engine = &mut *engine;
+
+        let backup = || {
+            let engine = engine.as_mut().unwrap();
+
+            engine
+                .create_new_backup_flush(db, flush)
+                .map(|_| engine.get_backup_info().iter().map(|info| info.backup_id).max())
+        };
+
+        let created = match progress {
+            Some(progress) => with_progress(py, db, &self.path, flush, progress, backup)?,
+            None => py.allow_threads(backup),
+        };
+
+        match created {
+            Ok(Some(backup_id)) => Ok(backup_id),
+            Ok(None) => Err(RocksDBPyException::new_err(
+                "Backup cannot create, backup is missing",
+            )),
+            Err(e) => Err(rocksdb_error(&e, format!("Backup cannot create. {}", e))),
+        }
+    }
+
+    /// Returns the backups from the oldest to the newest. Every backup contains `backup_id`,
+    /// `timestamp` which is the creation time in seconds since the epoch, `size` in bytes and
+    /// `num_files`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for info in engine.get_backup_info():
+    ///     print(info['backup_id'], info['size'])
+    /// ```
+    fn get_backup_info<'py>(&self, py: Python<'py>) -> PyResult<&'py PyList> {
+        let engine = self.engine(py, "Backup info cannot get")?;
+        let backups = engine.as_ref().unwrap().get_backup_info();
+
+        let result = PyList::empty(py);
+
+        for backup in backups {
+            let info = PyDict::new(py);
+
+            info.set_item("backup_id", backup.backup_id)?;
+            info.set_item("timestamp", backup.timestamp)?;
+            info.set_item("size", backup.size)?;
+            info.set_item("num_files", backup.num_files)?;
+
+            result.append(info)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Checks that the files of the backup exist and have the sizes of its metadata.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// engine.verify_backup(1)
+    /// ```
+    fn verify_backup(&self, py: Python, backup_id: u32) -> PyResult<()> {
+        let mut engine = self.engine(py, "Backup cannot verify")?;
+        let engine = &mut *engine;
+
+        match py.allow_threads(|| engine.as_mut().unwrap().verify_backup(backup_id)) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("Backup cannot verify. {}", e))),
+        }
+    }
+
+    /// Deletes the oldest backups and keeps the newest `num_to_keep` ones. The table files
+    /// which are only used by the deleted backups are removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// engine.create_backup(db)
+    /// engine.purge_old_backups(5)
+    /// ```
+    fn purge_old_backups(&self, py: Python, num_to_keep: usize) -> PyResult<()> {
+        let mut engine = self.engine(py, "Backups cannot purge")?;
+        let engine = &mut *engine;
+
+        match py.allow_threads(|| engine.as_mut().unwrap().purge_old_backups(num_to_keep)) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("Backups cannot purge. {}", e))),
+        }
+    }
+
+    /// Restores the backup into `db_dir`, the write ahead log is restored into `wal_dir` which
+    /// defaults to `db_dir`. The database must not be open. If `keep_log_files` is true, the
+    /// log files of `wal_dir` are kept, so the writes after the backup which are only in the
+    /// log are recovered by the next open. The backup must be created without a flush then.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// engine.restore_from_backup(1, '/tmp/restore')
+    ///
+    /// db = rocksdbpy.open_default('/tmp/restore')
+    ///
+    /// engine.restore_from_backup(1, '/tmp/rocksdb', '/tmp/rocksdb', keep_log_files=True)
+    /// ```
+    #[pyo3(signature = (backup_id, db_dir, wal_dir=None, keep_log_files=None))]
+    fn restore_from_backup(
+        &self,
+        py: Python,
+        backup_id: u32,
+        db_dir: PathBuf,
+        wal_dir: Option<PathBuf>,
+        keep_log_files: Option<bool>,
+    ) -> PyResult<()> {
+        let wal_dir = wal_dir.unwrap_or_else(|| db_dir.clone());
+        let mut engine = self.engine(py, "Backup cannot restore")?;
+        let engine = &mut *engine;
+
+        let restored = py.allow_threads(|| {
+            let opts = restore_options(keep_log_files);
+
+            engine.as_mut().unwrap().restore_from_backup(&db_dir, &wal_dir, &opts, backup_id)
+        });
+
+        match restored {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("Backup cannot restore. {}", e))),
+        }
+    }
+
+    /// Restores the newest backup into `db_dir`, the write ahead log is restored into
+    /// `wal_dir` which defaults to `db_dir`. The database must not be open. The log files of
+    /// `wal_dir` are kept if `keep_log_files` is true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// engine.restore_from_latest_backup('/tmp/restore')
+    ///
+    /// engine.restore_from_latest_backup('/tmp/restore', keep_log_files=True)
+    /// ```
+    #[pyo3(signature = (db_dir, wal_dir=None, keep_log_files=None))]
+    fn restore_from_latest_backup(
+        &self,
+        py: Python,
+        db_dir: PathBuf,
+        wal_dir: Option<PathBuf>,
+        keep_log_files: Option<bool>,
+    ) -> PyResult<()> {
+        let wal_dir = wal_dir.unwrap_or_else(|| db_dir.clone());
+        let mut engine = self.engine(py, "Backup cannot restore")?;
+        let engine = &mut *engine;
+
+        let restored = py.allow_threads(|| {
+            let opts = restore_options(keep_log_files);
+
+            engine.as_mut().unwrap().restore_from_latest_backup(&db_dir, &wal_dir, &opts)
+        });
+
+        match restored {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("Backup cannot restore. {}", e))),
+        }
+    }
+
+    /// Closes the backup engine.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// engine.close()
+    /// ```
+    fn close(&self, py: Python) {
+        let engine = lock_mutex(py, &self.engine).take();
+
+        py.allow_threads(|| drop(engine));
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python,
+        _exc_type: Option<&PyAny>,
+        _exc_value: Option<&PyAny>,
+        _traceback: Option<&PyAny>,
+    ) {
+        self.close(py)
+    }
+}
+
+impl BackupEnginePy {
+    /// Locks the engine, `action` describes the call in the error if the engine is closed.
+    fn engine(&self, py: Python, action: &str) -> PyResult<MutexGuard<Option<BackupEngine>>> {
+        let engine = lock_mutex(py, &self.engine);
+
+        if engine.is_none() {
+            return Err(RocksDBPyException::new_err(format!(
+                "{}, backup engine is closed",
+                action
+            )));
+        }
+
+        Ok(engine)
+    }
+}
+
+/// Returns the options of a restore, the log files of the WAL directory are removed by default.
+pub(crate) fn restore_options(keep_log_files: Option<bool>) -> RestoreOptions {
+    let mut opts = RestoreOptions::default();
+
+    opts.set_keep_log_files(keep_log_files.unwrap_or(false));
+
+    opts
+}
+
+/// Runs the backup on another thread and calls `progress` with the copied and total bytes
+/// every interval until the backup completes.
+fn with_progress<T: Send>(
+    py: Python,
+    db: &DB,
+    path: &Path,
+    flush: bool,
+    progress: PyObject,
+    backup: impl FnOnce() -> Result<T, Error> + Send,
+) -> PyResult<Result<T, Error>> {
+    // the flush creates the table files of the memtables, so they are counted in the total
+    let measured = py.allow_threads(|| {
+        if flush {
+            db.flush()?;
+        }
+
+        Ok::<_, Error>((unshared_size(db, path)?, directory_size(path)))
+    });
+
+    let (total, start) = match measured {
+        Ok(measured) => measured,
+        Err(e) => return Ok(Err(e)),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let receiver = Mutex::new(receiver);
+
+    thread::scope(|scope| {
+        scope.spawn(move || sender.send(backup()));
+
+        let mut failure = None;
+
+        loop {
+            let (received, copied) = py.allow_threads(|| {
+                let received = receiver.lock().unwrap().recv_timeout(PROGRESS_INTERVAL);
+
+                (received, directory_size(path).saturating_sub(start))
+            });
+
+            let result = match received {
+                Ok(result) => result,
+                Err(RecvTimeoutError::Timeout) => {
+                    if failure.is_none() {
+                        failure = progress.call1(py, (copied, total.max(copied))).err();
+                    }
+
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(RocksDBPyException::new_err("Backup cannot create, backup failed"))
+                }
+            };
+
+            if result.is_ok() && failure.is_none() {
+                let total = total.max(copied);
+
+                failure = progress.call1(py, (total, total)).err();
+            }
+
+            return match failure {
+                Some(e) => Err(e),
+                None => Ok(result),
+            };
+        }
+    })
+}
+
+/// Returns the size of the live table files which are not shared with the previous backups,
+/// these files are copied by the next backup.
+fn unshared_size(db: &DB, path: &Path) -> Result<u64, Error> {
+    let mut shared = HashSet::new();
+
+    for dir in ["shared", "shared_checksum"] {
+        for entry in fs::read_dir(path.join(dir)).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            // the shared files are named by the file number, or start with it with checksums
+            if let Some(number) = name.split(['_', '.']).next() {
+                shared.insert(number.to_string());
+            }
+        }
+    }
+
+    let size = db
+        .live_files()?
+        .iter()
+        .filter(|file| {
+            let name = file.name.trim_start_matches('/');
+
+            !shared.contains(name.split('.').next().unwrap_or(name))
+        })
+        .map(|file| file.size as u64)
+        .sum();
+
+    Ok(size)
+}
+
+/// Returns the total size of the files under the directory.
+fn directory_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Returns a directory next to the database for the checkpoint of a stream, so the table
+/// files are hard-linked on the same filesystem.
+pub(crate) fn stream_dir(path: &Path) -> PathBuf {
+    scratch_dir(path, "stream")
+}
+
+/// Returns a new directory name next to the database for the temporary files of `purpose`,
+/// the files are moved into the database on the same filesystem.
+pub(crate) fn scratch_dir(path: &Path, purpose: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let count = SCRATCHES.fetch_add(1, Ordering::Relaxed);
+
+    path.with_file_name(format!(".{}.{}-{}-{}", name, purpose, process::id(), count))
+}
+
+/// Writes the files of the directory into the file object as an uncompressed tar stream.
+pub(crate) fn write_stream(py: Python, dir: &Path, fileobj: &PyAny) -> PyResult<()> {
+    let mut names = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            return Err(RocksDBPyException::new_err(format!("Stream cannot write. {}", e)))
+        }
+    };
+
+    names.sort();
+
+    let tar = open_tar(py, fileobj, "w|")?;
+
+    let added = names
+        .iter()
+        .try_for_each(|name| tar.call_method1("add", (dir.join(name), name)).map(|_| ()));
+
+    // the end of the archive is written even if a file fails, the error of the file is raised
+    let closed = tar.call_method0("close");
+
+    added?;
+    closed?;
+
+    Ok(())
+}
+
+/// Reads the tar stream of `write_stream` from the file object into the directory, the
+/// directory must be missing or empty. The directory is removed if the stream fails.
+pub(crate) fn read_stream(py: Python, fileobj: &PyAny, dir: &Path) -> PyResult<()> {
+    let empty = fs::create_dir_all(dir)
+        .and_then(|_| fs::read_dir(dir))
+        .map(|mut entries| entries.next().is_none());
+
+    match empty {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(RocksDBPyException::new_err(format!(
+                "Stream cannot restore, {} is not empty",
+                dir.display()
+            )))
+        }
+        Err(e) => {
+            return Err(RocksDBPyException::new_err(format!("Stream cannot restore. {}", e)))
+        }
+    }
+
+    let restored = extract(py, fileobj, dir);
+
+    if restored.is_err() {
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    restored
+}
+
+/// Extracts the files of the tar stream into the directory.
+fn extract(py: Python, fileobj: &PyAny, dir: &Path) -> PyResult<()> {
+    let tar = open_tar(py, fileobj, "r|")?;
+
+    for member in tar.iter()? {
+        let member = member?;
+        let name: String = member.getattr("name")?.extract()?;
+
+        // the stream only contains the files of a database directory
+        let file = member.call_method0("isfile")?.is_true()?;
+
+        if !file || name.contains('/') || name.starts_with('.') {
+            return Err(RocksDBPyException::new_err(format!(
+                "Stream cannot restore, {} is not a database file",
+                name
+            )));
+        }
+
+        let source = tar.call_method1("extractfile", (member,))?;
+
+        let mut file = match File::create(dir.join(&name)) {
+            Ok(file) => file,
+            Err(e) => {
+                return Err(RocksDBPyException::new_err(format!("Stream cannot restore. {}", e)))
+            }
+        };
+
+        loop {
+            let chunk: &PyBytes = source.call_method1("read", (STREAM_CHUNK,))?.downcast()?;
+            let chunk = chunk.as_bytes();
+
+            if chunk.is_empty() {
+                break;
+            }
+
+            if let Err(e) = py.allow_threads(|| file.write_all(chunk)) {
+                return Err(RocksDBPyException::new_err(format!("Stream cannot restore. {}", e)));
+            }
+        }
+    }
+
+    tar.call_method0("close")?;
+
+    Ok(())
+}
+
+/// Opens a tar archive of the file object in a stream mode of `tarfile`.
+fn open_tar<'py>(py: Python<'py>, fileobj: &'py PyAny, mode: &str) -> PyResult<&'py PyAny> {
+    let kwargs = PyDict::new(py);
+
+    kwargs.set_item("fileobj", fileobj)?;
+    kwargs.set_item("mode", mode)?;
+
+    py.import("tarfile")?.getattr("open")?.call((), Some(kwargs))
+}
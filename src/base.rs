@@ -1,13 +1,59 @@
+use crate::codec::*;
 use crate::db::*;
+use crate::merge::*;
 use crate::option::*;
+use crate::ttl::*;
 use pyo3::create_exception;
-use pyo3::exceptions::PyException;
+use pyo3::exceptions::{PyException, PyValueError};
 use pyo3::prelude::*;
-use rocksdb::{Options, DB};
+use rocksdb::{
+    BoundColumnFamily, ColumnFamilyDescriptor, ColumnFamilyTtl, DBRawIteratorWithThreadMode,
+    DBWithThreadMode, Error, ErrorKind, MultiThreaded, Options, DEFAULT_COLUMN_FAMILY_NAME,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// RocksDB database, column families can be created while the database is shared.
+pub type DB = DBWithThreadMode<MultiThreaded>;
+
+/// Raw iterator of the database.
+pub type DBRawIterator<'a> = DBRawIteratorWithThreadMode<'a, DB>;
 
 create_exception!(rocksdbpy, RocksDBPyException, PyException);
+create_exception!(rocksdbpy, DBClosedError, RocksDBPyException);
+create_exception!(rocksdbpy, DBForkedError, RocksDBPyException);
+create_exception!(rocksdbpy, DBLockedError, RocksDBPyException);
+create_exception!(rocksdbpy, DBNotFoundError, RocksDBPyException);
+create_exception!(rocksdbpy, DBCorruptionError, RocksDBPyException);
+create_exception!(rocksdbpy, DBIOError, RocksDBPyException);
+create_exception!(rocksdbpy, DBBusyError, RocksDBPyException);
+create_exception!(rocksdbpy, DBTryAgainError, DBBusyError);
+create_exception!(rocksdbpy, DBTimedOutError, RocksDBPyException);
+create_exception!(rocksdbpy, DBInvalidArgumentError, RocksDBPyException);
+
+/// Messages of the status subcodes of RocksDB, the index is the subcode.
+const SUBCODES: [&str; 17] = [
+    "",
+    "Timeout Acquiring Mutex",
+    "Timeout waiting to lock key",
+    "Failed to acquire lock due to max_num_locks limit",
+    "No space left on device",
+    "Deadlock",
+    "Stale file handle",
+    "Memory limit reached",
+    "Space limit reached",
+    "No such file or directory",
+    "Insufficient capacity for merge operands",
+    "Manual compaction paused",
+    " (overwritten)",
+    "Txn not prepared",
+    "IO fenced off",
+    "Merge operator failed",
+    "Number of operands merged exceeded threshold",
+];
 
 /// Opens a database with default options.
 ///
@@ -16,22 +62,46 @@ create_exception!(rocksdbpy, RocksDBPyException, PyException);
 /// ```
 /// db = RocksDB()
 /// rocksdbpy.open_default('/tmp/test')
+///
+/// rocksdbpy.open_default(pathlib.Path('/tmp/test'))
+///
+/// rocksdbpy.open_default('/tmp/test', codec='json')
+///
+/// rocksdbpy.open_default('/tmp/test', text=True)
+///
+/// rocksdbpy.open_default('/tmp/test', wait_for_lock=10)
 /// ```
 #[pyfunction]
-pub fn open_default(path: &str) -> PyResult<DBPy> {
-    match DB::open_default(path) {
+#[pyo3(signature = (path, codec=None, text=None, wait_for_lock=None))]
+pub fn open_default(
+    py: Python,
+    path: PathBuf,
+    codec: Option<&PyAny>,
+    text: Option<bool>,
+    wait_for_lock: Option<f64>,
+) -> PyResult<DBPy> {
+    let codec = codec.map(Codec::parse).transpose()?;
+    let mut opts = Options::default();
+
+    opts.create_if_missing(true);
+    prepare(&mut opts, false);
+
+    let opened = retry_locked(py, wait_for_lock, || {
+        DB::open_cf_descriptors(&opts, &path, descriptors(&opts, &path))
+    })?;
+
+    match opened {
         Ok(db) => {
-            let db = DBPy {
-                db: Some(Arc::new(db)),
-                path: path.as_bytes().to_vec(),
-            };
+            return Ok(DBPy::new(db, &path)
+                .options(opts)
+                .codec(codec)
+                .text(text.unwrap_or(false)));
+        }
+        Err(e) => {
+            let message = format!("Database cannot be open, {}", e);
 
-            return Ok(db);
+            Err(open_error(py, &path, &e, message))
         }
-        Err(e) => Err(RocksDBPyException::new_err(format!(
-            "Database cannot be open, {}",
-            e
-        ))),
     }
 }
 
@@ -44,26 +114,49 @@ pub fn open_default(path: &str) -> PyResult<DBPy> {
 /// opts.create_if_missing(True)
 ///
 /// rocksdbpy.open('/tmp/test', opts)
+///
+/// rocksdbpy.open('/tmp/test', opts, codec=(encode, decode))
+///
+/// rocksdbpy.open('/tmp/test', opts, wait_for_lock=10)
 /// ```
 #[pyfunction]
-pub fn open(path: &str, opts: &OptionPy) -> PyResult<DBPy> {
-    match DB::open(&opts.inner, path) {
+#[pyo3(signature = (path, opts, codec=None, text=None, wait_for_lock=None))]
+pub fn open(
+    py: Python,
+    path: PathBuf,
+    opts: &OptionPy,
+    codec: Option<&PyAny>,
+    text: Option<bool>,
+    wait_for_lock: Option<f64>,
+) -> PyResult<DBPy> {
+    let codec = codec.map(Codec::parse).transpose()?;
+    let key_ttl = opts.key_ttl;
+    let mut opts = opts.inner.clone();
+
+    prepare(&mut opts, key_ttl);
+
+    let opened = retry_locked(py, wait_for_lock, || {
+        DB::open_cf_descriptors(&opts, &path, descriptors(&opts, &path))
+    })?;
+
+    match opened {
         Ok(db) => {
-            let db = DBPy {
-                db: Some(Arc::new(db)),
-                path: path.as_bytes().to_vec(),
-            };
+            return Ok(DBPy::new(db, &path)
+                .options(opts)
+                .codec(codec)
+                .text(text.unwrap_or(false))
+                .expiry(key_ttl));
+        }
+        Err(e) => {
+            let message = format!("Database cannot be open, {}", e);
 
-            return Ok(db);
+            Err(open_error(py, &path, &e, message))
         }
-        Err(e) => Err(RocksDBPyException::new_err(format!(
-            "Database cannot be open, {}",
-            e
-        ))),
     }
 }
 
-/// Opens the database with TTL compaction filter.
+/// Opens the database with TTL compaction filter. The column families of `cf_ttls` expire
+/// their records by their own TTL in seconds, they are created if they are missing.
 ///
 /// # Example
 ///
@@ -72,26 +165,71 @@ pub fn open(path: &str, opts: &OptionPy) -> PyResult<DBPy> {
 /// opts.create_if_missing(True)
 ///
 /// rocksdbpy.open_with_ttl('/tmp/test', 5, opts)
+///
+/// rocksdbpy.open_with_ttl('/tmp/test', 5, opts, cf_ttls={'sessions': 3600})
 /// ```
 #[pyfunction]
-pub fn open_with_ttl(path: &str, ttl: u64, opts: &OptionPy) -> PyResult<DBPy> {
+#[pyo3(signature = (path, ttl, opts, codec=None, text=None, cf_ttls=None, wait_for_lock=None))]
+pub fn open_with_ttl(
+    py: Python,
+    path: PathBuf,
+    ttl: u64,
+    opts: &OptionPy,
+    codec: Option<&PyAny>,
+    text: Option<bool>,
+    cf_ttls: Option<HashMap<String, u64>>,
+    wait_for_lock: Option<f64>,
+) -> PyResult<DBPy> {
     let duration = Duration::from_secs(ttl);
+    let codec = codec.map(Codec::parse).transpose()?;
+    let key_ttl = opts.key_ttl;
+    let mut opts = opts.inner.clone();
+    let cf_ttls: BTreeMap<_, _> = cf_ttls.unwrap_or_default().into_iter().collect();
+
+    prepare(&mut opts, key_ttl);
 
-    match DB::open_with_ttl(&opts.inner, path, duration) {
+    if !cf_ttls.is_empty() {
+        opts.create_missing_column_families(true);
+    }
+
+    let cfs = || {
+        let mut missing = cf_ttls.clone();
+
+        let mut cfs: Vec<_> = descriptors(&opts, &path)
+            .into_iter()
+            .map(|cf| match missing.remove(cf.name()) {
+                Some(ttl) => ttl_descriptor(&opts, cf.name(), ttl),
+                None => cf,
+            })
+            .collect();
+
+        cfs.extend(missing.iter().map(|(name, ttl)| ttl_descriptor(&opts, name, *ttl)));
+
+        cfs
+    };
+
+    let opened = retry_locked(py, wait_for_lock, || {
+        DB::open_cf_descriptors_with_ttl(&opts, &path, cfs(), duration)
+    })?;
+
+    match opened {
         Ok(db) => {
-            let db = DBPy {
-                db: Some(Arc::new(db)),
-                path: path.as_bytes().to_vec(),
-            };
+            return Ok(DBPy::new(db, &path)
+                .options(opts)
+                .codec(codec)
+                .text(text.unwrap_or(false))
+                .expiry(key_ttl));
+        }
+        Err(e) => {
+            let message = format!(
+                "Database cannot be open with {} with ttl {} seconds. {}",
+                path.display(),
+                duration.as_secs(),
+                e,
+            );
 
-            return Ok(db);
+            Err(open_error(py, &path, &e, message))
         }
-        Err(e) => Err(RocksDBPyException::new_err(format!(
-            "Database cannot be open with {} with ttl {} seconds. {}",
-            path,
-            duration.as_secs(),
-            e,
-        ))),
     }
 }
 
@@ -109,39 +247,41 @@ pub fn open_with_ttl(path: &str, ttl: u64, opts: &OptionPy) -> PyResult<DBPy> {
 /// rocksdbpy.open_for_readonly('/tmp/test', opts, False)
 /// ```
 #[pyfunction]
+#[pyo3(signature = (path, option=None, error=None, codec=None, text=None))]
 pub fn open_for_readonly(
-    path: &str,
+    path: PathBuf,
     option: Option<OptionPy>,
     error: Option<bool>,
+    codec: Option<&PyAny>,
+    text: Option<bool>,
 ) -> PyResult<DBPy> {
-    let mut err: bool = false;
+    let err = error.unwrap_or(false);
     let mut opts: Options = Options::default();
+    let mut key_ttl = false;
+    let codec = codec.map(Codec::parse).transpose()?;
 
-    if !option.is_none() {
-        opts = option.unwrap().inner;
+    if let Some(option) = option {
+        key_ttl = option.key_ttl;
+        opts = option.inner;
     }
 
-    if !error.is_none() {
-        err = false;
-    }
+    prepare(&mut opts, key_ttl);
 
-    match DB::open_for_read_only(&opts, path, err) {
+    match DB::open_cf_descriptors_read_only(&opts, &path, descriptors(&opts, &path), err) {
         Ok(db) => {
-            let db = DBPy {
-                db: Some(Arc::new(db)),
-                path: path.as_bytes().to_vec(),
-            };
-
-            return Ok(db);
+            return Ok(DBPy::new(db, &path)
+                .options(opts)
+                .read_only(true)
+                .codec(codec)
+                .text(text.unwrap_or(false))
+                .expiry(key_ttl));
         }
-        Err(e) => Err(RocksDBPyException::new_err(format!(
-            "Database cannot be open for read only, {}",
-            e
-        ))),
+        Err(e) => Err(rocksdb_error(&e, format!("Database cannot be open for read only, {}", e))),
     }
 }
 
-/// Opens the database as a secondary.
+/// Opens the database as a secondary. If `catch_up_interval` is given, a background thread
+/// catches up with the primary every `catch_up_interval` seconds.
 ///
 /// # Example
 ///
@@ -151,32 +291,63 @@ pub fn open_for_readonly(
 /// rocksdbpy.open_as_secondary('/tmp/test/1', '/tmp/test/2')
 ///
 /// rocksdbpy.open_as_secondary('/tmp/test/1', '/tmp/test/2', opts)
+///
+/// rocksdbpy.open_as_secondary('/tmp/test/1', '/tmp/test/2', catch_up_interval=1.0)
 /// ```
 #[pyfunction]
+#[pyo3(signature = (
+    primary,
+    secondary,
+    option=None,
+    codec=None,
+    text=None,
+    catch_up_interval=None,
+))]
 pub fn open_as_secondary(
-    primary: &str,
-    secondary: &str,
+    primary: PathBuf,
+    secondary: PathBuf,
     option: Option<OptionPy>,
+    codec: Option<&PyAny>,
+    text: Option<bool>,
+    catch_up_interval: Option<f64>,
 ) -> PyResult<DBPy> {
     let mut opts: Options = Options::default();
+    let mut key_ttl = false;
+    let codec = codec.map(Codec::parse).transpose()?;
 
-    if !option.is_none() {
-        opts = option.unwrap().inner;
+    let interval = match catch_up_interval {
+        Some(seconds) => match Duration::try_from_secs_f64(seconds) {
+            Ok(interval) if !interval.is_zero() => Some(interval),
+            _ => {
+                return Err(PyValueError::new_err(
+                    "Database cannot be open, catch_up_interval must be positive",
+                ))
+            }
+        },
+        None => None,
+    };
+
+    if let Some(option) = option {
+        key_ttl = option.key_ttl;
+        opts = option.inner;
     }
 
-    match DB::open_as_secondary(&opts, primary, secondary) {
-        Ok(db) => {
-            let db = DBPy {
-                db: Some(Arc::new(db)),
-                path: secondary.as_bytes().to_vec(),
-            };
+    prepare(&mut opts, key_ttl);
+
+    let cfs = descriptors(&opts, &primary);
 
-            return Ok(db);
+    match DB::open_cf_descriptors_as_secondary(&opts, &primary, &secondary, cfs) {
+        Ok(db) => {
+            return Ok(DBPy::new(db, &secondary)
+                .options(opts)
+                .read_only(true)
+                .codec(codec)
+                .text(text.unwrap_or(false))
+                .expiry(key_ttl)
+                .primary(&primary)
+                .catch_up(interval));
         }
-        Err(e) => Err(RocksDBPyException::new_err(format!(
-            "Database cannot be open for read only, {}",
-            e
-        ))),
+        Err(e) => Err(rocksdb_error(&e, format!("Database cannot be open for read only, {}", e))),
     }
 }
 
@@ -188,18 +359,193 @@ pub fn open_as_secondary(
 /// rocksdbpy.destroy('/tmp/test')
 /// ```
 #[pyfunction]
-pub fn destroy(path: &str, option: Option<OptionPy>) -> PyResult<()> {
+#[pyo3(signature = (path, option=None))]
+pub fn destroy(path: PathBuf, option: Option<OptionPy>) -> PyResult<()> {
     let mut opts: Options = Options::default();
 
     if !option.is_none() {
         opts = option.unwrap().inner;
     }
 
-    match DB::destroy(&opts, path) {
+    match DB::destroy(&opts, &path) {
         Ok(()) => Ok(()),
-        Err(e) => Err(RocksDBPyException::new_err(format!(
-            "Database cannot be destory, {}",
-            e
+        Err(e) => Err(rocksdb_error(&e, format!("Database cannot be destory, {}", e))),
+    }
+}
+
+/// Returns the names of the column families of the database at the path.
+///
+/// # Example
+///
+/// ```
+/// names = rocksdbpy.list_column_families('/tmp/test')
+/// ```
+#[pyfunction]
+#[pyo3(signature = (path, option=None))]
+pub fn list_column_families(path: PathBuf, option: Option<OptionPy>) -> PyResult<Vec<String>> {
+    let opts = match option {
+        Some(option) => option.inner,
+        None => Options::default(),
+    };
+
+    match DB::list_cf(&opts, &path) {
+        Ok(names) => Ok(names),
+        Err(e) => Err(rocksdb_error(&e, format!("Column families cannot be listed. {}", e))),
+    }
+}
+
+/// Opens the database by `open`. If the database is locked by another instance, it is opened
+/// again every 100 milliseconds until `wait_for_lock` seconds elapse.
+fn retry_locked<T>(
+    py: Python,
+    wait_for_lock: Option<f64>,
+    mut open: impl FnMut() -> Result<T, Error>,
+) -> PyResult<Result<T, Error>> {
+    let deadline = match wait_for_lock.map(Duration::try_from_secs_f64) {
+        Some(Ok(timeout)) => Instant::now() + timeout,
+        Some(Err(_)) => {
+            return Err(PyValueError::new_err(
+                "Database cannot be open, wait_for_lock must not be negative",
+            ))
+        }
+        None => Instant::now(),
+    };
+
+    loop {
+        match open() {
+            Err(e) if locked(&e) && Instant::now() < deadline => {
+                py.check_signals()?;
+                py.allow_threads(|| thread::sleep(Duration::from_millis(100)));
+            }
+            result => return Ok(result),
+        }
+    }
+}
+
+/// Returns true if the open failed because the lock file is held by another instance.
+fn locked(e: &Error) -> bool {
+    let message = e.as_ref();
+
+    message.contains("While lock file") || message.contains("lock hold by current process")
+}
+
+/// Converts the error of an open, `DBLockedError` is raised with the path of the lock file if
+/// the database is locked. RocksDB does not record the holder of the lock, only whether it is
+/// the current process.
+fn open_error(py: Python, path: &Path, e: &Error, message: String) -> PyErr {
+    if !locked(e) {
+        return rocksdb_error(e, message);
+    }
+
+    let err = with_status(py, e, DBLockedError::new_err(message));
+    let value = err.value(py);
+
+    let current = e.as_ref().contains("lock hold by current process");
+
+    if let Err(e) = value
+        .setattr("lock_path", path.join("LOCK"))
+        .and_then(|_| value.setattr("held_by_current_process", current))
+    {
+        return e;
+    }
+
+    err
+}
+
+/// Converts an error of RocksDB to the exception of its status, e.g. `DBBusyError`. The
+/// exception has the `code` and `subcode` of the status like `rocksdb::Status`, the code is
+/// None if the status is unknown.
+pub fn rocksdb_error(e: &Error, message: String) -> PyErr {
+    let err = match e.kind() {
+        ErrorKind::NotFound => DBNotFoundError::new_err(message),
+        ErrorKind::Corruption => DBCorruptionError::new_err(message),
+        ErrorKind::IOError => DBIOError::new_err(message),
+        ErrorKind::Busy => DBBusyError::new_err(message),
+        ErrorKind::TryAgain => DBTryAgainError::new_err(message),
+        ErrorKind::TimedOut => DBTimedOutError::new_err(message),
+        ErrorKind::InvalidArgument => DBInvalidArgumentError::new_err(message),
+        _ => RocksDBPyException::new_err(message),
+    };
+
+    Python::with_gil(|py| with_status(py, e, err))
+}
+
+/// Sets the `code` and `subcode` of the status on the exception.
+fn with_status(py: Python, e: &Error, err: PyErr) -> PyErr {
+    let code = match e.kind() {
+        ErrorKind::NotFound => Some(1),
+        ErrorKind::Corruption => Some(2),
+        ErrorKind::NotSupported => Some(3),
+        ErrorKind::InvalidArgument => Some(4),
+        ErrorKind::IOError => Some(5),
+        ErrorKind::MergeInProgress => Some(6),
+        ErrorKind::Incomplete => Some(7),
+        ErrorKind::ShutdownInProgress => Some(8),
+        ErrorKind::TimedOut => Some(9),
+        ErrorKind::Aborted => Some(10),
+        ErrorKind::Busy => Some(11),
+        ErrorKind::Expired => Some(12),
+        ErrorKind::TryAgain => Some(13),
+        ErrorKind::CompactionTooLarge => Some(14),
+        ErrorKind::ColumnFamilyDropped => Some(15),
+        ErrorKind::Unknown => None,
+    };
+
+    // RocksDB only returns the message of the status, the subcode is found by its message
+    let subcode = SUBCODES
+        .iter()
+        .skip(1)
+        .position(|subcode| e.as_ref().contains(subcode))
+        .map_or(0, |i| i + 1);
+
+    let value = err.value(py);
+
+    if let Err(e) = value
+        .setattr("code", code)
+        .and_then(|_| value.setattr("subcode", subcode))
+    {
+        return e;
+    }
+
+    err
+}
+
+/// Sets the merge operator of the bindings, and the compaction filter of the expired records
+/// if the key TTL is enabled.
+pub(crate) fn prepare(opts: &mut Options, key_ttl: bool) {
+    set_merge_operator(opts, key_ttl);
+
+    if key_ttl {
+        set_expiry_filter(opts);
+    }
+}
+
+/// Returns the descriptors of the column families of the database, RocksDB requires opening
+/// all of them. A missing database has only the default column family.
+pub(crate) fn descriptors(opts: &Options, path: &Path) -> Vec<ColumnFamilyDescriptor> {
+    let names =
+        DB::list_cf(opts, path).unwrap_or_else(|_| vec![DEFAULT_COLUMN_FAMILY_NAME.to_string()]);
+
+    names
+        .into_iter()
+        .map(|name| ColumnFamilyDescriptor::new(name, opts.clone()))
+        .collect()
+}
+
+/// Returns the descriptor of a column family which expires its records by its own TTL.
+fn ttl_descriptor(opts: &Options, name: &str, ttl: u64) -> ColumnFamilyDescriptor {
+    let ttl = ColumnFamilyTtl::Duration(Duration::from_secs(ttl));
+
+    ColumnFamilyDescriptor::new_with_ttl(name, opts.clone(), ttl)
+}
+
+/// Returns the handle of the column family "name" of the database.
+pub fn cf_handle<'a>(db: &'a DB, name: &str) -> PyResult<Arc<BoundColumnFamily<'a>>> {
+    match db.cf_handle(name) {
+        Some(cf) => Ok(cf),
+        None => Err(RocksDBPyException::new_err(format!(
+            "Column family {} cannot be found",
+            name
         ))),
     }
 }
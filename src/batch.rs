@@ -1,12 +1,28 @@
 use crate::base::*;
+use crate::buffer::*;
+use crate::codec::*;
+use crate::entity::*;
+use crate::lock::*;
+use crate::merge::*;
+use crate::record::*;
+use crate::ttl::*;
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
-use rocksdb::WriteBatch;
+use pyo3::types::{PyBytes, PyIterator, PyList};
+use rocksdb::{BoundColumnFamily, WriteBatch};
+use std::borrow::Cow;
+use std::sync::Arc;
 
 /// Batch writer.
 #[pyclass(name = "WriteBatch")]
 pub struct WriteBatchPy {
     writer: Option<WriteBatch>,
+    db: Option<Arc<DB>>,
+    lock: WriteLock,
+    expiry: bool,
+    /// Values are encoded by the codec of the database which the batch is bound to.
+    codec: Option<Codec>,
+    /// `str` keys are accepted as UTF-8 in the text mode of the database.
+    text: bool,
 }
 
 #[pymethods]
@@ -15,10 +31,94 @@ impl WriteBatchPy {
     fn new() -> PyResult<Self> {
         Ok(WriteBatchPy {
             writer: Some(WriteBatch::default()),
+            db: None,
+            lock: WriteLock::default(),
+            expiry: false,
+            codec: None,
+            text: false,
         })
     }
 
-    /// Append new "key" and "value" in the batch.
+    /// Creates a batch from the serialized batch of `data()`, e.g. to apply a batch which
+    /// is received from another process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b = WriteBatch.from_data(data)
+    ///
+    /// db.write(b)
+    /// ```
+    #[staticmethod]
+    fn from_data(data: BytesLike) -> PyResult<Self> {
+        // malformed batches are rejected before RocksDB reads them
+        if let Err(e) = parse(data.as_bytes()) {
+            return Err(RocksDBPyException::new_err(format!(
+                "Batch cannot be decoded. {}",
+                e
+            )));
+        }
+
+        Ok(WriteBatchPy {
+            writer: Some(WriteBatch::from_data(data.as_bytes())),
+            db: None,
+            lock: WriteLock::default(),
+            expiry: false,
+            codec: None,
+            text: false,
+        })
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyResult<PyRef<Self>> {
+        if slf.db.is_none() {
+            return Err(RocksDBPyException::new_err(
+                "Batch writer is not bound to a database. Use db.write_batch()",
+            ));
+        }
+
+        Ok(slf)
+    }
+
+    /// Writes the batch to the database if the block is completed without an exception,
+    /// otherwise the batch is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// with db.write_batch() as b:
+    ///     b.add(b'first', b'first_value')
+    ///     b.delete(b'second')
+    /// ```
+    fn __exit__(
+        &mut self,
+        py: Python,
+        exc_type: Option<&PyAny>,
+        _exc_value: Option<&PyAny>,
+        _traceback: Option<&PyAny>,
+    ) -> PyResult<bool> {
+        let wr = self.get()?;
+
+        if exc_type.is_some() {
+            return Ok(false);
+        }
+
+        if let Some(db) = self.db.take() {
+            let len = wr.len();
+            let _guard = self.lock.shared(py);
+
+            if let Err(e) = py.allow_threads(|| db.write(wr)) {
+                return Err(rocksdb_error(
+                    &e,
+                    format!("Batch cannot write {} elements. {}", len, e),
+                ));
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Append new "key" and "value" in the batch. The value is encoded by the codec of the
+    /// database if the batch is created by `db.write_batch()`.
     ///
     /// # Example
     ///
@@ -26,17 +126,47 @@ impl WriteBatchPy {
     /// b = WriteBatch()
     ///
     /// b.add(b'first', 'first_value')
+    ///
+    /// with db.write_batch() as b:
+    ///     b.add(b'first', b'first_value', ttl=3600)
+    ///     b.add(b'first', b'first_value', cf='users')
     /// ```
-    fn add(&mut self, key: &PyBytes, value: &PyBytes) -> PyResult<()> {
-        match &mut self.writer {
-            Some(inner) => Ok(inner.put(key.as_bytes(), value.as_bytes())),
-            None => Err(RocksDBPyException::new_err(
-                "Batch writer is invalid. New writer is required",
-            )),
+    #[pyo3(signature = (key, value, ttl=None, cf=None))]
+    fn add(
+        &mut self,
+        py: Python,
+        key: &PyAny,
+        value: &PyAny,
+        ttl: Option<u64>,
+        cf: Option<&str>,
+    ) -> PyResult<()> {
+        if ttl.is_some() && !self.expiry {
+            return Err(RocksDBPyException::new_err(
+                "Batch cannot add with a TTL, key TTL is not enabled by the options",
+            ));
+        }
+
+        let key = encode_key(self.text, key)?;
+        let value = encode_value(py, &self.codec, value)?;
+
+        let value = if self.expiry {
+            Cow::Owned(wrap(value.as_bytes(), ttl))
+        } else {
+            Cow::Borrowed(value.as_bytes())
+        };
+
+        let inner = writer(&mut self.writer)?;
+
+        match column_family(&self.db, cf)? {
+            Some(cf) => inner.put_cf(&cf, key.as_bytes(), value),
+            None => inner.put(key.as_bytes(), value),
         }
+
+        Ok(())
     }
 
-    /// Remove "key" from the batch.
+    /// Remove "key" from the batch. A batch which is created by `db.write_batch()` removes
+    /// the entity of the key as well.
     ///
     /// # Example
     ///
@@ -44,17 +174,116 @@ impl WriteBatchPy {
     /// b = WriteBatch()
     ///
     /// b.delete(b'first')
+    ///
+    /// with db.write_batch() as b:
+    ///     b.delete(b'first', cf='users')
     /// ```
-    fn delete(&mut self, key: &PyBytes) -> PyResult<()> {
-        match &mut self.writer {
-            Some(inner) => Ok(inner.delete(key.as_bytes())),
-            None => Err(RocksDBPyException::new_err(
-                "Batch writer is invalid. New writer is required",
+    #[pyo3(signature = (key, cf=None))]
+    fn delete(&mut self, key: &PyAny, cf: Option<&str>) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+        let inner = writer(&mut self.writer)?;
+
+        match column_family(&self.db, cf)? {
+            Some(cf) => inner.delete_cf(&cf, key.as_bytes()),
+            None => inner.delete(key.as_bytes()),
+        }
+
+        if let Some(db) = &self.db {
+            delete_entity(db, inner, cf, key.as_bytes());
+        }
+
+        Ok(())
+    }
+
+    /// Removes the keys in the range from "start" to "end", "end" is excluded. A batch which is
+    /// created by `db.write_batch()` removes the entities in the range as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b = WriteBatch()
+    ///
+    /// b.delete_range(b'a', b'b')
+    /// ```
+    #[pyo3(signature = (start, end, cf=None))]
+    fn delete_range(&mut self, start: &PyAny, end: &PyAny, cf: Option<&str>) -> PyResult<()> {
+        let (start, end) = (encode_key(self.text, start)?, encode_key(self.text, end)?);
+        let inner = writer(&mut self.writer)?;
+
+        match column_family(&self.db, cf)? {
+            Some(cf) => inner.delete_range_cf(&cf, start.as_bytes(), end.as_bytes()),
+            None => inner.delete_range(start.as_bytes(), end.as_bytes()),
+        }
+
+        if let Some(db) = &self.db {
+            delete_entity_range(db, inner, cf, start.as_bytes(), end.as_bytes());
+        }
+
+        Ok(())
+    }
+
+    /// Merges the "value" into the value of the "key" by the merge operator of the database,
+    /// the value is appended like `db.append`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b = WriteBatch()
+    ///
+    /// b.merge(b'events', b'started\n')
+    /// ```
+    #[pyo3(signature = (key, value, cf=None))]
+    fn merge(&mut self, key: &PyAny, value: &PyBytes, cf: Option<&str>) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+        let operand = append_operand(value.as_bytes());
+        let inner = writer(&mut self.writer)?;
+
+        match column_family(&self.db, cf)? {
+            Some(cf) => inner.merge_cf(&cf, key.as_bytes(), operand),
+            None => inner.merge(key.as_bytes(), operand),
+        }
+
+        Ok(())
+    }
+
+    /// Records the current state of the batch, `rollback_to_savepoint` discards the records
+    /// which are added after it. Save points can be nested.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b.add(b'first', b'first_value')
+    ///
+    /// b.set_savepoint()
+    /// b.add(b'second', b'second_value')
+    /// b.rollback_to_savepoint()  # only b'first' is left
+    /// ```
+    fn set_savepoint(&mut self) -> PyResult<()> {
+        writer(&mut self.writer)?.set_save_point();
+
+        Ok(())
+    }
+
+    /// Discards the records which are added after the most recent save point and removes
+    /// the save point. Raises an exception if there is no save point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b.rollback_to_savepoint()
+    /// ```
+    fn rollback_to_savepoint(&mut self) -> PyResult<()> {
+        match writer(&mut self.writer)?.rollback_to_save_point() {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(
+                &e,
+                format!("Batch cannot rollback to the save point. {}", e),
             )),
         }
     }
 
-    /// Clear the batch.
+    /// Clear the batch. A batch which is written by `db.write` can be reused after it is
+    /// cleared.
     ///
     /// # Example
     ///
@@ -65,11 +294,11 @@ impl WriteBatchPy {
     /// ```
     fn clear(&mut self) -> PyResult<()> {
         match &mut self.writer {
-            Some(inner) => Ok(inner.clear()),
-            None => Err(RocksDBPyException::new_err(
-                "Batch writer is invalid. New writer is required",
-            )),
+            Some(inner) => inner.clear(),
+            None => self.writer = Some(WriteBatch::default()),
         }
+
+        Ok(())
     }
 
     /// Returns element count of the batch.
@@ -82,16 +311,106 @@ impl WriteBatchPy {
     /// size = b.len()
     /// ```
     fn len(&mut self) -> PyResult<usize> {
-        match &mut self.writer {
-            Some(inner) => Ok(inner.len()),
-            None => Err(RocksDBPyException::new_err(
-                "Batch writer is invalid. New writer is required",
-            )),
-        }
+        Ok(writer(&mut self.writer)?.len())
+    }
+
+    /// Returns element count of the batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// size = len(b)
+    /// ```
+    fn __len__(&mut self) -> PyResult<usize> {
+        self.len()
+    }
+
+    /// Returns the size of the serialized batch in bytes, so the batch can be written when the
+    /// size reaches a threshold.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if b.size_in_bytes() > 1024 * 1024:
+    ///     db.write(b)
+    ///     b.clear()
+    /// ```
+    fn size_in_bytes(&mut self) -> PyResult<usize> {
+        Ok(writer(&mut self.writer)?.size_in_bytes())
+    }
+
+    /// Returns the serialized batch, `WriteBatch.from_data` creates the same batch from it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// data = b.data()
+    /// ```
+    fn data<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        Ok(PyBytes::new(py, writer(&mut self.writer)?.data()))
+    }
+
+    /// Returns the records of the batch as (operation, column family, key, value) tuples. The
+    /// operation is one of 'put', 'delete', 'single_delete', 'delete_range', 'merge' and
+    /// 'put_entity', the column family is its id which is 0 for the default column family.
+    /// The values are returned as stored, the value of a range deletion is the end of the
+    /// range and deletions have no value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for op, cf, key, value in b.entries():
+    ///     print(op, cf, key, value)
+    /// ```
+    fn entries<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyList> {
+        let inner = writer(&mut self.writer)?;
+
+        records(py, inner.data())
+    }
+
+    /// Returns an iterator over the records of the batch, see `entries`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for op, cf, key, value in b:
+    ///     print(op, cf, key, value)
+    /// ```
+    fn __iter__<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyIterator> {
+        PyIterator::from_object(self.entries(py)?)
     }
 }
 
 impl WriteBatchPy {
+    /// Creates a batch which is written to the database when its block is completed.
+    pub fn bound(db: Arc<DB>, lock: WriteLock, expiry: bool, codec: Option<Codec>) -> WriteBatchPy {
+        WriteBatchPy {
+            writer: Some(WriteBatch::default()),
+            db: Some(db),
+            lock,
+            expiry,
+            codec,
+            text: false,
+        }
+    }
+
+    /// Accepts `str` keys as UTF-8 if the text mode is enabled.
+    pub fn text(mut self, enabled: bool) -> WriteBatchPy {
+        self.text = enabled;
+
+        self
+    }
+
+    /// Returns true if the values of the batch have an expiration header.
+    pub fn expiry(&self) -> bool {
+        self.expiry
+    }
+
+    /// Returns true if the values of the batch are encoded by a codec.
+    pub fn encoded(&self) -> bool {
+        self.codec.is_some()
+    }
+
     #[inline]
     pub fn get(&mut self) -> PyResult<WriteBatch> {
         match self.writer.take() {
@@ -102,3 +421,53 @@ impl WriteBatchPy {
         }
     }
 }
+
+/// Returns the writer of the batch, the writer is taken when the batch is written.
+#[inline]
+fn writer(writer: &mut Option<WriteBatch>) -> PyResult<&mut WriteBatch> {
+    match writer {
+        Some(inner) => Ok(inner),
+        None => Err(RocksDBPyException::new_err(
+            "Batch writer is invalid. New writer is required",
+        )),
+    }
+}
+
+/// Returns the handle of the column family "name", the column families are known only by the
+/// batches of `db.write_batch()`.
+fn column_family<'a>(
+    db: &'a Option<Arc<DB>>,
+    name: Option<&str>,
+) -> PyResult<Option<Arc<BoundColumnFamily<'a>>>> {
+    match (db, name) {
+        (_, None) => Ok(None),
+        (Some(db), Some(name)) => Ok(Some(cf_handle(db, name)?)),
+        (None, Some(_)) => Err(RocksDBPyException::new_err(
+            "Batch cannot use a column family, use db.write_batch()",
+        )),
+    }
+}
+
+/// Converts the records of a serialized batch to a list of Python tuples.
+pub fn records<'py>(py: Python<'py>, data: &[u8]) -> PyResult<&'py PyList> {
+    let records = match parse(data) {
+        Ok(records) => records,
+        Err(e) => {
+            return Err(RocksDBPyException::new_err(format!(
+                "Batch cannot be decoded. {}",
+                e
+            )))
+        }
+    };
+
+    let entries = records.iter().map(|record| {
+        (
+            record.op.name(),
+            record.cf,
+            PyBytes::new(py, record.key),
+            record.value.map(|value| PyBytes::new(py, value)),
+        )
+    });
+
+    Ok(PyList::new(py, entries.map(|entry| entry.into_py(py))))
+}
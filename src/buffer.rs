@@ -0,0 +1,56 @@
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Key or value argument which accepts `bytes` and any object exposing the buffer protocol,
+/// such as `bytearray`, `memoryview` or numpy arrays. The data is not copied.
+pub struct BytesLike<'a> {
+    slice: &'a [u8],
+    view: Option<Box<ffi::Py_buffer>>,
+}
+
+impl<'a> BytesLike<'a> {
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.slice
+    }
+}
+
+impl<'a> FromPyObject<'a> for BytesLike<'a> {
+    fn extract(obj: &'a PyAny) -> PyResult<Self> {
+        if let Ok(bytes) = obj.downcast::<PyBytes>() {
+            return Ok(BytesLike {
+                slice: bytes.as_bytes(),
+                view: None,
+            });
+        }
+
+        unsafe {
+            let mut view: Box<ffi::Py_buffer> = Box::new(std::mem::zeroed());
+
+            // simple buffer request fails for non-contiguous buffers
+            if ffi::PyObject_GetBuffer(obj.as_ptr(), &mut *view, ffi::PyBUF_SIMPLE) == -1 {
+                return Err(PyErr::fetch(obj.py()));
+            }
+
+            let slice = if view.len > 0 {
+                std::slice::from_raw_parts(view.buf as *const u8, view.len as usize)
+            } else {
+                &[]
+            };
+
+            Ok(BytesLike {
+                slice,
+                view: Some(view),
+            })
+        }
+    }
+}
+
+impl<'a> Drop for BytesLike<'a> {
+    fn drop(&mut self) {
+        if let Some(view) = &mut self.view {
+            unsafe { ffi::PyBuffer_Release(&mut **view) }
+        }
+    }
+}
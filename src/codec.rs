@@ -0,0 +1,151 @@
+use crate::buffer::*;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString, PyTuple};
+
+/// Serializes Python objects into values and deserializes them back.
+#[derive(Clone)]
+pub enum Codec {
+    Text,
+    Json,
+    Pickle,
+    Custom { encode: PyObject, decode: PyObject },
+}
+
+/// Encoded value, either borrowed from a bytes-like object or created by a codec.
+pub enum Encoded<'a> {
+    Raw(BytesLike<'a>),
+    Borrowed(&'a [u8]),
+    Owned(Vec<u8>),
+}
+
+impl<'a> Encoded<'a> {
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Encoded::Raw(raw) => raw.as_bytes(),
+            Encoded::Borrowed(borrowed) => borrowed,
+            Encoded::Owned(owned) => owned,
+        }
+    }
+}
+
+impl Codec {
+    /// Parses the codec argument of the open functions. The codec is either the name of a
+    /// built-in codec, a tuple of encode and decode callables, or an object which has
+    /// `encode` and `decode` methods.
+    pub fn parse(obj: &PyAny) -> PyResult<Codec> {
+        if let Ok(name) = obj.downcast::<PyString>() {
+            return match name.to_str()? {
+                "utf-8" => Ok(Codec::Text),
+                "json" => Ok(Codec::Json),
+                "pickle" => Ok(Codec::Pickle),
+                other => Err(PyValueError::new_err(format!(
+                    "Codec cannot be found, {} is not a built-in codec",
+                    other
+                ))),
+            };
+        }
+
+        let (encode, decode) = if let Ok(pair) = obj.downcast::<PyTuple>() {
+            pair.extract::<(&PyAny, &PyAny)>()?
+        } else if obj.hasattr("encode")? && obj.hasattr("decode")? {
+            (obj.getattr("encode")?, obj.getattr("decode")?)
+        } else {
+            return Err(PyTypeError::new_err(
+                "Codec must be 'utf-8', 'json', 'pickle', an (encode, decode) tuple or an object with encode and decode methods",
+            ));
+        };
+
+        if !encode.is_callable() || !decode.is_callable() {
+            return Err(PyTypeError::new_err("Codec encode and decode must be callable"));
+        }
+
+        Ok(Codec::Custom {
+            encode: encode.into(),
+            decode: decode.into(),
+        })
+    }
+
+    /// Serializes the Python object into bytes.
+    pub fn encode(&self, py: Python, value: &PyAny) -> PyResult<Vec<u8>> {
+        let encoded = match self {
+            Codec::Text => {
+                let text: &str = value.extract()?;
+
+                return Ok(text.as_bytes().to_vec());
+            }
+            Codec::Json => {
+                let text: String = py.import("json")?.call_method1("dumps", (value,))?.extract()?;
+
+                return Ok(text.into_bytes());
+            }
+            Codec::Pickle => py.import("pickle")?.call_method1("dumps", (value,))?,
+            Codec::Custom { encode, .. } => encode.as_ref(py).call1((value,))?,
+        };
+
+        let bytes: BytesLike = encoded.extract()?;
+
+        Ok(bytes.as_bytes().to_vec())
+    }
+
+    /// Deserializes the bytes into a Python object.
+    pub fn decode(&self, py: Python, value: &[u8]) -> PyResult<PyObject> {
+        let bytes = PyBytes::new(py, value);
+
+        let decoded = match self {
+            Codec::Text => match std::str::from_utf8(value) {
+                Ok(text) => return Ok(text.into_py(py)),
+                Err(e) => return Err(PyValueError::new_err(format!("Value cannot decode. {}", e))),
+            },
+            Codec::Json => py.import("json")?.call_method1("loads", (bytes,))?,
+            Codec::Pickle => py.import("pickle")?.call_method1("loads", (bytes,))?,
+            Codec::Custom { decode, .. } => decode.as_ref(py).call1((bytes,))?,
+        };
+
+        Ok(decoded.into_py(py))
+    }
+}
+
+/// Encodes the value with the codec, or borrows it as bytes if there is no codec.
+pub fn encode_value<'a>(
+    py: Python,
+    codec: &Option<Codec>,
+    value: &'a PyAny,
+) -> PyResult<Encoded<'a>> {
+    match codec {
+        Some(codec) => Ok(Encoded::Owned(codec.encode(py, value)?)),
+        None => Ok(Encoded::Raw(value.extract()?)),
+    }
+}
+
+/// Decodes the value with the codec, or returns it as bytes if there is no codec.
+pub fn decode_value(py: Python, codec: &Option<Codec>, value: &[u8]) -> PyResult<PyObject> {
+    match codec {
+        Some(codec) => codec.decode(py, value),
+        None => Ok(PyBytes::new(py, value).into_py(py)),
+    }
+}
+
+/// Borrows the key as bytes, `str` keys are encoded as UTF-8 if the text mode is enabled.
+pub fn encode_key(text: bool, key: &PyAny) -> PyResult<Encoded> {
+    if text {
+        if let Ok(key) = key.downcast::<PyString>() {
+            return Ok(Encoded::Borrowed(key.to_str()?.as_bytes()));
+        }
+    }
+
+    Ok(Encoded::Raw(key.extract()?))
+}
+
+/// Returns the key as bytes, or as `str` decoded from UTF-8 if the text mode is enabled.
+pub fn decode_key<'py>(py: Python<'py>, text: bool, key: &[u8]) -> PyResult<&'py PyAny> {
+    if !text {
+        return Ok(PyBytes::new(py, key));
+    }
+
+    match std::str::from_utf8(key) {
+        Ok(key) => Ok(PyString::new(py, key)),
+        Err(e) => Err(PyValueError::new_err(format!("Key cannot decode. {}", e))),
+    }
+}
@@ -0,0 +1,205 @@
+use crate::base::*;
+use crate::lock::*;
+use crate::scan::*;
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Maximum number of the chunks of a background compaction, a cancel takes effect after the
+/// running chunk.
+const COMPACTION_CHUNKS: usize = 64;
+
+/// State of a background compaction which is shared with its thread.
+#[derive(Default)]
+struct State {
+    finished: Mutex<bool>,
+    condvar: Condvar,
+    done: AtomicUsize,
+    total: AtomicUsize,
+    canceled: AtomicBool,
+}
+
+/// Manual compaction of a range which runs on a background thread. The range is compacted in
+/// chunks which are split at the boundaries of the SST files, so the compaction reports its
+/// progress and can be canceled between the chunks.
+#[pyclass(name = "Compaction")]
+pub struct CompactionPy {
+    state: Arc<State>,
+    handle: Option<JoinHandle<()>>,
+}
+
+#[pymethods]
+impl CompactionPy {
+    /// Returns true if the compaction is completed or canceled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if compaction.done():
+    ///     ...
+    /// ```
+    fn done(&self) -> bool {
+        *self.state.finished.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits until the compaction is completed or canceled, at most `timeout` seconds. Returns
+    /// true if the compaction is done.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// compaction.wait()
+    ///
+    /// done = compaction.wait(timeout=60)
+    /// ```
+    #[pyo3(signature = (timeout=None))]
+    fn wait(&mut self, py: Python, timeout: Option<f64>) -> bool {
+        let state = &self.state;
+
+        let finished = py.allow_threads(|| {
+            let finished = state.finished.lock().unwrap_or_else(|e| e.into_inner());
+
+            match timeout {
+                Some(timeout) => {
+                    let timeout = Duration::from_secs_f64(timeout.max(0.0));
+
+                    *state
+                        .condvar
+                        .wait_timeout_while(finished, timeout, |finished| !*finished)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+                None => *state
+                    .condvar
+                    .wait_while(finished, |finished| !*finished)
+                    .unwrap_or_else(|e| e.into_inner()),
+            }
+        });
+
+        if finished {
+            if let Some(handle) = self.handle.take() {
+                let _ = handle.join();
+            }
+        }
+
+        finished
+    }
+
+    /// Cancels the compaction, the running chunk is completed first. Call `wait` to wait
+    /// until it stops.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// compaction.cancel()
+    /// compaction.wait()
+    /// ```
+    fn cancel(&self) {
+        self.state.canceled.store(true, Ordering::Relaxed);
+    }
+
+    /// True if the compaction is canceled.
+    #[getter]
+    fn canceled(&self) -> bool {
+        self.state.canceled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of the compacted chunks and the number of all chunks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// done, total = compaction.progress()
+    /// ```
+    fn progress(&self) -> (usize, usize) {
+        (
+            self.state.done.load(Ordering::Relaxed),
+            self.state.total.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl CompactionPy {
+    /// Starts the compaction of the range of the column family. The progress callback is
+    /// called with the number of the compacted chunks and the number of all chunks after every
+    /// chunk.
+    pub fn start(
+        db: &Arc<DB>,
+        lock: &WriteLock,
+        start: Option<Vec<u8>>,
+        stop: Option<Vec<u8>>,
+        cf: Option<String>,
+        progress: Option<PyObject>,
+    ) -> PyResult<CompactionPy> {
+        if let Some(cf) = &cf {
+            cf_handle(db, cf)?;
+        }
+
+        let name = cf.as_deref().unwrap_or(rocksdb::DEFAULT_COLUMN_FAMILY_NAME);
+        let (lower, upper) = (start.as_deref(), stop.as_deref());
+        let points = split_points(db, name, lower, upper, COMPACTION_CHUNKS)?;
+
+        let mut lower = start;
+        let mut chunks = Vec::with_capacity(points.len() + 1);
+
+        for point in points {
+            chunks.push((lower, Some(point.clone())));
+
+            lower = Some(point);
+        }
+
+        chunks.push((lower, stop));
+
+        let state = Arc::new(State::default());
+
+        state.total.store(chunks.len(), Ordering::Relaxed);
+
+        let db = db.clone();
+        let lock = lock.clone();
+        let shared = state.clone();
+
+        let handle = thread::spawn(move || {
+            for (lower, upper) in chunks.iter() {
+                if shared.canceled.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                {
+                    let _guard = lock.shared_blocking();
+
+                    let (lower, upper) = (lower.as_deref(), upper.as_deref());
+
+                    match cf.as_deref() {
+                        Some(cf) => match db.cf_handle(cf) {
+                            Some(cf) => db.compact_range_cf(&cf, lower, upper),
+                            // the column family is dropped, so nothing is left to compact
+                            None => break,
+                        },
+                        None => db.compact_range(lower, upper),
+                    }
+                }
+
+                let done = shared.done.fetch_add(1, Ordering::Relaxed) + 1;
+
+                if let Some(progress) = &progress {
+                    Python::with_gil(|py| {
+                        if let Err(e) = progress.call1(py, (done, chunks.len())) {
+                            e.print(py);
+                        }
+                    });
+                }
+            }
+
+            *shared.finished.lock().unwrap_or_else(|e| e.into_inner()) = true;
+
+            shared.condvar.notify_all();
+        });
+
+        Ok(CompactionPy {
+            state,
+            handle: Some(handle),
+        })
+    }
+}
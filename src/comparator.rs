@@ -0,0 +1,147 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use rocksdb::Options;
+use std::cmp::Ordering;
+
+/// Part of a key compared by a composite comparator.
+#[derive(Clone, Copy)]
+enum Component {
+    /// Unsigned integer of the width, big-endian or little-endian.
+    Uint(usize, bool),
+    /// Rest of the key bytewise.
+    Bytes,
+    /// Rest of the key ignoring the ASCII case.
+    AsciiCi,
+}
+
+/// Name of the builtin reverse bytewise comparator of RocksDB, other RocksDB tools can open
+/// the database.
+const REVERSE: &str = "rocksdb.ReverseBytewiseComparator";
+
+/// Sets the comparator which orders the keys bytewise from the largest to the smallest.
+pub fn set_reverse_comparator(opts: &mut Options) {
+    opts.set_comparator(REVERSE, Box::new(|a: &[u8], b: &[u8]| b.cmp(a)));
+}
+
+/// Sets the comparator which orders the keys by a Python function, the function returns a
+/// negative number, zero or a positive number like the `cmp` functions.
+pub fn set_python_comparator(opts: &mut Options, name: &str, compare: PyObject) {
+    opts.set_comparator(name, Box::new(move |a, b| compare_py(&compare, a, b)));
+}
+
+/// Sets the comparator which orders the keys by their components, e.g. `['u32be', 'bytes']`.
+pub fn set_composite_comparator(
+    opts: &mut Options,
+    name: &str,
+    components: &[String],
+) -> PyResult<()> {
+    let components = parse(components)?;
+
+    opts.set_comparator(name, Box::new(move |a, b| compare_components(&components, a, b)));
+
+    Ok(())
+}
+
+fn compare_py(compare: &PyObject, a: &[u8], b: &[u8]) -> Ordering {
+    Python::with_gil(|py| {
+        let result = compare
+            .call1(py, (PyBytes::new(py, a), PyBytes::new(py, b)))
+            .and_then(|n| n.extract::<i64>(py));
+
+        match result {
+            Ok(n) => n.cmp(&0),
+            Err(e) => {
+                // RocksDB cannot handle a failed comparison, the bytewise order keeps the
+                // order consistent
+                e.print(py);
+
+                a.cmp(b)
+            }
+        }
+    })
+}
+
+fn parse(components: &[String]) -> PyResult<Vec<Component>> {
+    let mut parsed = Vec::with_capacity(components.len());
+
+    for (i, component) in components.iter().enumerate() {
+        let c = match component.as_str() {
+            "u16be" => Component::Uint(2, true),
+            "u16le" => Component::Uint(2, false),
+            "u32be" => Component::Uint(4, true),
+            "u32le" => Component::Uint(4, false),
+            "u64be" => Component::Uint(8, true),
+            "u64le" => Component::Uint(8, false),
+            "bytes" => Component::Bytes,
+            "ascii_ci" => Component::AsciiCi,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Comparator is not supported, {}",
+                    other
+                )))
+            }
+        };
+
+        // the variable-length components take the rest of the key
+        if !matches!(c, Component::Uint(..)) && i + 1 != components.len() {
+            return Err(PyValueError::new_err(format!(
+                "Comparator is not supported, {} must be the last component",
+                component
+            )));
+        }
+
+        parsed.push(c);
+    }
+
+    Ok(parsed)
+}
+
+fn compare_components(components: &[Component], mut a: &[u8], mut b: &[u8]) -> Ordering {
+    for component in components {
+        let ordering = match *component {
+            Component::Uint(width, big_endian) => {
+                // keys shorter than the component are ordered before the others and bytewise
+                // among themselves, so the order stays transitive for the little-endian widths
+                match (a.len() < width, b.len() < width) {
+                    (true, true) => return a.cmp(b),
+                    (true, false) => return Ordering::Less,
+                    (false, true) => return Ordering::Greater,
+                    (false, false) => {}
+                }
+
+                let (x, y) = (uint(&a[..width], big_endian), uint(&b[..width], big_endian));
+
+                a = &a[width..];
+                b = &b[width..];
+
+                x.cmp(&y)
+            }
+            Component::Bytes => return a.cmp(b),
+            Component::AsciiCi => {
+                // the keys which only differ by the case are still distinct keys
+                let folded = a.iter().map(u8::to_ascii_lowercase);
+
+                return folded
+                    .cmp(b.iter().map(u8::to_ascii_lowercase))
+                    .then_with(|| a.cmp(b));
+            }
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    a.cmp(b)
+}
+
+fn uint(bytes: &[u8], big_endian: bool) -> u64 {
+    let fold = |n: u64, byte: &u8| n << 8 | *byte as u64;
+
+    if big_endian {
+        bytes.iter().fold(0, fold)
+    } else {
+        bytes.iter().rev().fold(0, fold)
+    }
+}
@@ -0,0 +1,230 @@
+use crate::base::*;
+use crate::codec::*;
+use crate::iterator::*;
+use crate::ttl::*;
+use pyo3::prelude::*;
+use rocksdb::Direction;
+use std::sync::Arc;
+
+/// Cursor over the database which can be repositioned.
+#[pyclass(name = "Cursor")]
+pub struct CursorPy {
+    inner: Option<DBRawIterator<'static>>,
+    expiry: bool,
+    codec: Option<Codec>,
+    text: bool,
+    _db: Arc<DB>,
+}
+
+#[pymethods]
+impl CursorPy {
+    /// Moves the cursor to the first key at or after the given key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor.seek(b'key')
+    /// ```
+    fn seek(&mut self, key: &PyAny) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+
+        self.raw()?.seek(key.as_bytes());
+
+        self.skip(Direction::Forward)
+    }
+
+    /// Moves the cursor to the last key at or before the given key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor.seek_for_prev(b'key')
+    /// ```
+    fn seek_for_prev(&mut self, key: &PyAny) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+
+        self.raw()?.seek_for_prev(key.as_bytes());
+
+        self.skip(Direction::Reverse)
+    }
+
+    /// Moves the cursor to the first key of the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor.seek_to_first()
+    /// ```
+    fn seek_to_first(&mut self) -> PyResult<()> {
+        self.raw()?.seek_to_first();
+
+        self.skip(Direction::Forward)
+    }
+
+    /// Moves the cursor to the last key of the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor.seek_to_last()
+    /// ```
+    fn seek_to_last(&mut self) -> PyResult<()> {
+        self.raw()?.seek_to_last();
+
+        self.skip(Direction::Reverse)
+    }
+
+    /// Moves the cursor to the next key. The cursor must be valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor.next()
+    /// ```
+    fn next(&mut self) -> PyResult<()> {
+        let raw = self.valid_raw()?;
+
+        raw.next();
+
+        self.skip(Direction::Forward)
+    }
+
+    /// Moves the cursor to the previous key. The cursor must be valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor.prev()
+    /// ```
+    fn prev(&mut self) -> PyResult<()> {
+        let raw = self.valid_raw()?;
+
+        raw.prev();
+
+        self.skip(Direction::Reverse)
+    }
+
+    /// Returns true if the cursor is positioned at an entry. An error is raised if the cursor
+    /// became invalid because of a failure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// while cursor.valid():
+    ///     cursor.next()
+    /// ```
+    fn valid(&mut self) -> PyResult<bool> {
+        let raw = self.raw()?;
+
+        if raw.valid() {
+            return Ok(true);
+        }
+
+        match raw.status() {
+            Ok(()) => Ok(false),
+            Err(e) => Err(rocksdb_error(&e, format!("Cursor is invalid. {}", e))),
+        }
+    }
+
+    /// Returns the key of the current entry, `None` if the cursor is not valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// key = cursor.key()
+    /// ```
+    fn key<'py>(&mut self, py: Python<'py>) -> PyResult<Option<&'py PyAny>> {
+        let text = self.text;
+
+        self.raw()?.key().map(|k| decode_key(py, text, k)).transpose()
+    }
+
+    /// Returns the value of the current entry, `None` if the cursor is not valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = cursor.value()
+    /// ```
+    fn value(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        let expiry = self.expiry;
+        let codec = self.codec.clone();
+
+        match self.raw()?.value().map(|v| load(expiry, v)) {
+            Some(Some(v)) => Ok(Some(decode_value(py, &codec, v)?)),
+            // the value expired after the cursor moved to it
+            Some(None) => Err(RocksDBPyException::new_err(
+                "Value cannot read, the record is expired",
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Close and destroy active cursor
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor.close()
+    /// ```
+    fn close(&mut self) -> PyResult<()> {
+        self.inner = None;
+
+        Ok(())
+    }
+}
+
+impl CursorPy {
+    /// Creates a cursor whose values are decoded by the codec.
+    pub fn new(db: &Arc<DB>, opts: IteratorOptions, codec: Option<Codec>) -> CursorPy {
+        CursorPy {
+            inner: Some(raw_iterator(db, &opts)),
+            expiry: opts.expiry,
+            codec,
+            text: false,
+            _db: db.clone(),
+        }
+    }
+
+    /// Accepts `str` keys and returns the keys as `str` if the text mode is enabled.
+    pub fn text(mut self, enabled: bool) -> CursorPy {
+        self.text = enabled;
+
+        self
+    }
+
+    /// Moves the cursor over the expired entries by the direction of the last move.
+    #[inline]
+    fn skip(&mut self, direction: Direction) -> PyResult<()> {
+        let expiry = self.expiry;
+
+        skip_expired(self.raw()?, direction, expiry);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn raw(&mut self) -> PyResult<&mut DBRawIterator<'static>> {
+        match &mut self.inner {
+            Some(inner) => Ok(inner),
+            None => Err(RocksDBPyException::new_err("Cursor is closed")),
+        }
+    }
+
+    #[inline]
+    fn valid_raw(&mut self) -> PyResult<&mut DBRawIterator<'static>> {
+        let raw = self.raw()?;
+
+        if raw.valid() {
+            Ok(raw)
+        } else {
+            Err(RocksDBPyException::new_err("Cursor is not positioned"))
+        }
+    }
+}
+
+impl Drop for CursorPy {
+    fn drop(&mut self) {
+        self.inner = None
+    }
+}
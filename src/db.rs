@@ -1,396 +1,4013 @@
+use crate::aio::*;
+use crate::backup::*;
 use crate::base::*;
 use crate::batch::*;
+use crate::buffer::*;
+use crate::codec::*;
+use crate::compaction::*;
+use crate::dump::*;
+use crate::indexed::*;
+use crate::cursor::*;
+use crate::entity::*;
 use crate::iterator::*;
+use crate::lock::*;
+use crate::merge::*;
+use crate::namespace::*;
+use crate::option::*;
+use crate::pinned::*;
+use crate::queue::*;
+use crate::record::*;
+use crate::scan::*;
+use crate::sst::*;
+use crate::timestamp::*;
+use crate::ttl::*;
+use crate::worker::*;
+use crate::writer::*;
+use pyo3::exceptions::{PyKeyError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyList};
-use rocksdb::{Direction, IteratorMode, DB};
-use rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
-use std::sync::Arc;
-use std::path::Path;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use rocksdb::{
+    BoundColumnFamily, DBPinnableSlice, Direction, IngestExternalFileOptions, LiveFile, Options,
+    ReadOptions, WaitForCompactOptions, WriteBatch, WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
+};
+use rocksdb::backup::{BackupEngine, BackupEngineOptions};
+use rocksdb::checkpoint::Checkpoint;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::{fs, mem, process, thread};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Size of the batches which write the records of a dump.
+const LOAD_BATCH_SIZE: usize = 4 << 20;
+
+/// Interval of the checks of the memtable size when the auto flush has a size limit.
+const AUTO_FLUSH_POLL: Duration = Duration::from_millis(100);
+
+/// Default interval of the checks of the write stall state.
+const STALL_POLL: Duration = Duration::from_millis(100);
+
+/// Number of the records which `verify` reads at once without the GIL.
+const VERIFY_BATCH: usize = 1024;
 
 /// Base RocksDB database.
 #[pyclass(name = "RocksDB")]
 pub struct DBPy {
-    pub path: Vec<u8>,
+    pub path: PathBuf,
     pub db: Option<Arc<DB>>,
+    opts: Options,
+    readonly: bool,
+    codec: Option<Codec>,
+    text: bool,
+    expiry: bool,
+    lock: WriteLock,
+    stall_watcher: Mutex<Option<Worker>>,
+    /// Flushes the memtables in the background, see `auto_flush`.
+    auto_flusher: Mutex<Option<Worker>>,
+    /// Applies the updates of the primary to a secondary, shared by the handles.
+    catch_up: Option<Arc<Worker>>,
+    /// The path of the primary if the database is a secondary.
+    primary: Option<PathBuf>,
+    /// The process which opened the database, a forked child must not use it.
+    pid: u32,
+    /// Bounds of the live entries of the queues, shared by the handles.
+    queues: QueueHints,
 }
 
 #[pymethods]
 impl DBPy {
-    /// Return the value associated with a "key".
+    /// Opens the database for read only. The database is not locked, so it can be opened
+    /// while another process writes to it, and it cannot be changed by the returned handle.
+    /// The writes of the other process after the open are not visible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db = RocksDB.open_for_read_only('/tmp/rocksdb')
+    ///
+    /// db = RocksDB.open_for_read_only('/tmp/rocksdb', error_if_log_file_exists=True)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (path, error_if_log_file_exists=None, option=None, codec=None, text=None))]
+    fn open_for_read_only(
+        path: PathBuf,
+        error_if_log_file_exists: Option<bool>,
+        option: Option<OptionPy>,
+        codec: Option<&PyAny>,
+        text: Option<bool>,
+    ) -> PyResult<DBPy> {
+        open_for_readonly(path, option, error_if_log_file_exists, codec, text)
+    }
+
+    /// Opens the database as a secondary instance of the primary, the secondary keeps its own
+    /// logs at `secondary_path`. If `catch_up_interval` is given, a background thread catches
+    /// up with the primary every `catch_up_interval` seconds, otherwise the updates of the
+    /// primary are applied by `try_catch_up_with_primary`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db = RocksDB.open_as_secondary('/tmp/rocksdb', '/tmp/secondary')
+    ///
+    /// db = RocksDB.open_as_secondary('/tmp/rocksdb', '/tmp/secondary', catch_up_interval=1.0)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (
+        primary_path,
+        secondary_path,
+        catch_up_interval=None,
+        option=None,
+        codec=None,
+        text=None,
+    ))]
+    fn open_as_secondary(
+        primary_path: PathBuf,
+        secondary_path: PathBuf,
+        catch_up_interval: Option<f64>,
+        option: Option<OptionPy>,
+        codec: Option<&PyAny>,
+        text: Option<bool>,
+    ) -> PyResult<DBPy> {
+        open_as_secondary(primary_path, secondary_path, option, codec, text, catch_up_interval)
+    }
+
+    /// Opens the database with TTL, the records which are older than `ttl_seconds` are removed
+    /// by the compactions. The column families of `cf_ttls` expire their records by their own
+    /// TTL, they are created if they are missing. The database is created if it is missing
+    /// unless the options are given.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db = RocksDB.open_with_ttl('/tmp/rocksdb', 3600)
+    ///
+    /// db = RocksDB.open_with_ttl('/tmp/rocksdb', 3600, cf_ttls={'sessions': 60})
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (
+        path,
+        ttl_seconds,
+        option=None,
+        cf_ttls=None,
+        codec=None,
+        text=None,
+        wait_for_lock=None,
+    ))]
+    fn open_with_ttl(
+        py: Python,
+        path: PathBuf,
+        ttl_seconds: u64,
+        option: Option<OptionPy>,
+        cf_ttls: Option<HashMap<String, u64>>,
+        codec: Option<&PyAny>,
+        text: Option<bool>,
+        wait_for_lock: Option<f64>,
+    ) -> PyResult<DBPy> {
+        let option = option.unwrap_or_else(|| {
+            let mut inner = Options::default();
+
+            inner.create_if_missing(true);
+
+            OptionPy {
+                inner,
+                key_ttl: false,
+            }
+        });
+
+        open_with_ttl(py, path, ttl_seconds, &option, codec, text, cf_ttls, wait_for_lock)
+    }
+
+    /// The path of the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// path = db.path
+    /// ```
+    #[getter]
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// True until the database is closed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if db.is_open:
+    ///     db.close()
+    /// ```
+    #[getter]
+    fn is_open(&self) -> bool {
+        self.db.is_some()
+    }
+
+    /// True if the database is opened for read only or as a secondary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if not db.read_only:
+    ///     db.set(b'key', b'value')
+    /// ```
+    #[getter(read_only)]
+    fn is_read_only(&self) -> bool {
+        self.readonly
+    }
+
+    /// Return the value associated with a "key". The value is decoded if the database is
+    /// opened with a codec.
     ///
     /// # Example
     ///
     /// ```
     /// value = db.get(b'key')
+    ///
+    /// value = db.get(bytearray(b'key'))
+    ///
+    /// value = db.get(b'key', cf='users')
     /// ```
-    fn get<'py>(&self, py: Python<'py>, key: &PyBytes) -> PyResult<Option<&'py PyBytes>> {
-        if let Some(db) = &self.db {
-            match db.get(key.as_bytes()) {
+    #[pyo3(signature = (key, cf=None))]
+    pub(crate) fn get(
+        &self,
+        py: Python,
+        key: &PyAny,
+        cf: Option<&str>,
+    ) -> PyResult<Option<PyObject>> {
+        let key = encode_key(self.text, key)?;
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+
+            let value = py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.get_cf(&cf, key)),
+                None => Ok(db.get(key)),
+            })?;
+
+            match value {
                 Ok(None) => Ok(None),
-                Ok(Some(value)) => Ok(Some(PyBytes::new(py, &value))),
-                Err(e) => Err(RocksDBPyException::new_err(format!(
-                    "Record cannot get. {}",
-                    e
-                ))),
+                Ok(Some(value)) => match load(self.expiry, &value) {
+                    Some(value) => Ok(Some(decode_value(py, &self.codec, value)?)),
+                    None => Ok(None),
+                },
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot get. {}", e))),
             }
         } else {
-            Err(RocksDBPyException::new_err("Record cannot get"))
+            Err(DBClosedError::new_err("Record cannot get, database is closed"))
         }
     }
 
-    /// Sets records by "key" and "value".
+    /// Return the value associated with a "key" as a memoryview over the pinned slice of
+    /// RocksDB, so the value is not copied. The slice is released when the memoryview is
+    /// garbage collected.
     ///
-    /// # Example
+    /// # Example
     ///
     /// ```
-    /// db.set(b'key', b'value')
+    /// value = db.get_pinned(b'key')
     /// ```
-    fn set(&mut self, key: &PyBytes, value: &PyBytes) -> PyResult<()> {
-        if let Some(db) = &self.db {
-            match db.put(key.as_bytes(), value.as_bytes()) {
-                Ok(()) => Ok(()),
-                Err(e) => Err(RocksDBPyException::new_err(format!(
-                    "Record cannot set. {}",
-                    e
-                ))),
+    fn get_pinned(&self, py: Python, key: &PyAny) -> PyResult<Option<PyObject>> {
+        let key = encode_key(self.text, key)?;
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+
+            match py.allow_threads(|| db.get_pinned(key)) {
+                Ok(None) => Ok(None),
+                Ok(Some(value)) if load(self.expiry, &value).is_none() => Ok(None),
+                Ok(Some(value)) => {
+                    let value: DBPinnableSlice<'static> = unsafe { std::mem::transmute(value) };
+
+                    // the slice is owned by RocksDB, the database is kept open until it is released
+                    let mut pinned = unsafe { PinnedSlicePy::owned(value, db.clone()) };
+
+                    if self.expiry {
+                        pinned = pinned.advance(HEADER);
+                    }
+
+                    Ok(Some(memoryview(py, pinned)?))
+                }
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot get. {}", e))),
             }
         } else {
-            Err(RocksDBPyException::new_err("Record cannot set"))
+            Err(DBClosedError::new_err("Record cannot get, database is closed"))
         }
     }
 
-    /// Removes existing records by "key".
+    /// Sets records by "key" and "value".
     ///
     /// # Example
     ///
     /// ```
-    /// db.delete(b'key')
+    /// db.set(b'key', b'value')
+    ///
+    /// db.set(b'key', memoryview(b'value'))
+    ///
+    /// db.set(b'key', b'value', ttl=3600)
+    ///
+    /// db.set(b'key', b'value', cf='users')
     /// ```
-    fn delete(&mut self, key: &PyBytes) -> PyResult<()> {
-        if let Some(db) = &self.db {
-            match db.delete(key.as_bytes()) {
+    #[pyo3(signature = (key, value, ttl=None, cf=None))]
+    pub(crate) fn set(
+        &self,
+        py: Python,
+        key: &PyAny,
+        value: &PyAny,
+        ttl: Option<u64>,
+        cf: Option<&str>,
+    ) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+        let value = encode_value(py, &self.codec, value)?;
+        let value = self.store(value.as_bytes(), ttl)?;
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let _guard = self.lock.shared(py);
+
+            let result = py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.put_cf(&cf, key, &value)),
+                None => Ok(db.put(key, &value)),
+            })?;
+
+            match result {
                 Ok(()) => Ok(()),
-                Err(e) => Err(RocksDBPyException::new_err(format!(
-                    "Record cannot remove. {}",
-                    e
-                ))),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot set. {}", e))),
             }
         } else {
-            Err(RocksDBPyException::new_err("Record cannot remove"))
+            Err(DBClosedError::new_err("Record cannot set, database is closed"))
         }
     }
 
-    /// Sets database entries for list of key and values as a batch.
+    /// Removes existing records by "key", the entity of the key is removed as well.
     ///
     /// # Example
     ///
     /// ```
-    /// b = WriteBatch()
-    /// b.add(b'first', 'first_value')
-    /// b.add(b'second', 'second_value')
+    /// db.delete(b'key')
     ///
-    /// db.write(b)
+    /// db.delete(b'key', cf='users')
     /// ```
-    fn write(&self, batch: &mut WriteBatchPy) -> PyResult<()> {
-        let wr = batch.get().unwrap();
-        let len = wr.len();
+    #[pyo3(signature = (key, cf=None))]
+    pub(crate) fn delete(&self, py: Python, key: &PyAny, cf: Option<&str>) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
 
-        if let Some(db) = &self.db {
-            match db.write(wr) {
-                Ok(_) => Ok(()),
-                Err(e) => Err(RocksDBPyException::new_err(format!(
-                    "Batch cannot write {} elements. {}",
-                    len, e,
-                ))),
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let _guard = self.lock.shared(py);
+
+            let result = py.allow_threads(|| delete_with_entity(db, cf, key))?;
+
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot remove. {}", e))),
             }
         } else {
-            Err(RocksDBPyException::new_err(format!(
-                "Batch cannot write {} elements",
-                len
-            )))
+            Err(DBClosedError::new_err("Record cannot remove, database is closed"))
         }
     }
 
-    /// Returns entries according to given list of key and values.
+    /// Sets records by "key" and "value" at the user timestamp "ts". The database must be
+    /// opened with `opts.enable_user_timestamp()`.
     ///
-    /// # Example
+    /// # Example
     ///
     /// ```
-    /// db.multi_get(b'first', b'second')
+    /// db.set_with_ts(b'key', b'value', 100)
     ///
-    /// db.multi_get(b'first', b'second', skip_missings=True)
+    /// db.set_with_ts(b'key', b'value', 100, cf='users')
     /// ```
-    fn multi_get<'py>(
-        &mut self,
-        py: Python<'py>,
-        keys: &'py PyList,
-        skip_missings: Option<bool>,
-    ) -> PyResult<&'py PyList> {
-        // generate list of keys based on Python's list
-        let ks: Vec<&[u8]> = keys
-            .iter()
-            .map(|k| <PyBytes as PyTryFrom>::try_from(k).unwrap().as_bytes())
-            .collect();
+    #[pyo3(signature = (key, value, ts, cf=None))]
+    fn set_with_ts(
+        &self,
+        py: Python,
+        key: &PyAny,
+        value: &PyAny,
+        ts: u64,
+        cf: Option<&str>,
+    ) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+        let value = encode_value(py, &self.codec, value)?;
+        let value = self.store(value.as_bytes(), None)?;
 
-        let r = PyList::empty(py);
-        let skip = skip_missings.is_none() || skip_missings.unwrap() == false;
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let ts = encode_ts(ts);
+            let _guard = self.lock.shared(py);
 
-        if let Some(db) = &self.db {
-            for value in db.multi_get(ks) {
-                match value {
-                    Ok(v) => match v {
-                        Some(item) => r.append(PyBytes::new(py, item.as_ref())).unwrap(),
-                        None => {
-                            // skip missing records if skip_missings is true, the output
-                            // array will be shorter then given key array size.
-                            if skip {
-                                r.append(py.None()).unwrap()
-                            } else {
-                                continue;
-                            }
-                        }
-                    },
-                    Err(e) => {
-                        return Err(RocksDBPyException::new_err(format!(
-                            "Record cannot get. {}",
-                            e,
-                        )))
-                    }
-                }
+            let result = py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.put_cf_with_ts(&cf, key, ts, &value)),
+                None => Ok(db.put_with_ts(key, ts, &value)),
+            })?;
+
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot set. {}", e))),
             }
+        } else {
+            Err(DBClosedError::new_err("Record cannot set, database is closed"))
         }
-
-        Ok(r)
     }
 
-    /// Returns a heap-allocated iterator over the contents of the database.
+    /// Return the value associated with a "key" as of the user timestamp "ts", the newest
+    /// value set at or before the timestamp.
     ///
-    /// # Example
+    /// # Example
     ///
     /// ```
-    /// iterator = db.iterator()
+    /// value = db.get_with_ts(b'key', 100)
     ///
-    /// iterator = db.iterator(mode='end')
-    ///
-    /// iterator = db.iterator(mode='from', key=b'test')
-    ///
-    /// iterator = db.iterator(mode='from', key=b'test', direction=-1)
+    /// value = db.get_with_ts(b'key', 100, cf='users')
     /// ```
-    fn iterator(
+    #[pyo3(signature = (key, ts, cf=None))]
+    fn get_with_ts(
         &self,
-        mode: Option<&str>,
-        key: Option<&PyBytes>,
-        direction: Option<i32>,
-    ) -> PyResult<IteratorPy> {
-        let mut im = IteratorMode::Start;
+        py: Python,
+        key: &PyAny,
+        ts: u64,
+        cf: Option<&str>,
+    ) -> PyResult<Option<PyObject>> {
+        let key = encode_key(self.text, key)?;
 
-        if !mode.is_none() {
-            let mut ik: &[u8] = b"";
-            let mut dr = Direction::Forward;
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
 
-            if !key.is_none() {
-                ik = key.unwrap().as_bytes();
-            }
+            let value = py.allow_threads(|| {
+                let opts = read_options_at(ts);
 
-            // Generate direction by minus or plus integer
-            if !key.is_none() && !direction.is_none() {
-                dr = match direction.unwrap() {
-                    -1 => Direction::Reverse,
-                    _ => Direction::Forward,
+                match cf {
+                    Some(cf) => cf_handle(db, cf).map(|cf| db.get_cf_opt(&cf, key, &opts)),
+                    None => Ok(db.get_opt(key, &opts)),
                 }
-            }
+            })?;
 
-            im = match mode.unwrap() {
-                "end" => IteratorMode::End,
-                "from" => IteratorMode::From(ik, dr),
-                _ => IteratorMode::Start,
+            match value {
+                Ok(None) => Ok(None),
+                Ok(Some(value)) => match load(self.expiry, &value) {
+                    Some(value) => Ok(Some(decode_value(py, &self.codec, value)?)),
+                    None => Ok(None),
+                },
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot get. {}", e))),
             }
-        }
-
-        if let Some(db) = &self.db {
-            Ok(IteratorPy::new(db.as_ref(), im))
         } else {
-            Err(RocksDBPyException::new_err("Iterator cannot get"))
+            Err(DBClosedError::new_err("Record cannot get, database is closed"))
         }
     }
 
-    /// Request stopping background work, if wait is true wait until it’s done.
+    /// Removes existing records by "key" at the user timestamp "ts", reads as of an older
+    /// timestamp still see the previous value.
     ///
     /// # Example
     ///
     /// ```
-    /// db.cancel_all_background_work()
+    /// db.delete_with_ts(b'key', 200)
     ///
-    /// db.cancel_all_background_work(True)
+    /// db.delete_with_ts(b'key', 200, cf='users')
     /// ```
-    fn cancel_all_background_work(&self, wait: Option<bool>) -> PyResult<()> {
-        let mut w = false;
+    #[pyo3(signature = (key, ts, cf=None))]
+    fn delete_with_ts(&self, py: Python, key: &PyAny, ts: u64, cf: Option<&str>) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
 
-        if wait.is_some() {
-            w = wait.unwrap()
-        }
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let ts = encode_ts(ts);
+            let _guard = self.lock.shared(py);
 
-        if let Some(db) = &self.db {
-            db.cancel_all_background_work(w);
+            let result = py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.delete_cf_with_ts(&cf, key, ts)),
+                None => Ok(db.delete_with_ts(key, ts)),
+            })?;
 
-            Ok(())
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot remove. {}", e))),
+            }
         } else {
-            Err(RocksDBPyException::new_err("Cancel cannot do"))
+            Err(DBClosedError::new_err("Record cannot remove, database is closed"))
         }
     }
 
-    /// Flushes database memtables to SST files on the disk using default options.
+    /// Raises the lowest user timestamp whose history is kept, compactions may drop the
+    /// versions of a key which are older than it. Reads as of an older timestamp fail.
     ///
     /// # Example
     ///
     /// ```
-    /// db.flush()
+    /// db.increase_full_history_ts_low(100)
+    ///
+    /// db.increase_full_history_ts_low(100, cf='users')
     /// ```
-    fn flush(&self) -> PyResult<()> {
-        if let Some(db) = &self.db {
-            match db.flush() {
-                Ok(_) => Ok(()),
-                Err(e) => Err(RocksDBPyException::new_err(format!(
-                    "Database cannot flush. {}",
-                    e,
-                ))),
+    #[pyo3(signature = (ts, cf=None))]
+    fn increase_full_history_ts_low(&self, py: Python, ts: u64, cf: Option<&str>) -> PyResult<()> {
+        if let Some(db) = self.inner()? {
+            let ts = encode_ts(ts);
+            let _guard = self.lock.shared(py);
+
+            let result = py.allow_threads(|| {
+                let cf = cf_handle(db, cf.unwrap_or(DEFAULT_COLUMN_FAMILY_NAME))?;
+
+                Ok::<_, PyErr>(db.increase_full_history_ts_low(&cf, ts))
+            })?;
+
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("History cannot increase. {}", e))),
             }
         } else {
-            Err(RocksDBPyException::new_err("Database cannot flush"))
+            Err(DBClosedError::new_err("History cannot increase, database is closed"))
         }
     }
 
-    /// Try to catch up with the primary by applying all the oplog entries.
-    /// This function is only useful for secondary instances.
+    /// Returns the lowest user timestamp whose history is kept.
     ///
     /// # Example
+    ///
     /// ```
-    /// db.try_catch_up_with_primary()
+    /// ts = db.get_full_history_ts_low()
+    ///
+    /// ts = db.get_full_history_ts_low(cf='users')
     /// ```
-    fn try_catch_up_with_primary(&self) -> PyResult<()> {
-        if let Some(db) = &self.db {
-            match db.try_catch_up_with_primary() {
-                Ok(_) => Ok(()),
-                Err(e) => Err(RocksDBPyException::new_err(format!(
-                    "Database cannot catch up with primary. {}",
-                    e,
-                ))),
+    #[pyo3(signature = (cf=None))]
+    fn get_full_history_ts_low(&self, py: Python, cf: Option<&str>) -> PyResult<u64> {
+        if let Some(db) = self.inner()? {
+            let result = py.allow_threads(|| {
+                let cf = cf_handle(db, cf.unwrap_or(DEFAULT_COLUMN_FAMILY_NAME))?;
+
+                Ok::<_, PyErr>(db.get_full_history_ts_low(&cf))
+            })?;
+
+            match result {
+                // a column family without history trimming has no timestamp yet
+                Ok(ts) if ts.is_empty() => Ok(0),
+                Ok(ts) => decode_ts(&ts),
+                Err(e) => Err(rocksdb_error(&e, format!("History cannot get. {}", e))),
             }
         } else {
-            Err(RocksDBPyException::new_err("Database cannot catch up with primary"))
+            Err(DBClosedError::new_err("History cannot get, database is closed"))
         }
     }
 
-    /// Creates a consistent backup of the currently opened database at the given path.
-    ///
-    /// This method flushes memtables and stores a snapshot of the database in backup format,
-    /// which can later be restored using `RocksDB.restore_latest_backup(...)`.
+    /// Removes the "key" and its entity and returns its value, or the default if the key is
+    /// missing. No other write of the database happens between the read and the delete.
     ///
     /// # Example
     ///
     /// ```
-    /// db.create_backup("/path/to/backup")
+    /// value = db.pop(b'key')
+    ///
+    /// value = db.pop(b'key', b'default')
     /// ```
-    fn create_backup(&self, backup_path: &str) -> PyResult<()> {
-        if let Some(db) = &self.db {
-            let mut backup_opts = match BackupEngineOptions::new(backup_path) {
-                Ok(opts) => opts,
-                Err(e) => {
-                    return Err(RocksDBPyException::new_err(format!(
-                        "Failed to create backup options: {}",
-                        e
-                    )))
-                }
-            };
+    #[pyo3(signature = (key, default=None))]
+    fn pop(
+        &self,
+        py: Python,
+        key: &PyAny,
+        default: Option<PyObject>,
+    ) -> PyResult<Option<PyObject>> {
+        let key = encode_key(self.text, key)?;
 
-            let env = rocksdb::Env::new().map_err(|e| {
-                RocksDBPyException::new_err(format!("Failed to create Env: {}", e))
-            })?;
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let _guard = self.lock.exclusive(py);
 
-            let mut engine = match BackupEngine::open(&backup_opts, &env) {
-                Ok(engine) => engine,
+            let stored = match py.allow_threads(|| db.get(key)) {
+                Ok(stored) => stored,
                 Err(e) => {
-                    return Err(RocksDBPyException::new_err(format!(
-                        "Failed to open backup engine: {}",
-                        e
-                    )))
+                    return Err(rocksdb_error(&e, format!("Record cannot pop. {}", e)))
                 }
             };
 
-            if let Err(e) = engine.create_new_backup_flush(db, true) {
-                return Err(RocksDBPyException::new_err(format!(
-                    "Failed to create backup: {}",
-                    e
-                )));
+            let value = stored.as_deref().and_then(|v| load(self.expiry, v));
+            let mut wr = WriteBatch::default();
+
+            wr.delete(key);
+
+            delete_entity(db, &mut wr, None, key);
+
+            // nothing is written for a missing key without an entity
+            if value.is_none() && wr.len() == 1 {
+                return Ok(default);
             }
 
-            Ok(())
+            match py.allow_threads(|| db.write(wr)) {
+                Ok(()) => match value {
+                    Some(value) => Ok(Some(decode_value(py, &self.codec, value)?)),
+                    None => Ok(default),
+                },
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot pop. {}", e))),
+            }
         } else {
-            Err(RocksDBPyException::new_err("Database is not open"))
+            Err(DBClosedError::new_err("Record cannot pop, database is closed"))
         }
     }
 
-    /// Restores the latest backup from a given backup directory into a new RocksDB instance.
-    ///
-    /// This static method reads the backup metadata and reconstructs the database at the specified path.
-    /// It can be used before opening the database with `open_default(...)`.
+    /// Returns the value of the "key" if it exists, otherwise stores the default and returns
+    /// it. No other write of the database happens between the read and the write.
     ///
     /// # Example
     ///
     /// ```
-    /// RocksDB.restore_latest_backup("/path/to/backup", "/path/to/restore")
-    /// db = RocksDB.open_default("/path/to/restore")
+    /// value = db.get_or_set(b'key', b'default')
     /// ```
-    #[staticmethod]
-    fn restore_latest_backup(backup_path: &str, restore_path: &str) -> PyResult<()> {
-        let backup_opts = match BackupEngineOptions::new(backup_path) {
-            Ok(opts) => opts,
-            Err(e) => {
-                return Err(RocksDBPyException::new_err(format!(
-                    "Failed to create backup options: {}",
-                    e
-                )))
-            }
-        };
+    fn get_or_set(&self, py: Python, key: &PyAny, default: &PyAny) -> PyResult<PyObject> {
+        let key = encode_key(self.text, key)?;
+        let value = encode_value(py, &self.codec, default)?;
+        let value = self.store(value.as_bytes(), None)?;
 
-        let env = rocksdb::Env::new().map_err(|e| {
-            RocksDBPyException::new_err(format!("Failed to create Env: {}", e))
-        })?;
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let _guard = self.lock.exclusive(py);
 
-        let mut engine = match BackupEngine::open(&backup_opts, &env) {
-            Ok(e) => e,
-            Err(e) => {
-                return Err(RocksDBPyException::new_err(format!(
-                    "Failed to open backup engine: {}",
-                    e
-                )))
+            match py.allow_threads(|| db.get(key)) {
+                Ok(Some(existing)) => {
+                    if let Some(existing) = load(self.expiry, &existing) {
+                        return decode_value(py, &self.codec, existing);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    return Err(rocksdb_error(&e, format!("Record cannot get. {}", e)))
+                }
             }
-        };
-
-        let restore_opts = RestoreOptions::default();
-        let path = Path::new(restore_path);
 
-        if let Err(e) = engine.restore_from_latest_backup(path, path, &restore_opts) {
-            return Err(RocksDBPyException::new_err(format!(
-                "Restore failed: {}",
-                e
-            )));
+            match py.allow_threads(|| db.put(key, &value)) {
+                Ok(()) => Ok(default.into_py(py)),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot set. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Record cannot set, database is closed"))
         }
+    }
 
-        Ok(())
+    /// Alias of `get_or_set` like `dict.setdefault`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = db.setdefault(b'key', b'default')
+    /// ```
+    fn setdefault(&self, py: Python, key: &PyAny, default: &PyAny) -> PyResult<PyObject> {
+        self.get_or_set(py, key, default)
     }
 
-    /// Close active database
+    /// Replaces the value of the "key" with `new` only if the current value equals `expected`,
+    /// `None` as `expected` means the key must be missing and `None` as `new` removes the key.
+    /// The values are compared after they are encoded. The new value is set with the `ttl` like
+    /// `set`. Returns whether the value is replaced and the value which was found.
     ///
     /// # Example
     ///
     /// ```
-    /// db.close()
+    /// swapped, actual = db.compare_and_swap(b'key', b'old', b'new')
+    ///
+    /// swapped, actual = db.compare_and_swap(b'lock', None, b'owner', ttl=30)
+    ///
+    /// swapped, actual = db.compare_and_swap(b'key', b'old', b'new', cf='users')
     /// ```
-    fn close(&mut self) -> PyResult<()> {
-        self.db = None;
+    #[pyo3(signature = (key, expected=None, new=None, ttl=None, cf=None))]
+    fn compare_and_swap(
+        &self,
+        py: Python,
+        key: &PyAny,
+        expected: Option<&PyAny>,
+        new: Option<&PyAny>,
+        ttl: Option<u64>,
+        cf: Option<&str>,
+    ) -> PyResult<(bool, Option<PyObject>)> {
+        let key = encode_key(self.text, key)?;
+        let expected = expected.map(|v| encode_value(py, &self.codec, v)).transpose()?;
+        let new = new.map(|v| encode_value(py, &self.codec, v)).transpose()?;
+        let new = new.as_ref().map(|v| self.store(v.as_bytes(), ttl)).transpose()?;
 
-        Ok(())
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let _guard = self.lock.exclusive(py);
+
+            let stored = py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.get_cf(&cf, key)),
+                None => Ok(db.get(key)),
+            })?;
+
+            let stored = match stored {
+                Ok(stored) => stored,
+                Err(e) => {
+                    return Err(rocksdb_error(&e, format!("Record cannot get. {}", e)))
+                }
+            };
+
+            let actual = stored.as_deref().and_then(|v| load(self.expiry, v));
+
+            let decoded = match actual {
+                Some(value) => Some(decode_value(py, &self.codec, value)?),
+                None => None,
+            };
+
+            if actual != expected.as_ref().map(|v| v.as_bytes()) {
+                return Ok((false, decoded));
+            }
+
+            let result = py.allow_threads(|| match (cf, new) {
+                (Some(cf), Some(value)) => cf_handle(db, cf).map(|cf| db.put_cf(&cf, key, value)),
+                (None, Some(value)) => Ok(db.put(key, value)),
+                (cf, None) => delete_with_entity(db, cf, key),
+            })?;
+
+            match result {
+                Ok(()) => Ok((true, decoded)),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot set. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Record cannot set, database is closed"))
+        }
+    }
+
+    /// Replaces the value of the "key" with the result of `func(value)` and returns it. The
+    /// value is `None` if the key is missing, and `None` as the result removes the key and its
+    /// entity. The function is called without holding the write lock, if another write changes
+    /// the key before the result is written, the function is called again with the new value,
+    /// so concurrent updates are not lost. The new value is set with the `ttl` like `set`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = db.update(b'key', lambda value: (value or b'') + b'!')
+    ///
+    /// value = db.update(b'session', lambda value: b'active', ttl=3600)
+    ///
+    /// value = db.update(b'key', lambda value: b'new', cf='users')
+    /// ```
+    #[pyo3(signature = (key, func, ttl=None, cf=None))]
+    fn update(
+        &self,
+        py: Python,
+        key: &PyAny,
+        func: &PyAny,
+        ttl: Option<u64>,
+        cf: Option<&str>,
+    ) -> PyResult<Option<PyObject>> {
+        let key = encode_key(self.text, key)?;
+        let key = key.as_bytes();
+        let db = self.handle("Record cannot update")?;
+
+        // the function is not called for a write which cannot succeed
+        if ttl.is_some() && !self.expiry {
+            return Err(RocksDBPyException::new_err(
+                "Record cannot set with a TTL, key TTL is not enabled by the options",
+            ));
+        }
+
+        let read = |db: &DB| {
+            let stored = py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.get_cf(&cf, key)),
+                None => Ok(db.get(key)),
+            })?;
+
+            stored.map_err(|e| rocksdb_error(&e, format!("Record cannot get. {}", e)))
+        };
+
+        loop {
+            let stored = read(db)?;
+
+            let current = match stored.as_deref().and_then(|v| load(self.expiry, v)) {
+                Some(value) => Some(decode_value(py, &self.codec, value)?),
+                None => None,
+            };
+
+            let result = func.call1((current,))?;
+
+            let new = if result.is_none() {
+                None
+            } else {
+                Some(encode_value(py, &self.codec, result)?)
+            };
+
+            let _guard = self.lock.exclusive(py);
+
+            // the key changed while the function was called, retry with the new value
+            if read(db)? != stored {
+                continue;
+            }
+
+            let encoded = match &new {
+                Some(value) => Some(self.store(value.as_bytes(), ttl)?),
+                None => None,
+            };
+
+            let written = py.allow_threads(|| match (cf, encoded) {
+                (Some(cf), Some(value)) => cf_handle(db, cf).map(|cf| db.put_cf(&cf, key, value)),
+                (None, Some(value)) => Ok(db.put(key, value)),
+                (cf, None) => delete_with_entity(db, cf, key),
+            })?;
+
+            return match written {
+                Ok(()) => Ok(new.map(|_| result.into_py(py))),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot update. {}", e))),
+            };
+        }
+    }
+
+    /// Adds the delta to the counter of the "key" by a merge and returns the new value. A
+    /// missing counter starts from zero. Counters are stored as 8 little-endian bytes without
+    /// the codec, and they must only be changed by `incr` and `decr`. A value which is not a
+    /// counter raises and is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = db.incr(b'visits')
+    ///
+    /// value = db.incr(b'visits', 10)
+    /// ```
+    #[pyo3(signature = (key, delta=None))]
+    fn incr(&self, py: Python, key: &PyAny, delta: Option<i64>) -> PyResult<i64> {
+        let key = encode_key(self.text, key)?;
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+
+            // the lock makes the returned value the result of this merge
+            let _guard = self.lock.exclusive(py);
+
+            let current = py
+                .allow_threads(|| db.get(key))
+                .map_err(|e| rocksdb_error(&e, format!("Counter cannot increment. {}", e)))?;
+
+            // a merge into a value which is not a counter fails every later read and compaction
+            if let Some(current) = current.as_deref().and_then(|v| load(self.expiry, v)) {
+                if counter(current).is_none() {
+                    return Err(RocksDBPyException::new_err(
+                        "Counter cannot increment, the value is not a counter",
+                    ));
+                }
+            }
+
+            let value = py.allow_threads(|| {
+                db.merge(key, add_operand(delta.unwrap_or(1)))?;
+                db.get(key)
+            });
+
+            match value {
+                Ok(value) => {
+                    let value = value.as_deref().and_then(|v| load(self.expiry, v));
+
+                    match value.and_then(counter) {
+                        Some(value) => Ok(value),
+                        None => Err(RocksDBPyException::new_err(
+                            "Counter cannot increment, the value is not a counter",
+                        )),
+                    }
+                }
+                Err(e) => Err(rocksdb_error(&e, format!("Counter cannot increment. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Counter cannot increment, database is closed"))
+        }
+    }
+
+    /// Subtracts the delta from the counter of the "key" and returns the new value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = db.decr(b'stock')
+    /// ```
+    #[pyo3(signature = (key, delta=None))]
+    fn decr(&self, py: Python, key: &PyAny, delta: Option<i64>) -> PyResult<i64> {
+        self.incr(py, key, Some(delta.unwrap_or(1).wrapping_neg()))
+    }
+
+    /// Appends the data to the value of the "key" by a merge, so the value is not read. A
+    /// missing value starts empty. The data is appended without the codec.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.append(b'events', b'started\n')
+    /// ```
+    fn append(&self, py: Python, key: &PyAny, data: BytesLike) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let operand = append_operand(data.as_bytes());
+            let _guard = self.lock.shared(py);
+
+            match py.allow_threads(|| db.merge(key, operand)) {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot append. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Record cannot append, database is closed"))
+        }
+    }
+
+    /// Sets the "key" to an entity of named columns, the names are bytes or str and the values
+    /// are bytes. The columns are stored without the codec in a column family which keeps the
+    /// entities, it is created on the first use. The plain value of the key is removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.put_entity(b'user:1', {'name': b'Ada', 'email': b'ada@example.com'})
+    ///
+    /// db.put_entity(b'user:1', {'name': b'Ada'}, cf='users')
+    /// ```
+    #[pyo3(signature = (key, columns, ttl=None, cf=None))]
+    fn put_entity(
+        &self,
+        py: Python,
+        key: &PyAny,
+        columns: &PyDict,
+        ttl: Option<u64>,
+        cf: Option<&str>,
+    ) -> PyResult<()> {
+        let key = encode_key(self.text, key)?;
+        let entity = encode_entity(columns)?;
+        let value = self.store(&entity, ttl)?;
+        let family = entity_family(cf);
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+
+            if let Some(cf) = cf {
+                cf_handle(db, cf)?;
+            }
+
+            self.column_family(db, &family)?;
+
+            let _guard = self.lock.shared(py);
+
+            let result = py.allow_threads(|| -> PyResult<_> {
+                let mut wr = WriteBatch::default();
+
+                match cf {
+                    Some(cf) => wr.delete_cf(&cf_handle(db, cf)?, key),
+                    None => wr.delete(key),
+                }
+
+                wr.put_cf(&cf_handle(db, &family)?, key, &value);
+
+                Ok(db.write(wr))
+            })?;
+
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("Entity cannot set. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Entity cannot set, database is closed"))
+        }
+    }
+
+    /// Returns the columns of the entity of the "key" as a dict of bytes. A plain value is
+    /// returned as the default column, whose name is empty. The plain value is newer than the
+    /// entity of the same key, because `put_entity` removes it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// columns = db.get_entity(b'user:1')
+    ///
+    /// name = columns[b'name']
+    /// ```
+    #[pyo3(signature = (key, cf=None))]
+    fn get_entity<'py>(
+        &self,
+        py: Python<'py>,
+        key: &PyAny,
+        cf: Option<&str>,
+    ) -> PyResult<Option<&'py PyDict>> {
+        let key = encode_key(self.text, key)?;
+        let family = entity_family(cf);
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+
+            let found = py.allow_threads(|| -> PyResult<Option<(bool, Vec<u8>)>> {
+                let plain = match cf {
+                    Some(cf) => db.get_cf(&cf_handle(db, cf)?, key),
+                    None => db.get(key),
+                };
+
+                if let Some(value) = plain.map_err(entity_error)? {
+                    return Ok(Some((false, value)));
+                }
+
+                match db.cf_handle(&family) {
+                    Some(entities) => match db.get_cf(&entities, key) {
+                        Ok(value) => Ok(value.map(|value| (true, value))),
+                        Err(e) => Err(entity_error(e)),
+                    },
+                    None => Ok(None),
+                }
+            })?;
+
+            let (entity, value) = match &found {
+                Some((entity, value)) => (*entity, load(self.expiry, value)),
+                None => return Ok(None),
+            };
+
+            match value {
+                Some(value) if entity => Ok(Some(decode_entity(py, value)?)),
+                Some(value) => Ok(Some(default_column(py, value)?)),
+                None => Ok(None),
+            }
+        } else {
+            Err(DBClosedError::new_err("Entity cannot get, database is closed"))
+        }
+    }
+
+    /// Awaitable `get` which runs on the default executor of the running event loop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = await db.aget(b'key')
+    /// ```
+    #[pyo3(signature = (key, cf=None))]
+    fn aget<'py>(
+        slf: &'py PyCell<Self>,
+        py: Python<'py>,
+        key: PyObject,
+        cf: Option<String>,
+    ) -> PyResult<&'py PyAny> {
+        run_in_executor(py, slf.getattr("get")?, vec![key, cf.into_py(py)])
+    }
+
+    /// Awaitable `set` which runs on the default executor of the running event loop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// await db.aset(b'key', b'value')
+    ///
+    /// await db.aset(b'key', b'value', ttl=3600)
+    /// ```
+    #[pyo3(signature = (key, value, ttl=None, cf=None))]
+    fn aset<'py>(
+        slf: &'py PyCell<Self>,
+        py: Python<'py>,
+        key: PyObject,
+        value: PyObject,
+        ttl: Option<u64>,
+        cf: Option<String>,
+    ) -> PyResult<&'py PyAny> {
+        let args = vec![key, value, ttl.into_py(py), cf.into_py(py)];
+
+        run_in_executor(py, slf.getattr("set")?, args)
+    }
+
+    /// Awaitable `delete` which runs on the default executor of the running event loop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// await db.adelete(b'key')
+    /// ```
+    #[pyo3(signature = (key, cf=None))]
+    fn adelete<'py>(
+        slf: &'py PyCell<Self>,
+        py: Python<'py>,
+        key: PyObject,
+        cf: Option<String>,
+    ) -> PyResult<&'py PyAny> {
+        run_in_executor(py, slf.getattr("delete")?, vec![key, cf.into_py(py)])
+    }
+
+    /// Awaitable `multi_get` which runs on the default executor of the running event loop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// values = await db.amulti_get([b'first', b'second'])
+    /// ```
+    #[pyo3(signature = (keys, skip_missings=None))]
+    fn amulti_get<'py>(
+        slf: &'py PyCell<Self>,
+        py: Python<'py>,
+        keys: PyObject,
+        skip_missings: Option<bool>,
+    ) -> PyResult<&'py PyAny> {
+        let args = vec![keys, skip_missings.into_py(py)];
+
+        run_in_executor(py, slf.getattr("multi_get")?, args)
+    }
+
+    /// Awaitable `write` which runs on the default executor of the running event loop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// await db.awrite(b)
+    ///
+    /// await db.awrite(b, sync=True)
+    /// ```
+    #[pyo3(signature = (batch, sync=None, disable_wal=None, no_slowdown=None))]
+    fn awrite<'py>(
+        slf: &'py PyCell<Self>,
+        py: Python<'py>,
+        batch: PyObject,
+        sync: Option<bool>,
+        disable_wal: Option<bool>,
+        no_slowdown: Option<bool>,
+    ) -> PyResult<&'py PyAny> {
+        let args = vec![
+            batch,
+            sync.into_py(py),
+            disable_wal.into_py(py),
+            no_slowdown.into_py(py),
+        ];
+
+        run_in_executor(py, slf.getattr("write")?, args)
+    }
+
+    /// Returns the value associated with a "key", raises `KeyError` if the key is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = db[b'key']
+    /// ```
+    fn __getitem__(&self, py: Python, key: &PyAny) -> PyResult<PyObject> {
+        match self.get(py, key, None)? {
+            Some(value) => Ok(value),
+            None => Err(PyKeyError::new_err(key.into_py(py))),
+        }
+    }
+
+    /// Sets records by "key" and "value".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db[b'key'] = b'value'
+    /// ```
+    fn __setitem__(&self, py: Python, key: &PyAny, value: &PyAny) -> PyResult<()> {
+        self.set(py, key, value, None, None)
+    }
+
+    /// Removes existing records by "key". Missing keys are ignored like `delete`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// del db[b'key']
+    /// ```
+    fn __delitem__(&self, py: Python, key: &PyAny) -> PyResult<()> {
+        self.delete(py, key, None)
+    }
+
+    /// Returns true if the "key" exists.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if b'key' in db:
+    ///     ...
+    /// ```
+    pub(crate) fn __contains__(&self, py: Python, key: &PyAny) -> PyResult<bool> {
+        let key = encode_key(self.text, key)?;
+
+        if let Some(db) = self.inner()? {
+            let key = key.as_bytes();
+            let expiry = self.expiry;
+
+            let found = py.allow_threads(|| {
+                db.get_pinned(key)
+                    .map(|v| v.map_or(false, |v| load(expiry, &v).is_some()))
+            });
+
+            match found {
+                Ok(found) => Ok(found),
+                Err(e) => Err(rocksdb_error(&e, format!("Record cannot get. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Record cannot get, database is closed"))
+        }
+    }
+
+    /// Sets database entries for list of key and values as a batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b = WriteBatch()
+    /// b.add(b'first', 'first_value')
+    /// b.add(b'second', 'second_value')
+    ///
+    /// db.write(b)
+    ///
+    /// b = WriteBatchWithIndex()
+    /// b.add(b'first', b'first_value')
+    ///
+    /// db.write(b)
+    ///
+    /// # the batch is fsynced before the call returns
+    /// db.write(b, sync=True)
+    ///
+    /// # bulk ingestion, the batch is lost if the process crashes before a flush
+    /// db.write(b, disable_wal=True)
+    ///
+    /// # fails instead of waiting if the writes are stalled
+    /// db.write(b, no_slowdown=True)
+    /// ```
+    #[pyo3(signature = (batch, sync=None, disable_wal=None, no_slowdown=None))]
+    fn write(
+        &self,
+        py: Python,
+        batch: &PyAny,
+        sync: Option<bool>,
+        disable_wal: Option<bool>,
+        no_slowdown: Option<bool>,
+    ) -> PyResult<()> {
+        let mut opts = WriteOptions::default();
+
+        opts.set_sync(sync.unwrap_or(false));
+        opts.disable_wal(disable_wal.unwrap_or(false));
+        opts.set_no_slowdown(no_slowdown.unwrap_or(false));
+
+        let wr = if let Ok(indexed) = batch.extract::<PyRef<WriteBatchWithIndexPy>>() {
+            // the values of the indexed batch are raw bytes which the codec cannot decode
+            if self.codec.is_some() {
+                return Err(RocksDBPyException::new_err(
+                    "Batch cannot write, use db.write_batch() for databases with a codec",
+                ));
+            }
+
+            indexed.build(self.expiry)
+        } else {
+            let mut batch = batch.extract::<PyRefMut<WriteBatchPy>>()?;
+
+            // the values of the batch must have the expiration header of the database
+            if self.expiry != batch.expiry() {
+                return Err(RocksDBPyException::new_err(
+                    "Batch cannot write, use db.write_batch() for databases with key TTL",
+                ));
+            }
+
+            // the values of the batch must be encoded by a codec if the database has one
+            if self.codec.is_some() != batch.encoded() {
+                return Err(RocksDBPyException::new_err(
+                    "Batch cannot write, use db.write_batch() for databases with a codec",
+                ));
+            }
+
+            batch.get()?
+        };
+
+        let len = wr.len();
+
+        if let Some(db) = self.inner()? {
+            let _guard = self.lock.shared(py);
+
+            match py.allow_threads(|| db.write_opt(wr, &opts)) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(rocksdb_error(
+                    &e,
+                    format!("Batch cannot write {} elements. {}", len, e),
+                )),
+            }
+        } else {
+            Err(DBClosedError::new_err(format!(
+                "Batch cannot write {} elements, database is closed",
+                len
+            )))
+        }
+    }
+
+    /// Sets database entries from a mapping or an iterable of (key, value) pairs. The entries
+    /// are written atomically as a batch without holding the GIL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.put_many({b'first': b'first_value', b'second': b'second_value'})
+    ///
+    /// db.put_many([(b'first', b'first_value'), (b'second', b'second_value')])
+    /// ```
+    fn put_many(&self, py: Python, items: &PyAny) -> PyResult<()> {
+        let mut wr = WriteBatch::default();
+
+        if let Ok(mapping) = items.downcast::<PyDict>() {
+            for (k, v) in mapping.iter() {
+                let key = encode_key(self.text, k)?;
+                let value = encode_value(py, &self.codec, v)?;
+
+                wr.put(key.as_bytes(), self.store(value.as_bytes(), None)?);
+            }
+        } else {
+            for item in items.iter()? {
+                let (k, v): (&PyAny, &PyAny) = item?.extract()?;
+                let key = encode_key(self.text, k)?;
+                let value = encode_value(py, &self.codec, v)?;
+
+                wr.put(key.as_bytes(), self.store(value.as_bytes(), None)?);
+            }
+        }
+
+        self.commit(py, wr)
+    }
+
+    /// Removes the given keys and their entities atomically as a batch without holding the GIL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.delete_many([b'first', b'second'])
+    /// ```
+    fn delete_many(&self, py: Python, keys: &PyAny) -> PyResult<()> {
+        let mut wr = WriteBatch::default();
+
+        let db = self.handle("Batch cannot write")?;
+
+        for key in keys.iter()? {
+            let key = encode_key(self.text, key?)?;
+
+            wr.delete(key.as_bytes());
+
+            delete_entity(db, &mut wr, None, key.as_bytes());
+        }
+
+        self.commit(py, wr)
+    }
+
+    /// Removes every key and entity which starts with the given prefix. A range delete is used
+    /// if the prefix has an upper bound, otherwise the keys are iterated and deleted as a batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.delete_prefix(b'users:')
+    /// ```
+    fn delete_prefix(&self, py: Python, prefix: &PyAny) -> PyResult<()> {
+        let prefix = self.key(prefix)?;
+        let prefix = prefix.as_bytes();
+        let db = self.handle("Prefix cannot delete")?;
+        let mut wr = WriteBatch::default();
+
+        match prefix_upper_bound(prefix) {
+            Some(upper) => {
+                wr.delete_range(prefix, upper.as_slice());
+
+                delete_entity_range(db, &mut wr, None, prefix, upper.as_slice());
+            }
+            None => {
+                let opts = IteratorOptions {
+                    lower_bound: Some(prefix.to_vec()),
+                    ..Default::default()
+                };
+
+                // prefixes without an upper bound cover the end of the key space
+                py.allow_threads(|| {
+                    let mut inner = db.raw_iterator_opt(opts.read_options());
+
+                    inner.seek_to_first();
+
+                    while let Some(key) = inner.key() {
+                        wr.delete(key);
+
+                        inner.next();
+                    }
+
+                    inner.status()?;
+
+                    if let Some(entities) = db.cf_handle(&entity_family(None)) {
+                        let mut inner = db.raw_iterator_cf_opt(&entities, opts.read_options());
+
+                        inner.seek_to_first();
+
+                        while let Some(key) = inner.key() {
+                            wr.delete_cf(&entities, key);
+
+                            inner.next();
+                        }
+
+                        inner.status()?;
+                    }
+
+                    Ok::<_, rocksdb::Error>(())
+                })
+                .map_err(|e| rocksdb_error(&e, format!("Prefix cannot delete. {}", e)))?;
+            }
+        }
+
+        self.commit(py, wr)
+    }
+
+    /// Returns a batch which is written atomically when the `with` block is completed. The
+    /// batch is discarded if the block raises an exception.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// with db.write_batch() as b:
+    ///     b.add(b'first', b'first_value')
+    ///     b.add(b'second', b'second_value')
+    /// ```
+    fn write_batch(&self) -> PyResult<WriteBatchPy> {
+        if let Some(db) = self.inner()? {
+            Ok(WriteBatchPy::bound(
+                db.clone(),
+                self.lock.clone(),
+                self.expiry,
+                self.codec.clone(),
+            )
+            .text(self.text))
+        } else {
+            Err(DBClosedError::new_err("Batch cannot create, database is closed"))
+        }
+    }
+
+    /// Returns entries according to given list of key and values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.multi_get(b'first', b'second')
+    ///
+    /// db.multi_get(b'first', b'second', skip_missings=True)
+    /// ```
+    #[pyo3(signature = (keys, skip_missings=None))]
+    fn multi_get<'py>(
+        &self,
+        py: Python<'py>,
+        keys: &'py PyList,
+        skip_missings: Option<bool>,
+    ) -> PyResult<&'py PyList> {
+        // generate list of keys based on Python's list
+        let ks = keys
+            .iter()
+            .map(|k| encode_key(self.text, k))
+            .collect::<PyResult<Vec<Encoded>>>()?;
+
+        let r = PyList::empty(py);
+        let skip = skip_missings.is_none() || skip_missings.unwrap() == false;
+        let bytes: Vec<&[u8]> = ks.iter().map(|k| k.as_bytes()).collect();
+
+        for value in self.fetch_many(py, &bytes)? {
+            match value {
+                Some(item) => r.append(decode_value(py, &self.codec, &item)?).unwrap(),
+                None => {
+                    // skip missing records if skip_missings is true, the output
+                    // array will be shorter then given key array size.
+                    if skip {
+                        r.append(py.None()).unwrap()
+                    } else {
+                        continue;
+                    }
+                }
+            }
+        }
+
+        Ok(r)
+    }
+
+    /// Returns a dictionary which maps every given key to its value, `None` if the key is
+    /// missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// values = db.multi_get_dict([b'first', b'second'])
+    /// ```
+    fn multi_get_dict<'py>(&self, py: Python<'py>, keys: &'py PyList) -> PyResult<&'py PyDict> {
+        let ks = keys
+            .iter()
+            .map(|k| encode_key(self.text, k))
+            .collect::<PyResult<Vec<Encoded>>>()?;
+
+        let r = PyDict::new(py);
+        let bytes: Vec<&[u8]> = ks.iter().map(|k| k.as_bytes()).collect();
+
+        for (key, value) in keys.iter().zip(self.fetch_many(py, &bytes)?) {
+            match value {
+                Some(item) => r.set_item(key, decode_value(py, &self.codec, &item)?)?,
+                None => r.set_item(key, py.None())?,
+            }
+        }
+
+        Ok(r)
+    }
+
+    /// Returns a heap-allocated iterator over the contents of the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// iterator = db.iterator()
+    ///
+    /// iterator = db.iterator(mode='end')
+    ///
+    /// iterator = db.iterator(mode='from', key=b'test')
+    ///
+    /// iterator = db.iterator(mode='from', key=b'test', direction=-1)
+    ///
+    /// iterator = db.iterator(mode='for_prev', key=b'test')
+    ///
+    /// iterator = db.iterator(lower_bound=b'a', upper_bound=b'b')
+    ///
+    /// iterator = db.iterator(tailing=True)
+    ///
+    /// iterator = db.iterator(readahead_size=2 * 1024 * 1024, async_io=True)
+    ///
+    /// iterator = db.iterator(values=False)
+    ///
+    /// iterator = db.iterator(pinned=True)
+    ///
+    /// iterator = db.iterator(timestamp=100)
+    /// ```
+    #[pyo3(signature = (
+        mode=None,
+        key=None,
+        direction=None,
+        *,
+        lower_bound=None,
+        upper_bound=None,
+        tailing=None,
+        readahead_size=None,
+        auto_readahead_size=None,
+        async_io=None,
+        keys=None,
+        values=None,
+        pinned=None,
+        timestamp=None,
+    ))]
+    fn iterator(
+        &self,
+        mode: Option<&str>,
+        key: Option<&PyAny>,
+        direction: Option<i32>,
+        lower_bound: Option<&PyAny>,
+        upper_bound: Option<&PyAny>,
+        tailing: Option<bool>,
+        readahead_size: Option<usize>,
+        auto_readahead_size: Option<bool>,
+        async_io: Option<bool>,
+        keys: Option<bool>,
+        values: Option<bool>,
+        pinned: Option<bool>,
+        timestamp: Option<u64>,
+    ) -> PyResult<IteratorPy> {
+        let mut im = IteratorSeek::Start;
+        let keys = keys.unwrap_or(true);
+        let values = values.unwrap_or(true);
+
+        if !keys && !values {
+            return Err(RocksDBPyException::new_err(
+                "Iterator requires keys or values",
+            ));
+        }
+
+        // a pinned value is a view of the stored bytes, so it cannot be decoded
+        if pinned.unwrap_or(false) && self.codec.is_some() {
+            return Err(PyValueError::new_err(
+                "Iterator cannot pin the values of a database with a codec",
+            ));
+        }
+
+        let key = key.map(|k| self.key(k)).transpose()?;
+
+        let opts = IteratorOptions {
+            lower_bound: self.key_bound(lower_bound)?,
+            upper_bound: self.key_bound(upper_bound)?,
+            tailing: tailing.unwrap_or(false),
+            readahead_size,
+            auto_readahead_size,
+            async_io: async_io.unwrap_or(false),
+            expiry: self.expiry,
+            timestamp,
+        };
+
+        if !mode.is_none() {
+            let mut ik: &[u8] = b"";
+            let mut dr = Direction::Forward;
+
+            if let Some(key) = &key {
+                ik = key.as_bytes();
+            }
+
+            // Generate direction by minus or plus integer
+            if !key.is_none() && !direction.is_none() {
+                dr = match direction.unwrap() {
+                    -1 => Direction::Reverse,
+                    _ => Direction::Forward,
+                }
+            }
+
+            im = match mode.unwrap() {
+                "end" => IteratorSeek::End,
+                "from" => IteratorSeek::From(ik, dr),
+                "for_prev" => IteratorSeek::ForPrev(ik, dr),
+                _ => IteratorSeek::Start,
+            }
+        }
+
+        if let Some(db) = self.inner()? {
+            Ok(IteratorPy::new(db, im, opts)
+                .select(keys, values)
+                .pinned(pinned.unwrap_or(false))
+                .decoded(self.codec.clone())
+                .text(self.text))
+        } else {
+            Err(DBClosedError::new_err("Iterator cannot get, database is closed"))
+        }
+    }
+
+    /// Returns an iterator over the (key, value) entries of the range. The range includes
+    /// `start` and excludes `stop`, missing bounds mean the range is not limited on that side.
+    /// If `reverse` is true, the entries are returned from the last key before `stop` down to
+    /// `start`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, value in db.items(b'a', b'b'):
+    ///     print(key, value)
+    ///
+    /// for key, value in db.items(b'a', b'b', reverse=True):
+    ///     print(key, value)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn items(
+        &self,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+    ) -> PyResult<IteratorPy> {
+        self.range(start, stop, reverse.unwrap_or(false), true, true)
+    }
+
+    /// Returns an iterator over the (key, columns) entries of the range like `items`, the
+    /// columns are decoded like `get_entity`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, columns in db.entities(b'user:', b'user;'):
+    ///     print(key, columns[b'name'])
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn entities(
+        &self,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+    ) -> PyResult<EntityIteratorPy> {
+        let opts = IteratorOptions {
+            lower_bound: self.key_bound(start)?,
+            upper_bound: self.key_bound(stop)?,
+            expiry: self.expiry,
+            ..Default::default()
+        };
+
+        if let Some(db) = self.inner()? {
+            Ok(EntityIteratorPy::new(db, &opts, reverse.unwrap_or(false)).text(self.text))
+        } else {
+            Err(DBClosedError::new_err("Iterator cannot get, database is closed"))
+        }
+    }
+
+    /// Returns an iterator over the keys of the range. The range includes `start` and excludes
+    /// `stop`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key in db.keys(b'a', b'b'):
+    ///     print(key)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn keys(
+        &self,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+    ) -> PyResult<IteratorPy> {
+        self.range(start, stop, reverse.unwrap_or(false), true, false)
+    }
+
+    /// Returns an iterator over the values of the range. The range includes `start` and
+    /// excludes `stop`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for value in db.values(b'a', b'b'):
+    ///     print(value)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn values(
+        &self,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+    ) -> PyResult<IteratorPy> {
+        self.range(start, stop, reverse.unwrap_or(false), false, true)
+    }
+
+    /// Returns an async iterator over the (key, value) entries of the range. The entries are
+    /// read in batches of `batch_size` on the default executor of the running event loop, and
+    /// the next batch is read while the current batch is consumed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// async for key, value in db.aiter():
+    ///     print(key, value)
+    ///
+    /// async for key, value in db.aiter(b'a', b'b', reverse=True, batch_size=100):
+    ///     print(key, value)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None, batch_size=None))]
+    fn aiter(
+        &self,
+        py: Python,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+        batch_size: Option<usize>,
+    ) -> PyResult<AsyncIteratorPy> {
+        let batch_size = batch_size.unwrap_or(1000);
+
+        if batch_size == 0 {
+            return Err(PyValueError::new_err(
+                "Iterator cannot open, batch_size must be positive",
+            ));
+        }
+
+        let iterator = self.range(start, stop, reverse.unwrap_or(false), true, true)?;
+
+        Ok(AsyncIteratorPy::new(Py::new(py, iterator)?, batch_size))
+    }
+
+    /// Returns an iterator over the (key, value) entries of the range which includes `start`
+    /// and excludes `stop`, the range is read by `workers` threads without holding the GIL. The
+    /// range is split at the boundaries of the SST files, so the chunks are approximately
+    /// equal. The entries of a chunk are in order, but the chunks are interleaved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, value in db.parallel_scan(workers=8):
+    ///     ...
+    ///
+    /// for key, value in db.parallel_scan(b'a', b'b', workers=4):
+    ///     ...
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, workers=None))]
+    fn parallel_scan(
+        &self,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        workers: Option<usize>,
+    ) -> PyResult<ParallelScanPy> {
+        let workers = match workers {
+            Some(0) => return Err(PyValueError::new_err("Range scan requires a worker")),
+            Some(workers) => workers,
+            None => thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+
+        let start = self.key_bound(start)?;
+        let stop = self.key_bound(stop)?;
+
+        if let Some(db) = self.inner()? {
+            ParallelScanPy::new(db, start, stop, workers, self.expiry, self.codec.clone())
+                .map(|scan| scan.text(self.text))
+        } else {
+            Err(DBClosedError::new_err("Range cannot scan, database is closed"))
+        }
+    }
+
+    /// Returns the number of keys in the range which includes `start` and excludes `stop`. The
+    /// keys are counted without holding the GIL.
+    ///
+    /// If `approximate` is true, the number is estimated from the metadata of the SST files
+    /// which overlap the range. The estimate does not include the entries in the memtables
+    /// and may count overwritten or deleted keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = db.count()
+    ///
+    /// n = db.count(b'a', b'b')
+    ///
+    /// n = db.count(b'a', b'b', approximate=True)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, approximate=None))]
+    fn count(
+        &self,
+        py: Python,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        approximate: Option<bool>,
+    ) -> PyResult<u64> {
+        let start = self.key_bound(start)?;
+        let stop = self.key_bound(stop)?;
+        let (start, stop) = (start.as_deref(), stop.as_deref());
+
+        if let Some(db) = self.inner()? {
+            if approximate.unwrap_or(false) {
+                return estimate_count(db, start, stop);
+            }
+
+            let opts = IteratorOptions {
+                lower_bound: start.map(|b| b.to_vec()),
+                upper_bound: stop.map(|b| b.to_vec()),
+                expiry: self.expiry,
+                ..Default::default()
+            };
+
+            py.allow_threads(|| {
+                let mut inner = db.raw_iterator_opt(opts.read_options());
+                let mut n = 0;
+
+                inner.seek_to_first();
+
+                while let Some(value) = inner.value() {
+                    if load(opts.expiry, value).is_some() {
+                        n += 1;
+                    }
+
+                    inner.next();
+                }
+
+                match inner.status() {
+                    Ok(()) => Ok(n),
+                    Err(e) => Err(rocksdb_error(&e, format!("Keys cannot count. {}", e))),
+                }
+            })
+        } else {
+            Err(DBClosedError::new_err("Keys cannot count, database is closed"))
+        }
+    }
+
+    /// Returns the estimated number of keys by the `rocksdb.estimate-num-keys` property.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = len(db)
+    /// ```
+    fn __len__(&self) -> PyResult<usize> {
+        if let Some(db) = self.inner()? {
+            Ok(estimate_count(db, None, None)? as usize)
+        } else {
+            Err(DBClosedError::new_err("Length cannot get, database is closed"))
+        }
+    }
+
+    /// Returns the exact number of keys by scanning the database without holding the GIL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = db.exact_len()
+    /// ```
+    fn exact_len(&self, py: Python) -> PyResult<u64> {
+        self.count(py, None, None, Some(false))
+    }
+
+    /// Returns an iterator over the entries whose keys start with the given prefix. The scan is
+    /// bounded inside RocksDB, so it stops at the end of the prefix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, value in db.prefix_iterator(b'users:'):
+    ///     print(key, value)
+    /// ```
+    fn prefix_iterator(&self, prefix: &PyAny) -> PyResult<IteratorPy> {
+        let prefix = self.key(prefix)?;
+
+        let opts = IteratorOptions {
+            lower_bound: Some(prefix.as_bytes().to_vec()),
+            upper_bound: prefix_upper_bound(prefix.as_bytes()),
+            expiry: self.expiry,
+            ..Default::default()
+        };
+
+        if let Some(db) = self.inner()? {
+            Ok(IteratorPy::new(db, IteratorSeek::Start, opts)
+                .decoded(self.codec.clone())
+                .text(self.text))
+        } else {
+            Err(DBClosedError::new_err("Iterator cannot get, database is closed"))
+        }
+    }
+
+    /// Returns a view over the keys which start with the prefix. The view prepends the prefix
+    /// to the given keys and removes it from the keys of its iterators.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// users = db.namespace(b'users:')
+    /// users.set(b'1', b'value')
+    ///
+    /// for key, value in users.iterator():
+    ///     print(key, value)
+    /// ```
+    fn namespace(slf: PyRef<Self>, prefix: &PyAny) -> PyResult<NamespacePy> {
+        let prefix = slf.key(prefix)?.as_bytes().to_vec();
+
+        Ok(NamespacePy::new(slf.into(), prefix))
+    }
+
+    /// Creates the column family "name" with the options of the database if it is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.create_column_family('users')
+    ///
+    /// db.set(b'key', b'value', cf='users')
+    /// ```
+    fn create_column_family(&self, name: &str) -> PyResult<()> {
+        self.column_family(self.handle("Column family cannot be created")?, name)?;
+
+        Ok(())
+    }
+
+    /// Drops the column family "name" and its records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.drop_column_family('users')
+    /// ```
+    fn drop_column_family(&self, py: Python, name: &str) -> PyResult<()> {
+        let db = self.handle("Column family cannot be dropped")?;
+
+        match py.allow_threads(|| db.drop_cf(name)) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("Column family cannot be dropped. {}", e))),
+        }
+    }
+
+    /// Returns a writer which collects the writes and writes them as a batch when the batch
+    /// has `max_ops` writes or `max_bytes` bytes, every `flush_interval` seconds and when the
+    /// writer is flushed or closed. The buffered writes are not visible to reads until they
+    /// are written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// with db.buffered(max_ops=1000, max_bytes=1024 * 1024, flush_interval=0.5) as w:
+    ///     for key, value in items:
+    ///         w.set(key, value)
+    /// ```
+    #[pyo3(signature = (max_ops=None, max_bytes=None, flush_interval=None))]
+    fn buffered(
+        slf: PyRef<Self>,
+        max_ops: Option<usize>,
+        max_bytes: Option<usize>,
+        flush_interval: Option<f64>,
+    ) -> PyResult<BufferedWriterPy> {
+        let inner = match slf.inner()? {
+            Some(db) => db.clone(),
+            None => return Err(DBClosedError::new_err("Writer cannot open, database is closed")),
+        };
+
+        let interval = match flush_interval {
+            Some(seconds) => match Duration::try_from_secs_f64(seconds) {
+                Ok(interval) if !interval.is_zero() => Some(interval),
+                _ => {
+                    return Err(PyValueError::new_err(
+                        "Writer cannot open, flush_interval must be positive",
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let lock = slf.lock.clone();
+
+        Ok(BufferedWriterPy::new(
+            slf.into(),
+            inner,
+            lock,
+            max_ops,
+            max_bytes,
+            interval,
+        ))
+    }
+
+    /// Returns a durable queue stored in the column family "name", the column family is
+    /// created if it is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// jobs = db.queue()
+    /// jobs.push(b'job')
+    ///
+    /// job = jobs.pop_front()
+    ///
+    /// emails = db.queue('emails')
+    /// ```
+    #[pyo3(signature = (name=None))]
+    fn queue(slf: PyRef<Self>, name: Option<String>) -> PyResult<QueuePy> {
+        let name = name.unwrap_or_else(|| String::from("queue"));
+
+        slf.column_family(slf.handle("Queue cannot open")?, &name)?;
+
+        Ok(QueuePy::new(slf.into(), name))
+    }
+
+    /// Returns a cursor over the contents of the database which can be repositioned by
+    /// `seek`, `seek_to_first`, `seek_to_last`, `next` and `prev`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cursor = db.cursor()
+    /// cursor.seek(b'test')
+    ///
+    /// while cursor.valid():
+    ///     print(cursor.key(), cursor.value())
+    ///     cursor.next()
+    ///
+    /// cursor = db.cursor(lower_bound=b'a', upper_bound=b'b')
+    ///
+    /// cursor = db.cursor(readahead_size=2 * 1024 * 1024, async_io=True)
+    /// ```
+    #[pyo3(signature = (
+        lower_bound=None,
+        upper_bound=None,
+        readahead_size=None,
+        auto_readahead_size=None,
+        async_io=None,
+    ))]
+    fn cursor(
+        &self,
+        lower_bound: Option<&PyAny>,
+        upper_bound: Option<&PyAny>,
+        readahead_size: Option<usize>,
+        auto_readahead_size: Option<bool>,
+        async_io: Option<bool>,
+    ) -> PyResult<CursorPy> {
+        let opts = IteratorOptions {
+            lower_bound: self.key_bound(lower_bound)?,
+            upper_bound: self.key_bound(upper_bound)?,
+            readahead_size,
+            auto_readahead_size,
+            async_io: async_io.unwrap_or(false),
+            expiry: self.expiry,
+            ..Default::default()
+        };
+
+        if let Some(db) = self.inner()? {
+            Ok(CursorPy::new(db, opts, self.codec.clone()).text(self.text))
+        } else {
+            Err(DBClosedError::new_err("Cursor cannot get, database is closed"))
+        }
+    }
+
+    /// Request stopping background work, if wait is true wait until it’s done.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.cancel_all_background_work()
+    ///
+    /// db.cancel_all_background_work(True)
+    /// ```
+    #[pyo3(signature = (wait=None))]
+    fn cancel_all_background_work(&self, py: Python, wait: Option<bool>) -> PyResult<()> {
+        let mut w = false;
+
+        if wait.is_some() {
+            w = wait.unwrap()
+        }
+
+        if let Some(db) = self.inner()? {
+            py.allow_threads(|| db.cancel_all_background_work(w));
+
+            Ok(())
+        } else {
+            Err(DBClosedError::new_err("Cancel cannot do, database is closed"))
+        }
+    }
+
+    /// Flushes database memtables to SST files on the disk using default options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.flush()
+    /// ```
+    fn flush(&self, py: Python) -> PyResult<()> {
+        if let Some(db) = self.inner()? {
+            match py.allow_threads(|| db.flush()) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("Database cannot flush. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Database cannot flush, database is closed"))
+        }
+    }
+
+    /// Compacts the keys from "start" to "stop" to the bottommost level, the whole database
+    /// if the bounds are omitted. Deleted and overwritten records are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.compact_range()
+    ///
+    /// db.compact_range(b'a', b'b')
+    ///
+    /// db.compact_range(cf='users')
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, cf=None))]
+    fn compact_range(
+        &self,
+        py: Python,
+        start: Option<BytesLike>,
+        stop: Option<BytesLike>,
+        cf: Option<&str>,
+    ) -> PyResult<()> {
+        if let Some(db) = self.inner()? {
+            let start = start.as_ref().map(|b| b.as_bytes());
+            let stop = stop.as_ref().map(|b| b.as_bytes());
+            let _guard = self.lock.shared(py);
+
+            py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.compact_range_cf(&cf, start, stop)),
+                None => Ok(db.compact_range(start, stop)),
+            })
+        } else {
+            Err(DBClosedError::new_err("Database cannot compact, database is closed"))
+        }
+    }
+
+    /// Starts a manual compaction of the range which includes `start` and excludes `stop` on a
+    /// background thread and returns its handle. The range is compacted in chunks, `progress` is
+    /// called with the number of the compacted chunks and the number of all chunks after every
+    /// chunk and a cancel takes effect after the running chunk.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// compaction = db.compact_range_async(b'a', b'z')
+    /// compaction.wait()
+    ///
+    /// compaction = db.compact_range_async(progress=lambda done, total: print(done, total))
+    /// compaction.cancel()
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, cf=None, progress=None))]
+    fn compact_range_async(
+        &self,
+        start: Option<BytesLike>,
+        stop: Option<BytesLike>,
+        cf: Option<String>,
+        progress: Option<PyObject>,
+    ) -> PyResult<CompactionPy> {
+        let start = start.map(|b| b.as_bytes().to_vec());
+        let stop = stop.map(|b| b.as_bytes().to_vec());
+
+        if let Some(db) = self.inner()? {
+            CompactionPy::start(db, &self.lock, start, stop, cf, progress)
+        } else {
+            Err(DBClosedError::new_err("Database cannot compact, database is closed"))
+        }
+    }
+
+    /// Reads every record of every column family with the checksum verification on and
+    /// decodes the values, the errors are collected into a report instead of raised. The scan
+    /// continues after the key range of a corrupt SST file, so the blast radius is known before
+    /// a repair. `progress` is called with the number of the verified records and the estimated
+    /// number of all records.
+    ///
+    /// The report contains `keys` which is the number of the verified records, `corrupt_files`
+    /// which is the list of the files which cannot be read with their `name`, `column_family`
+    /// and `error`, and `corrupt_keys` which is the list of the records whose values cannot be
+    /// decoded with their `key`, `column_family` and `error`. `ok` is true if both are empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// report = db.verify()
+    ///
+    /// for file in report['corrupt_files']:
+    ///     print(file['name'], file['error'])
+    ///
+    /// report = db.verify(progress=lambda done, total: print(done, total))
+    /// ```
+    #[pyo3(signature = (progress=None))]
+    fn verify<'py>(&self, py: Python<'py>, progress: Option<PyObject>) -> PyResult<&'py PyDict> {
+        let db = self.handle("Database cannot verify")?;
+        let names = self.cf_names(db);
+
+        let (files, total) = py.allow_threads(|| {
+            let total: u64 = names
+                .iter()
+                .filter_map(|name| db.cf_handle(name))
+                .filter_map(|cf| {
+                    db.property_int_value_cf(&cf, "rocksdb.estimate-num-keys")
+                        .ok()
+                        .flatten()
+                })
+                .sum();
+
+            db.live_files().map(|files| (files, total))
+        })
+        .map_err(|e| rocksdb_error(&e, format!("Database cannot verify. {}", e)))?;
+
+        let expiry = self.expiry;
+        let corrupt_files = PyList::empty(py);
+        let corrupt_keys = PyList::empty(py);
+        let mut keys = 0;
+
+        let corrupt_key = |key: &[u8], name: &str, error: String| -> PyResult<()> {
+            let corrupt = PyDict::new(py);
+
+            corrupt.set_item("key", PyBytes::new(py, key))?;
+            corrupt.set_item("column_family", name)?;
+            corrupt.set_item("error", error)?;
+
+            corrupt_keys.append(corrupt)
+        };
+
+        for name in names.iter() {
+            let cf = match db.cf_handle(name) {
+                Some(cf) => cf,
+                None => continue,
+            };
+
+            // the entities are stored without the codec
+            let decode = self.codec.is_some() && !is_entity_family(name);
+            let mut opts = ReadOptions::default();
+
+            opts.set_verify_checksums(true);
+            opts.fill_cache(false);
+
+            let mut inner = db.raw_iterator_cf_opt(&cf, opts);
+            let mut skipped = HashSet::new();
+
+            py.allow_threads(|| inner.seek_to_first());
+
+            loop {
+                let (count, truncated, values) = py.allow_threads(|| {
+                    let mut count = 0;
+                    let mut truncated = Vec::new();
+                    let mut values = Vec::new();
+
+                    while count < VERIFY_BATCH {
+                        match inner.item() {
+                            Some((key, value)) if expiry && value.len() < HEADER => {
+                                truncated.push(key.to_vec())
+                            }
+                            Some((key, value)) if decode => {
+                                values.push((key.to_vec(), value.to_vec()))
+                            }
+                            Some(_) => {}
+                            None => break,
+                        }
+
+                        count += 1;
+                        inner.next();
+                    }
+
+                    (count, truncated, values)
+                });
+
+                for key in truncated {
+                    corrupt_key(&key, name, "Value has no expiration header".to_string())?;
+                }
+
+                for (key, value) in values {
+                    if let Err(e) = self.decode(py, &value) {
+                        corrupt_key(&key, name, e.to_string())?;
+                    }
+                }
+
+                keys += count as u64;
+
+                if let Some(progress) = &progress {
+                    progress.call1(py, (keys, total.max(keys)))?;
+                }
+
+                if inner.valid() {
+                    continue;
+                }
+
+                let e = match inner.status() {
+                    Ok(()) => break,
+                    Err(e) => e.to_string(),
+                };
+
+                // the same file fails again if its range cannot be skipped
+                let file = corrupt_file(&files, name, &e);
+
+                if matches!(file, Some(file) if skipped.contains(&file.name)) {
+                    break;
+                }
+
+                let corrupt = PyDict::new(py);
+
+                corrupt.set_item("name", file.map(|file| file.name.as_str()))?;
+                corrupt.set_item("column_family", name)?;
+                corrupt.set_item("error", e)?;
+                corrupt_files.append(corrupt)?;
+
+                match file.and_then(|file| file.end_key.as_ref().map(|end| (file, end))) {
+                    Some((file, end)) => {
+                        skipped.insert(file.name.clone());
+
+                        // the first key after the range of the file
+                        let next = [end.as_slice(), &[0]].concat();
+
+                        py.allow_threads(|| inner.seek(&next));
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        let report = PyDict::new(py);
+
+        report.set_item("ok", corrupt_files.is_empty() && corrupt_keys.is_empty())?;
+        report.set_item("keys", keys)?;
+        report.set_item("corrupt_files", corrupt_files)?;
+        report.set_item("corrupt_keys", corrupt_keys)?;
+
+        Ok(report)
+    }
+
+    /// Returns the value of the RocksDB property "name", e.g. `rocksdb.stats`, or None if the
+    /// property is not supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// stats = db.property('rocksdb.stats')
+    ///
+    /// keys = db.property('rocksdb.estimate-num-keys', cf='users')
+    /// ```
+    #[pyo3(signature = (name, cf=None))]
+    fn property(&self, py: Python, name: &str, cf: Option<&str>) -> PyResult<Option<String>> {
+        if let Some(db) = self.inner()? {
+            let value = py.allow_threads(|| match cf {
+                Some(cf) => cf_handle(db, cf).map(|cf| db.property_value_cf(&cf, name)),
+                None => Ok(db.property_value(name)),
+            })?;
+
+            match value {
+                Ok(value) => Ok(value),
+                Err(e) => Err(rocksdb_error(&e, format!("Property cannot get. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Property cannot get, database is closed"))
+        }
+    }
+
+    /// Returns the size and the number of the live SST files, in total and by column family.
+    /// The files which are obsolete but not deleted yet are not counted, they are reported by the
+    /// `rocksdb.total-sst-files-size` property.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// usage = db.disk_usage()
+    ///
+    /// print(usage['size'], usage['files'], usage['column_families']['users']['size'])
+    /// ```
+    fn disk_usage<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let db = self.handle("Disk usage cannot get")?;
+
+        let files = py
+            .allow_threads(|| db.live_files())
+            .map_err(|e| rocksdb_error(&e, format!("Disk usage cannot get. {}", e)))?;
+
+        let mut families: HashMap<&str, (u64, u64)> = HashMap::new();
+
+        for file in files.iter() {
+            let family = families.entry(file.column_family_name.as_str()).or_default();
+
+            family.0 += file.size as u64;
+            family.1 += 1;
+        }
+
+        let column_families = PyDict::new(py);
+
+        for (name, (size, count)) in families.iter() {
+            let family = PyDict::new(py);
+
+            family.set_item("size", size)?;
+            family.set_item("files", count)?;
+
+            column_families.set_item(name, family)?;
+        }
+
+        let usage = PyDict::new(py);
+
+        usage.set_item("size", files.iter().map(|f| f.size as u64).sum::<u64>())?;
+        usage.set_item("files", files.len())?;
+        usage.set_item("column_families", column_families)?;
+
+        Ok(usage)
+    }
+
+    /// Try to catch up with the primary by applying all the oplog entries.
+    /// This function is only useful for secondary instances.
+    ///
+    /// # Example
+    /// ```
+    /// db.try_catch_up_with_primary()
+    /// ```
+    fn try_catch_up_with_primary(&self, py: Python) -> PyResult<()> {
+        if let Some(db) = self.inner()? {
+            match py.allow_threads(|| db.try_catch_up_with_primary()) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(rocksdb_error(
+                    &e,
+                    format!("Database cannot catch up with primary. {}", e),
+                )),
+            }
+        } else {
+            Err(DBClosedError::new_err("Database cannot catch up with primary, database is closed"))
+        }
+    }
+
+    /// Returns how far a secondary is behind its primary. The result contains
+    /// `primary_sequence` which is the latest sequence number of the primary,
+    /// `secondary_sequence` which is the latest sequence number applied by the secondary and
+    /// `lag` which is the number of sequence numbers the secondary is behind.
+    ///
+    /// The primary is opened for read only to read its sequence number, which replays its
+    /// write ahead log, so the lag should be checked at the interval of the alerts rather than
+    /// on every read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// info = db.catch_up_lag()
+    ///
+    /// if info['lag'] > 100000:
+    ///     ...
+    /// ```
+    fn catch_up_lag<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let db = match self.inner()? {
+            Some(db) => db,
+            None => return Err(DBClosedError::new_err("Lag cannot get, database is closed")),
+        };
+
+        let primary = match &self.primary {
+            Some(primary) => primary,
+            None => {
+                return Err(RocksDBPyException::new_err(
+                    "Lag cannot get, database is not a secondary",
+                ))
+            }
+        };
+
+        let opts = &self.opts;
+
+        let sequences = py.allow_threads(|| {
+            let secondary_sequence = db.latest_sequence_number();
+            let cfs = descriptors(opts, primary);
+
+            DB::open_cf_descriptors_read_only(opts, primary, cfs, false)
+                .map(|primary| (primary.latest_sequence_number(), secondary_sequence))
+        });
+
+        let (primary_sequence, secondary_sequence) = match sequences {
+            Ok(sequences) => sequences,
+            Err(e) => return Err(rocksdb_error(&e, format!("Lag cannot get. {}", e))),
+        };
+
+        let info = PyDict::new(py);
+
+        info.set_item("primary_sequence", primary_sequence)?;
+        info.set_item("secondary_sequence", secondary_sequence)?;
+        info.set_item("lag", primary_sequence.saturating_sub(secondary_sequence))?;
+
+        Ok(info)
+    }
+
+    /// Returns the sequence number of the most recent write.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// seq = db.latest_sequence_number()
+    /// ```
+    fn latest_sequence_number(&self) -> PyResult<u64> {
+        match self.inner()? {
+            Some(db) => Ok(db.latest_sequence_number()),
+            None => Err(DBClosedError::new_err("Sequence number cannot get, database is closed")),
+        }
+    }
+
+    /// Returns the keys of the default column family which changed after the sequence number
+    /// `seq`, read from the write ahead log. Every key is returned once with its current value,
+    /// None if it is deleted. The result contains `sequence` which is the latest sequence
+    /// number of the changes and is passed to the next call, `changes` which is the list of the
+    /// (key, value) pairs in key order and `deleted_ranges` which is the list of the (start,
+    /// end) ranges which are deleted by `delete_range`. The keys and the values are decoded like
+    /// the results of `get`.
+    ///
+    /// The write ahead log must be kept since `seq`, see `Option.set_wal_ttl_seconds`,
+    /// otherwise the changes cannot be read and the keys must be synced again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// seq = db.latest_sequence_number()
+    ///
+    /// result = db.changes_since(seq)
+    ///
+    /// for key, value in result['changes']:
+    ///     ...
+    ///
+    /// seq = result['sequence']
+    /// ```
+    fn changes_since<'py>(&self, py: Python<'py>, seq: u64) -> PyResult<&'py PyDict> {
+        let db = self.handle("Changes cannot get")?;
+        let expiry = self.expiry;
+
+        // no write of the handle runs between the two, so the snapshot is at the latest sequence
+        // number and the values are read as of the last change which is returned
+        let (snapshot, latest) = {
+            let _guard = self.lock.exclusive(py);
+
+            (db.snapshot(), db.latest_sequence_number())
+        };
+
+        let (sequence, changes, ranges) = py.allow_threads(|| {
+            let (sequence, keys, ranges) = read_changes(db, seq, latest)?;
+            let mut changes = Vec::with_capacity(keys.len());
+
+            for key in keys {
+                let value = match snapshot.get(&key) {
+                    Ok(value) => value.and_then(|v| load(expiry, &v).map(|v| v.to_vec())),
+                    Err(e) => {
+                        return Err(rocksdb_error(&e, format!("Changes cannot get. {}", e)))
+                    }
+                };
+
+                changes.push((key, value));
+            }
+
+            Ok((sequence, changes, ranges))
+        })?;
+
+        let items = PyList::empty(py);
+
+        for (key, value) in changes.iter() {
+            let value = match value {
+                Some(value) => Some(decode_value(py, &self.codec, value)?),
+                None => None,
+            };
+
+            items.append((decode_key(py, self.text, key)?, value))?;
+        }
+
+        let deleted_ranges = PyList::empty(py);
+
+        for (start, end) in ranges.iter() {
+            deleted_ranges
+                .append((decode_key(py, self.text, start)?, decode_key(py, self.text, end)?))?;
+        }
+
+        let result = PyDict::new(py);
+
+        result.set_item("sequence", sequence)?;
+        result.set_item("changes", items)?;
+        result.set_item("deleted_ranges", deleted_ranges)?;
+
+        Ok(result)
+    }
+
+    /// Creates a consistent checkpoint of the database at the given path, which must not exist.
+    /// The SST files are hard-linked if the path is on the same filesystem, so the checkpoint
+    /// is much faster and smaller than a backup. The checkpoint can be opened as a database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.create_checkpoint('/path/to/checkpoint')
+    ///
+    /// clone = rocksdbpy.open_default('/path/to/checkpoint')
+    /// ```
+    fn create_checkpoint(&self, py: Python, path: PathBuf) -> PyResult<()> {
+        if let Some(db) = self.inner()? {
+            let created = py.allow_threads(|| {
+                Checkpoint::new(db)?.create_checkpoint(&path)
+            });
+
+            match created {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(&e, format!("Checkpoint cannot be created. {}", e))),
+            }
+        } else {
+            Err(DBClosedError::new_err("Checkpoint cannot be created, database is closed"))
+        }
+    }
+
+    /// Adds the SST files of `SstFileWriter` or of another database to the database
+    /// atomically. The files are moved into the database unless `move_files` is false, they
+    /// are copied then. If `ingest_behind` is true, the files are added below the existing
+    /// data, so they do not overwrite the keys of the database. It requires
+    /// `set_allow_ingest_behind` of the options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.ingest_external_file(['/tmp/data.sst'])
+    ///
+    /// db.ingest_external_file(['/tmp/data.sst'], move_files=False, cf='users')
+    /// ```
+    #[pyo3(signature = (paths, move_files=None, ingest_behind=None, cf=None))]
+    fn ingest_external_file(
+        &self,
+        py: Python,
+        paths: Vec<PathBuf>,
+        move_files: Option<bool>,
+        ingest_behind: Option<bool>,
+        cf: Option<&str>,
+    ) -> PyResult<()> {
+        if let Some(db) = self.inner()? {
+            let move_files = move_files.unwrap_or(true);
+            let ingest_behind = ingest_behind.unwrap_or(false);
+            let count = paths.len();
+            let _guard = self.lock.shared(py);
+
+            let result = py.allow_threads(|| {
+                let mut opts = IngestExternalFileOptions::default();
+
+                opts.set_move_files(move_files);
+                opts.set_ingest_behind(ingest_behind);
+
+                match cf {
+                    Some(cf) => cf_handle(db, cf)
+                        .map(|cf| db.ingest_external_file_cf_opts(&cf, &opts, paths)),
+                    None => Ok(db.ingest_external_file_opts(&opts, paths)),
+                }
+            })?;
+
+            match result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(rocksdb_error(
+                    &e,
+                    format!("Database cannot ingest {} files. {}", count, e),
+                )),
+            }
+        } else {
+            Err(DBClosedError::new_err("Database cannot ingest files, database is closed"))
+        }
+    }
+
+    /// Writes a consistent checkpoint of the database into the file object as an uncompressed
+    /// tar stream, so it can be uploaded without a copy on the local disk. The checkpoint is
+    /// hard-linked next to the database while it is written and removed afterwards. The
+    /// stream is restored by `RocksDB.restore_from_stream`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// with open('/tmp/rocksdb.tar', 'wb') as f:
+    ///     db.stream_checkpoint(f)
+    /// ```
+    fn stream_checkpoint(&self, py: Python, fileobj: &PyAny) -> PyResult<()> {
+        let db = match self.inner()? {
+            Some(db) => db,
+            None => {
+                return Err(DBClosedError::new_err(
+                    "Checkpoint cannot be streamed, database is closed",
+                ))
+            }
+        };
+
+        let dir = stream_dir(&self.path);
+
+        let created = py.allow_threads(|| Checkpoint::new(db)?.create_checkpoint(&dir));
+
+        if let Err(e) = created {
+            let _ = fs::remove_dir_all(&dir);
+
+            return Err(rocksdb_error(&e, format!("Checkpoint cannot be streamed. {}", e)));
+        }
+
+        let written = write_stream(py, &dir, fileobj);
+
+        let _ = py.allow_threads(|| fs::remove_dir_all(&dir));
+
+        written
+    }
+
+    /// Restores the tar stream of `stream_checkpoint` from the file object into the path, which
+    /// must be missing or empty. The restored path can be opened as a database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// with open('/tmp/rocksdb.tar', 'rb') as f:
+    ///     RocksDB.restore_from_stream(f, '/tmp/restore')
+    ///
+    /// db = rocksdbpy.open_default('/tmp/restore')
+    /// ```
+    #[staticmethod]
+    fn restore_from_stream(py: Python, fileobj: &PyAny, path: PathBuf) -> PyResult<()> {
+        read_stream(py, fileobj, &path)
+    }
+
+    /// Writes the records from "start" to "stop" into a file or a binary file object, so the
+    /// records can be moved to a database of another RocksDB version or read by other tools.
+    /// The format is `jsonl`, a JSON object per line with the base64 key and value, or `cbor`,
+    /// a CBOR sequence of [key, value] arrays. The stored bytes are written without the codec,
+    /// the expired records are skipped. Returns the number of the records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = db.dump('/tmp/rocksdb.jsonl')
+    ///
+    /// with open('/tmp/users.cbor', 'wb') as f:
+    ///     n = db.dump(f, format='cbor', start=b'user:', stop=b'user;', cf='users')
+    /// ```
+    #[pyo3(signature = (target, format=None, start=None, stop=None, cf=None))]
+    fn dump(
+        &self,
+        py: Python,
+        target: &PyAny,
+        format: Option<&str>,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        cf: Option<&str>,
+    ) -> PyResult<u64> {
+        let format = DumpFormat::parse(format)?;
+
+        let db = match self.inner()? {
+            Some(db) => db,
+            None => return Err(DBClosedError::new_err("Dump cannot write, database is closed")),
+        };
+
+        let opts = IteratorOptions {
+            lower_bound: self.key_bound(start)?,
+            upper_bound: self.key_bound(stop)?,
+            ..Default::default()
+        };
+
+        let mut inner = match cf {
+            Some(cf) => db.raw_iterator_cf_opt(&cf_handle(db, cf)?, opts.read_options()),
+            None => db.raw_iterator_opt(opts.read_options()),
+        };
+
+        let mut writer = DumpWriter::new(target, format)?;
+        let mut n = 0;
+
+        inner.seek_to_first();
+
+        while let (Some(key), Some(value)) = (inner.key(), inner.value()) {
+            if let Some(value) = load(self.expiry, value) {
+                writer.write(key, value)?;
+
+                n += 1;
+            }
+
+            inner.next();
+        }
+
+        if let Err(e) = inner.status() {
+            return Err(rocksdb_error(&e, format!("Dump cannot write. {}", e)));
+        }
+
+        writer.finish()?;
+
+        Ok(n)
+    }
+
+    /// Writes the records of a dump of `db.dump` into the database by batches, the existing
+    /// keys are overwritten. Returns the number of the records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = db.load('/tmp/rocksdb.jsonl')
+    ///
+    /// with open('/tmp/users.cbor', 'rb') as f:
+    ///     n = db.load(f, format='cbor', cf='users')
+    /// ```
+    #[pyo3(signature = (source, format=None, cf=None))]
+    fn load(
+        &self,
+        py: Python,
+        source: &PyAny,
+        format: Option<&str>,
+        cf: Option<&str>,
+    ) -> PyResult<u64> {
+        let format = DumpFormat::parse(format)?;
+        let mut reader = DumpReader::new(py, source, format)?;
+        let mut wr = WriteBatch::default();
+        let mut n = 0;
+
+        let db = self.handle("Dump cannot load")?;
+        let cf = cf.map(|cf| cf_handle(db, cf)).transpose()?;
+
+        while let Some((key, value)) = reader.next()? {
+            let value = self.store(&value, None)?;
+
+            match &cf {
+                Some(cf) => wr.put_cf(cf, key, value),
+                None => wr.put(key, value),
+            }
+
+            n += 1;
+
+            if wr.size_in_bytes() >= LOAD_BATCH_SIZE {
+                self.commit(py, mem::take(&mut wr))?;
+            }
+        }
+
+        if !wr.is_empty() {
+            self.commit(py, wr)?;
+        }
+
+        Ok(n)
+    }
+
+    /// Imports the (key, value) records of an iterable, e.g. the iterator of a plyvel
+    /// database. The records are written into SST files next to the database and ingested,
+    /// so they skip the memtable and the compactions. The keys must be in ascending order,
+    /// the stored bytes are written without the codec. Returns the number of the records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = db.ingest_records(plyvel.DB('/tmp/leveldb').iterator())
+    ///
+    /// n = db.ingest_records([(b'a', b'first'), (b'b', b'second')], cf='users')
+    /// ```
+    #[pyo3(signature = (records, cf=None))]
+    fn ingest_records(&self, py: Python, records: &PyAny, cf: Option<&str>) -> PyResult<u64> {
+        let db = self.handle("Records cannot import")?;
+        let dir = scratch_dir(&self.path, "import");
+
+        fs::create_dir_all(&dir).map_err(import_error)?;
+
+        let imported = (|| -> PyResult<u64> {
+            let mut files = SstFiles::new(&self.opts, &dir);
+
+            for record in records.iter()? {
+                let (key, value): (BytesLike, BytesLike) = record?.extract()?;
+                let value = self.store(value.as_bytes(), None)?;
+
+                files.put(key.as_bytes(), &value).map_err(import_error)?;
+            }
+
+            let (files, count) = files.finish().map_err(import_error)?;
+
+            self.ingest_files(py, db, files, cf)?;
+
+            Ok(count)
+        })();
+
+        let _ = fs::remove_dir_all(&dir);
+
+        imported
+    }
+
+    /// Imports the records of another database at "path", a RocksDB or a LevelDB database
+    /// which RocksDB can read, without passing them through Python. The source is opened for
+    /// read only, its records are written into SST files next to this database and ingested.
+    /// The values are copied as they are stored, so both databases should have the same key
+    /// TTL setting. Returns the number of the records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = db.import_from('/tmp/old')
+    ///
+    /// n = db.import_from('/tmp/old', cf='users', source_cf='users')
+    /// ```
+    #[pyo3(signature = (path, cf=None, source_cf=None))]
+    fn import_from(
+        &self,
+        py: Python,
+        path: PathBuf,
+        cf: Option<&str>,
+        source_cf: Option<&str>,
+    ) -> PyResult<u64> {
+        let db = self.handle("Records cannot import")?;
+        let dir = scratch_dir(&self.path, "import");
+        let opts = &self.opts;
+
+        fs::create_dir_all(&dir).map_err(import_error)?;
+
+        let written = py.allow_threads(|| {
+            let source_opts = Options::default();
+            let descriptors = descriptors(&source_opts, &path);
+
+            let source =
+                DB::open_cf_descriptors_read_only(&source_opts, &path, descriptors, false)
+                    .map_err(import_error)?;
+
+            let mut inner = match source_cf {
+                Some(cf) => source.raw_iterator_cf(&cf_handle(&source, cf)?),
+                None => source.raw_iterator(),
+            };
+
+            let mut files = SstFiles::new(opts, &dir);
+
+            inner.seek_to_first();
+
+            while let (Some(key), Some(value)) = (inner.key(), inner.value()) {
+                files.put(key, value).map_err(import_error)?;
+
+                inner.next();
+            }
+
+            inner.status().map_err(import_error)?;
+
+            files.finish().map_err(import_error)
+        });
+
+        let imported = written.and_then(|(files, count)| {
+            self.ingest_files(py, db, files, cf)?;
+
+            Ok(count)
+        });
+
+        let _ = fs::remove_dir_all(&dir);
+
+        imported
+    }
+
+    /// Copies the records of the range which includes `start` and excludes `stop` into `dst`,
+    /// another database or a column family of this database, without passing them through
+    /// Python. The records are written into SST files next to the destination and ingested, so
+    /// they overwrite the keys of the destination. The expired values are skipped and the
+    /// expiration header is kept if both databases have the key TTL enabled. Returns the number
+    /// of the records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = db.copy_range(shard, b'a', b'm')
+    ///
+    /// n = db.copy_range('archive', stop=b'2024', cf='events')
+    ///
+    /// n = db.copy_range(shard, cf='users', dst_cf='users')
+    /// ```
+    #[pyo3(signature = (dst, start=None, stop=None, cf=None, dst_cf=None))]
+    fn copy_range(
+        &self,
+        py: Python,
+        dst: &PyAny,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        cf: Option<&str>,
+        dst_cf: Option<&str>,
+    ) -> PyResult<u64> {
+        // a name is a column family of this database
+        let (target, dst_cf) = match dst.extract::<&str>() {
+            Ok(_) if dst_cf.is_some() => {
+                return Err(PyValueError::new_err(
+                    "Range cannot copy, dst_cf requires a database destination",
+                ))
+            }
+            Ok(name) => (None, Some(name)),
+            Err(_) => (Some(dst.extract::<PyRef<DBPy>>()?), dst_cf),
+        };
+
+        let target: &DBPy = target.as_deref().unwrap_or(self);
+        let db = self.handle("Range cannot copy")?;
+        let target_db = target.handle("Range cannot copy")?;
+
+        // the missing column families fail before the records are written
+        if let Some(cf) = cf {
+            cf_handle(db, cf)?;
+        }
+
+        if let Some(cf) = dst_cf {
+            cf_handle(target_db, cf)?;
+        }
+
+        let read = IteratorOptions {
+            lower_bound: self.key_bound(start)?,
+            upper_bound: self.key_bound(stop)?,
+            ..Default::default()
+        };
+
+        let dir = scratch_dir(&target.path, "copy");
+        let (opts, expiry, target_expiry) = (&target.opts, self.expiry, target.expiry);
+
+        fs::create_dir_all(&dir).map_err(copy_error)?;
+
+        let written = py.allow_threads(|| {
+            let mut inner = match cf {
+                Some(cf) => db.raw_iterator_cf_opt(&cf_handle(db, cf)?, read.read_options()),
+                None => db.raw_iterator_opt(read.read_options()),
+            };
+
+            let mut files = SstFiles::new(opts, &dir);
+
+            inner.seek_to_first();
+
+            while let (Some(key), Some(stored)) = (inner.key(), inner.value()) {
+                match (load(expiry, stored), expiry == target_expiry) {
+                    (None, _) => {}
+                    (Some(_), true) => files.put(key, stored).map_err(copy_error)?,
+                    (Some(value), false) if target_expiry => {
+                        files.put(key, &wrap(value, None)).map_err(copy_error)?
+                    }
+                    (Some(value), false) => files.put(key, value).map_err(copy_error)?,
+                }
+
+                inner.next();
+            }
+
+            inner.status().map_err(copy_error)?;
+
+            files.finish().map_err(copy_error)
+        });
+
+        let copied = written.and_then(|(files, count)| {
+            target.ingest_files(py, target_db, files, dst_cf)?;
+
+            Ok(count)
+        });
+
+        let _ = fs::remove_dir_all(&dir);
+
+        copied
+    }
+
+    /// Creates a consistent backup of the currently opened database at the given path.
+    ///
+    /// This method flushes memtables and stores a snapshot of the database in backup format,
+    /// which can later be restored using `RocksDB.restore_latest_backup(...)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.create_backup("/path/to/backup")
+    /// ```
+    fn create_backup(&self, py: Python, backup_path: PathBuf) -> PyResult<()> {
+        if let Some(db) = self.inner()? {
+            let mut backup_opts = match BackupEngineOptions::new(backup_path) {
+                Ok(opts) => opts,
+                Err(e) => {
+                    return Err(rocksdb_error(&e, format!("Failed to create backup options: {}", e)))
+                }
+            };
+
+            let env = rocksdb::Env::new().map_err(|e| {
+                rocksdb_error(&e, format!("Failed to create Env: {}", e))
+            })?;
+
+            let mut engine = match BackupEngine::open(&backup_opts, &env) {
+                Ok(engine) => engine,
+                Err(e) => {
+                    return Err(rocksdb_error(&e, format!("Failed to open backup engine: {}", e)))
+                }
+            };
+
+            if let Err(e) = py.allow_threads(|| engine.create_new_backup_flush(db, true)) {
+                return Err(rocksdb_error(&e, format!("Failed to create backup: {}", e)));
+            }
+
+            Ok(())
+        } else {
+            Err(DBClosedError::new_err("Database is closed"))
+        }
+    }
+
+    /// Restores the latest backup from a given backup directory into a new RocksDB instance.
+    ///
+    /// This static method reads the backup metadata and reconstructs the database at the specified path.
+    /// It can be used before opening the database with `open_default(...)`.
+    /// The write ahead log is restored into `wal_path` which defaults to `restore_path`, its
+    /// log files are kept if `keep_log_files` is true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// RocksDB.restore_latest_backup("/path/to/backup", "/path/to/restore")
+    /// db = RocksDB.open_default("/path/to/restore")
+    ///
+    /// RocksDB.restore_latest_backup("/path/to/backup", "/path/to/db", keep_log_files=True)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (backup_path, restore_path, wal_path=None, keep_log_files=None))]
+    fn restore_latest_backup(
+        py: Python,
+        backup_path: PathBuf,
+        restore_path: PathBuf,
+        wal_path: Option<PathBuf>,
+        keep_log_files: Option<bool>,
+    ) -> PyResult<()> {
+        let wal_path = wal_path.unwrap_or_else(|| restore_path.clone());
+
+        let backup_opts = match BackupEngineOptions::new(backup_path) {
+            Ok(opts) => opts,
+            Err(e) => {
+                return Err(rocksdb_error(&e, format!("Failed to create backup options: {}", e)))
+            }
+        };
+
+        let env = rocksdb::Env::new().map_err(|e| {
+            rocksdb_error(&e, format!("Failed to create Env: {}", e))
+        })?;
+
+        let mut engine = match BackupEngine::open(&backup_opts, &env) {
+            Ok(e) => e,
+            Err(e) => {
+                return Err(rocksdb_error(&e, format!("Failed to open backup engine: {}", e)))
+            }
+        };
+
+        let restored = py.allow_threads(|| {
+            let restore_opts = restore_options(keep_log_files);
+
+            engine.restore_from_latest_backup(&restore_path, &wal_path, &restore_opts)
+        });
+
+        if let Err(e) = restored {
+            return Err(rocksdb_error(&e, format!("Restore failed: {}", e)));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current write stall state of the database.
+    ///
+    /// The result contains `stopped` if writes are stopped, `delayed_write_rate` which is the
+    /// current delayed write rate in bytes per second (`0` if writes are not delayed) and
+    /// `stalled` if writes are stopped or delayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// info = db.stall_info()
+    ///
+    /// if info['stalled']:
+    ///     ...
+    /// ```
+    fn stall_info<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        if let Some(db) = self.inner()? {
+            StallState::read(db)?.to_dict(py)
+        } else {
+            Err(DBClosedError::new_err("Stall info cannot get, database is closed"))
+        }
+    }
+
+    /// Registers a callback which is called with the stall info when writes start or stop
+    /// stalling. The state is checked every `interval` seconds on a background thread, 0.1 by
+    /// default. Passing `None` removes the active callback.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// def on_stall(info):
+    ///     print(info['stalled'])
+    ///
+    /// db.on_write_stall(on_stall)
+    ///
+    /// db.on_write_stall(on_stall, 0.5)
+    ///
+    /// db.on_write_stall(None)
+    /// ```
+    #[pyo3(signature = (callback=None, interval=None))]
+    fn on_write_stall(
+        &self,
+        py: Python,
+        callback: Option<PyObject>,
+        interval: Option<f64>,
+    ) -> PyResult<()> {
+        let interval = match interval {
+            Some(seconds) => match Duration::try_from_secs_f64(seconds) {
+                Ok(interval) if !interval.is_zero() => interval,
+                _ => {
+                    return Err(PyValueError::new_err(
+                        "Stall callback requires a positive interval",
+                    ))
+                }
+            },
+            None => STALL_POLL,
+        };
+
+        let mut stall_watcher = lock_mutex(py, &self.stall_watcher);
+
+        if let Some(mut watcher) = stall_watcher.take() {
+            py.allow_threads(|| watcher.stop());
+        }
+
+        let callback = match callback {
+            Some(callback) => callback,
+            None => return Ok(()),
+        };
+
+        if let Some(db) = self.inner()? {
+            let mut stalled = StallState::read(db)?.stalled();
+            let db = Arc::downgrade(db);
+
+            *stall_watcher = Some(Worker::spawn(interval, move || {
+                let db = match db.upgrade() {
+                    Some(db) => db,
+                    None => return false,
+                };
+
+                let state = match StallState::read(&db) {
+                    Ok(state) => state,
+                    Err(_) => return true,
+                };
+
+                // only notify on transitions between stalled and not stalled
+                if state.stalled() != stalled {
+                    stalled = state.stalled();
+
+                    Python::with_gil(|py| {
+                        let result = state
+                            .to_dict(py)
+                            .and_then(|info| callback.call1(py, (info,)));
+
+                        if let Err(e) = result {
+                            e.print(py);
+                        }
+                    });
+                }
+
+                true
+            }));
+
+            Ok(())
+        } else {
+            Err(DBClosedError::new_err("Stall callback cannot register, database is closed"))
+        }
+    }
+
+    /// Flushes the memtables of all column families on a background thread every `interval`
+    /// seconds, or when the memtables have `max_bytes` of writes. If `wal` is true, the write
+    /// ahead log is synced as well, so the data loss window of a crash is bounded for the
+    /// writes which skip the sync. Calling it without limits stops the background flush.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.auto_flush(interval=60)
+    ///
+    /// db.auto_flush(interval=60, max_bytes=16 * 1024 * 1024, wal=True)
+    ///
+    /// db.auto_flush()
+    /// ```
+    #[pyo3(signature = (interval=None, max_bytes=None, wal=None))]
+    fn auto_flush(
+        &self,
+        py: Python,
+        interval: Option<f64>,
+        max_bytes: Option<u64>,
+        wal: Option<bool>,
+    ) -> PyResult<()> {
+        let mut auto_flusher = lock_mutex(py, &self.auto_flusher);
+
+        if let Some(mut flusher) = auto_flusher.take() {
+            py.allow_threads(|| flusher.stop());
+        }
+
+        if interval.is_none() && max_bytes.is_none() {
+            return Ok(());
+        }
+
+        let interval = match interval {
+            Some(interval) if !interval.is_finite() || interval <= 0.0 => {
+                return Err(PyValueError::new_err("Auto flush requires a positive interval"))
+            }
+            Some(interval) => Some(Duration::from_secs_f64(interval)),
+            None => None,
+        };
+
+        let db = match self.inner()? {
+            Some(db) => Arc::downgrade(db),
+            None => {
+                return Err(DBClosedError::new_err(
+                    "Auto flush cannot start, database is closed",
+                ))
+            }
+        };
+
+        if self.readonly {
+            return Err(RocksDBPyException::new_err(
+                "Auto flush cannot start, database is read only",
+            ));
+        }
+
+        let opts = self.opts.clone();
+        let path = self.path.clone();
+        let wal = wal.unwrap_or(false);
+
+        // the size of the memtables is checked often, the interval alone only wakes up to flush
+        let poll = match (interval, max_bytes) {
+            (Some(interval), None) => interval,
+            (Some(interval), Some(_)) => interval.min(AUTO_FLUSH_POLL),
+            (None, _) => AUTO_FLUSH_POLL,
+        };
+
+        let mut flushed = Instant::now();
+
+        *auto_flusher = Some(Worker::spawn(poll, move || {
+            let db = match db.upgrade() {
+                Some(db) => db,
+                None => return false,
+            };
+
+            // the column families are listed every time, they can be created after the start
+            let names = DB::list_cf(&opts, &path)
+                .unwrap_or_else(|_| vec![DEFAULT_COLUMN_FAMILY_NAME.to_string()]);
+
+            let due = interval.map_or(false, |interval| flushed.elapsed() >= interval)
+                || max_bytes.map_or(false, |max_bytes| memtable_size(&db, &names) >= max_bytes);
+
+            if due {
+                // a failed flush is retried by the next check
+                let _ = flush_all(&db, &names, wal);
+
+                flushed = Instant::now();
+            }
+
+            true
+        }));
+
+        Ok(())
+    }
+
+    /// Returns a new handle of the same database for another thread. The handles share the
+    /// database, the write lock and the settings, and the database is closed when the last
+    /// handle is closed. Calls of one handle from many threads are safe as well, except `close`
+    /// which must not run while another thread is using the handle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// handle = db.clone_handle()
+    ///
+    /// threading.Thread(target=worker, args=(handle,)).start()
+    /// ```
+    fn clone_handle(&self) -> PyResult<DBPy> {
+        let db = match self.inner()? {
+            Some(db) => db.clone(),
+            None => return Err(DBClosedError::new_err("Handle cannot clone, database is closed")),
+        };
+
+        Ok(DBPy {
+            path: self.path.clone(),
+            db: Some(db),
+            opts: self.opts.clone(),
+            readonly: self.readonly,
+            codec: self.codec.clone(),
+            text: self.text,
+            expiry: self.expiry,
+            lock: self.lock.clone(),
+            stall_watcher: Mutex::new(None),
+            auto_flusher: Mutex::new(None),
+            catch_up: self.catch_up.clone(),
+            primary: self.primary.clone(),
+            pid: self.pid,
+            queues: self.queues.clone(),
+        })
+    }
+
+    /// Close active database, the database stays open until every handle of it is closed.
+    /// If "flush" is true, the memtables of every column family and the write ahead log are
+    /// flushed to the disk first, so the next open does not replay the log. If "wait" is true,
+    /// the running and the pending compactions finish first. Calls after the close raise
+    /// `DBClosedError`.
+    ///
+    /// The LOCK file is released when the database is dropped. The iterators, cursors, pinned
+    /// values, writers, scans and compactions of the database keep it open after the close,
+    /// so opening it again raises `DBLockedError` until they are released.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// db.close()
+    ///
+    /// db.close(flush=True, wait=True)
+    /// ```
+    #[pyo3(signature = (flush=None, wait=None))]
+    fn close(&mut self, py: Python, flush: Option<bool>, wait: Option<bool>) -> PyResult<()> {
+        if self.forked() {
+            self.abandon();
+
+            return Ok(());
+        }
+
+        if let Some(db) = &self.db {
+            let flush = flush.unwrap_or(false) && !self.readonly;
+            let wait = wait.unwrap_or(false) && !self.readonly;
+
+            let names = self.cf_names(db);
+
+            let prepared = py.allow_threads(|| {
+                if flush {
+                    flush_all(db, &names, true)?;
+                }
+
+                if wait {
+                    db.wait_for_compact(&WaitForCompactOptions::default())?;
+                }
+
+                Ok::<_, rocksdb::Error>(())
+            });
+
+            // the database stays open if it cannot be flushed, so the close can be retried
+            if let Err(e) = prepared {
+                return Err(rocksdb_error(&e, format!("Database cannot close. {}", e)));
+            }
+        }
+
+        let stall_watcher = self.stall_watcher.get_mut().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(mut watcher) = stall_watcher.take() {
+            py.allow_threads(|| watcher.stop());
+        }
+
+        let auto_flusher = self.auto_flusher.get_mut().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(mut flusher) = auto_flusher.take() {
+            py.allow_threads(|| flusher.stop());
+        }
+
+        // the last handle stops the catch-up before the database is dropped
+        if let Some(worker) = self.catch_up.take() {
+            if let Ok(mut worker) = Arc::try_unwrap(worker) {
+                py.allow_threads(|| worker.stop());
+            }
+        }
+
+        // the memtables may be flushed when the last handle of the database is dropped
+        if let Some(db) = self.db.take() {
+            py.allow_threads(|| drop(db));
+        }
+
+        Ok(())
+    }
+}
+
+impl DBPy {
+    /// Returns the names of the column families which are open in the handle. A secondary
+    /// opens the column families of its primary, so they are listed at the primary.
+    fn cf_names(&self, db: &DB) -> Vec<String> {
+        let path = self.primary.as_ref().unwrap_or(&self.path);
+
+        DB::list_cf(&self.opts, path)
+            .unwrap_or_else(|_| vec![DEFAULT_COLUMN_FAMILY_NAME.to_string()])
+            .into_iter()
+            .filter(|name| db.cf_handle(name).is_some())
+            .collect()
+    }
+
+    pub fn new(db: DB, path: &Path) -> DBPy {
+        DBPy {
+            db: Some(Arc::new(db)),
+            path: path.to_path_buf(),
+            opts: Options::default(),
+            readonly: false,
+            codec: None,
+            text: false,
+            expiry: false,
+            lock: WriteLock::default(),
+            stall_watcher: Mutex::new(None),
+            auto_flusher: Mutex::new(None),
+            catch_up: None,
+            primary: None,
+            pid: process::id(),
+            queues: QueueHints::default(),
+        }
+    }
+
+    /// Sets the options of the database, new column families are created with them.
+    pub fn options(mut self, opts: Options) -> DBPy {
+        self.opts = opts;
+
+        self
+    }
+
+    /// Marks the database as opened for read only.
+    pub fn read_only(mut self, enabled: bool) -> DBPy {
+        self.readonly = enabled;
+
+        self
+    }
+
+    /// Sets the codec which serializes values, values are raw bytes without a codec.
+    pub fn codec(mut self, codec: Option<Codec>) -> DBPy {
+        self.codec = codec;
+
+        self
+    }
+
+    /// Enables the expiration header of the values for the key TTL.
+    pub fn expiry(mut self, enabled: bool) -> DBPy {
+        self.expiry = enabled;
+
+        self
+    }
+
+    /// Sets the path of the primary of a secondary.
+    pub fn primary(mut self, path: &Path) -> DBPy {
+        self.primary = Some(path.to_path_buf());
+
+        self
+    }
+
+    /// Starts a thread which catches up the secondary with the primary every interval. A
+    /// failed catch-up is retried on the next interval.
+    pub fn catch_up(mut self, interval: Option<Duration>) -> DBPy {
+        let db = match &self.db {
+            Some(db) => Arc::downgrade(db),
+            None => return self,
+        };
+
+        self.catch_up = interval.map(|interval| {
+            Arc::new(Worker::spawn(interval, move || match db.upgrade() {
+                Some(db) => {
+                    let _ = db.try_catch_up_with_primary();
+
+                    true
+                }
+                None => false,
+            }))
+        });
+
+        self
+    }
+
+    /// Returns true if the values have an expiration header.
+    pub fn key_ttl(&self) -> bool {
+        self.expiry
+    }
+
+    /// Returns the codec which serializes the values, `None` if the values are raw bytes.
+    pub(crate) fn value_codec(&self) -> Option<Codec> {
+        self.codec.clone()
+    }
+
+    /// Adds the expiration header to the value if the key TTL is enabled.
+    fn store<'a>(&self, value: &'a [u8], ttl: Option<u64>) -> PyResult<Cow<'a, [u8]>> {
+        match (self.expiry, ttl) {
+            (true, ttl) => Ok(Cow::Owned(wrap(value, ttl))),
+            (false, None) => Ok(Cow::Borrowed(value)),
+            (false, Some(_)) => Err(RocksDBPyException::new_err(
+                "Record cannot set with a TTL, key TTL is not enabled by the options",
+            )),
+        }
+    }
+
+    /// Encodes the value by the codec, with the expiration header if the key TTL is enabled.
+    pub(crate) fn encode(&self, py: Python, value: &PyAny) -> PyResult<Vec<u8>> {
+        let value = encode_value(py, &self.codec, value)?;
+
+        Ok(self.store(value.as_bytes(), None)?.into_owned())
+    }
+
+    /// Decodes the stored value by the codec, `None` if the value is expired.
+    pub(crate) fn decode(&self, py: Python, stored: &[u8]) -> PyResult<Option<PyObject>> {
+        match load(self.expiry, stored) {
+            Some(value) => Ok(Some(decode_value(py, &self.codec, value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the open database, `action` prefixes the error if the database is closed.
+    pub(crate) fn handle(&self, action: &str) -> PyResult<&DB> {
+        match self.inner()? {
+            Some(db) => Ok(db),
+            None => Err(DBClosedError::new_err(format!(
+                "{}, database is closed",
+                action
+            ))),
+        }
+    }
+
+    /// Returns the database, `None` if it is closed. The database raises in a forked child,
+    /// RocksDB does not support using it from the child of the process which opened it.
+    pub(crate) fn inner(&self) -> PyResult<Option<&Arc<DB>>> {
+        if self.db.is_some() && self.forked() {
+            return Err(DBForkedError::new_err(
+                "Database cannot be used after fork, open it again in the child process",
+            ));
+        }
+
+        Ok(self.db.as_ref())
+    }
+
+    /// Returns true if the process is a forked child of the process which opened the database.
+    fn forked(&self) -> bool {
+        self.pid != process::id()
+    }
+
+    /// Releases the database without closing it. The background threads of RocksDB do not exist
+    /// in a forked child, and closing the database would flush the memtables of the parent.
+    fn abandon(&mut self) {
+        if let Some(db) = self.db.take() {
+            mem::forget(db);
+        }
+
+        let stall_watcher = self.stall_watcher.get_mut().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(watcher) = stall_watcher.take() {
+            mem::forget(watcher);
+        }
+
+        let auto_flusher = self.auto_flusher.get_mut().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(flusher) = auto_flusher.take() {
+            mem::forget(flusher);
+        }
+
+        if let Some(worker) = self.catch_up.take() {
+            mem::forget(worker);
+        }
+    }
+
+    /// Returns the lock which orders the writes of the database.
+    pub(crate) fn write_lock(&self) -> &WriteLock {
+        &self.lock
+    }
+
+    /// Returns the bounds of the live entries of the queues of the database.
+    pub(crate) fn queue_hints(&self) -> &QueueHints {
+        &self.queues
+    }
+
+    /// Returns the column family, it is created with the options of the database if it is
+    /// missing.
+    pub(crate) fn column_family<'a>(
+        &self,
+        db: &'a DB,
+        name: &str,
+    ) -> PyResult<Arc<BoundColumnFamily<'a>>> {
+        if db.cf_handle(name).is_none() {
+            if let Err(e) = db.create_cf(name, &self.opts) {
+                return Err(rocksdb_error(&e, format!("Column family cannot be created. {}", e)));
+            }
+        }
+
+        match db.cf_handle(name) {
+            Some(cf) => Ok(cf),
+            None => Err(RocksDBPyException::new_err(format!(
+                "Column family {} cannot be found",
+                name
+            ))),
+        }
+    }
+
+    /// Converts the key argument to bytes by the key mode of the database.
+    pub fn key<'a>(&self, key: &'a PyAny) -> PyResult<Encoded<'a>> {
+        encode_key(self.text, key)
+    }
+
+    /// Converts the optional bound of a range to bytes by the key mode of the database.
+    fn key_bound(&self, key: Option<&PyAny>) -> PyResult<Option<Vec<u8>>> {
+        key.map(|key| Ok(self.key(key)?.as_bytes().to_vec())).transpose()
+    }
+
+    /// Returns true if `str` keys are accepted and the keys are returned as `str`.
+    pub(crate) fn text_keys(&self) -> bool {
+        self.text
+    }
+
+    /// Accepts `str` keys as UTF-8, values are decoded as `str` unless a codec is set.
+    pub fn text(mut self, enabled: bool) -> DBPy {
+        self.text = enabled;
+
+        if enabled && self.codec.is_none() {
+            self.codec = Some(Codec::Text);
+        }
+
+        self
+    }
+
+    /// Returns the values of the keys by the batched MultiGet API of RocksDB without holding
+    /// the GIL.
+    fn fetch_many(&self, py: Python, keys: &[&[u8]]) -> PyResult<Vec<Option<Vec<u8>>>> {
+        if let Some(db) = self.inner()? {
+            let expiry = self.expiry;
+
+            py.allow_threads(|| {
+                let cf = match db.cf_handle(DEFAULT_COLUMN_FAMILY_NAME) {
+                    Some(cf) => cf,
+                    None => return Err(RocksDBPyException::new_err("Record cannot get")),
+                };
+
+                db.batched_multi_get_cf(&cf, keys.iter(), false)
+                    .into_iter()
+                    .map(|value| match value {
+                        Ok(value) => Ok(value.and_then(|v| load(expiry, &v).map(|v| v.to_vec()))),
+                        Err(e) => Err(rocksdb_error(&e, format!("Record cannot get. {}", e))),
+                    })
+                    .collect()
+            })
+        } else {
+            Err(DBClosedError::new_err("Record cannot get, database is closed"))
+        }
+    }
+
+    /// Writes the batch to the database without holding the GIL.
+    fn commit(&self, py: Python, wr: WriteBatch) -> PyResult<()> {
+        let len = wr.len();
+
+        if let Some(db) = self.inner()? {
+            let _guard = self.lock.shared(py);
+
+            match py.allow_threads(|| db.write(wr)) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(rocksdb_error(
+                    &e,
+                    format!("Batch cannot write {} elements. {}", len, e),
+                )),
+            }
+        } else {
+            Err(DBClosedError::new_err(format!(
+                "Batch cannot write {} elements, database is closed",
+                len
+            )))
+        }
+    }
+
+    /// Moves the SST files of an import into the database.
+    fn ingest_files(
+        &self,
+        py: Python,
+        db: &DB,
+        files: Vec<PathBuf>,
+        cf: Option<&str>,
+    ) -> PyResult<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let _guard = self.lock.shared(py);
+
+        let result = py.allow_threads(|| {
+            let mut opts = IngestExternalFileOptions::default();
+
+            opts.set_move_files(true);
+
+            match cf {
+                Some(cf) => {
+                    cf_handle(db, cf).map(|cf| db.ingest_external_file_cf_opts(&cf, &opts, files))
+                }
+                None => Ok(db.ingest_external_file_opts(&opts, files)),
+            }
+        })?;
+
+        result.map_err(import_error)
+    }
+
+    /// Returns an iterator over the range which is limited by the iterator bounds. The reverse
+    /// iterator starts from the last key before the upper bound, so the exclusive stop key is
+    /// never returned.
+    fn range(
+        &self,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: bool,
+        keys: bool,
+        values: bool,
+    ) -> PyResult<IteratorPy> {
+        let seek = if reverse {
+            IteratorSeek::End
+        } else {
+            IteratorSeek::Start
+        };
+
+        let opts = IteratorOptions {
+            lower_bound: self.key_bound(start)?,
+            upper_bound: self.key_bound(stop)?,
+            expiry: self.expiry,
+            ..Default::default()
+        };
+
+        if let Some(db) = self.inner()? {
+            Ok(IteratorPy::new(db, seek, opts)
+                .select(keys, values)
+                .decoded(self.codec.clone())
+                .text(self.text))
+        } else {
+            Err(DBClosedError::new_err("Iterator cannot get, database is closed"))
+        }
+    }
+}
+
+impl Drop for DBPy {
+    fn drop(&mut self) {
+        if self.forked() {
+            self.abandon();
+        }
+    }
+}
+
+/// Reads the write ahead log after the sequence number `since` up to `latest`, and returns the
+/// last sequence number which is read, the changed keys and the deleted ranges of the default
+/// column family.
+fn read_changes(
+    db: &DB,
+    since: u64,
+    latest: u64,
+) -> PyResult<(u64, BTreeSet<Vec<u8>>, Vec<(Vec<u8>, Vec<u8>)>)> {
+    let mut keys = BTreeSet::new();
+    let mut ranges = Vec::new();
+
+    if since >= latest {
+        return Ok((since, keys, ranges));
+    }
+
+    let updates = match db.get_updates_since(since + 1) {
+        Ok(updates) => updates,
+        Err(e) => return Err(rocksdb_error(&e, format!("Changes cannot get. {}", e))),
+    };
+
+    let mut expected = since + 1;
+
+    for update in updates {
+        let (first, batch) = match update {
+            Ok(update) => update,
+            Err(e) => return Err(rocksdb_error(&e, format!("Changes cannot get. {}", e))),
+        };
+
+        // the batches of the deleted log files are missing, the changes would be incomplete
+        if first > expected {
+            return Err(RocksDBPyException::new_err(format!(
+                "Changes cannot get, the write ahead log after {} is deleted",
+                since
+            )));
+        }
+
+        let records = match parse(batch.data()) {
+            Ok(records) => records,
+            Err(e) => {
+                return Err(RocksDBPyException::new_err(format!("Changes cannot get. {}", e)))
+            }
+        };
+
+        // every record of a batch has the next sequence number
+        for (record, seq) in records.iter().zip(first..) {
+            if seq <= since || seq > latest || record.cf != 0 {
+                continue;
+            }
+
+            match (record.op, record.value) {
+                (Operation::DeleteRange, Some(end)) => {
+                    ranges.push((record.key.to_vec(), end.to_vec()))
+                }
+                _ => {
+                    keys.insert(record.key.to_vec());
+                }
+            }
+        }
+
+        expected = first + records.len() as u64;
+
+        if expected > latest {
+            break;
+        }
+    }
+
+    Ok((latest, keys, ranges))
+}
+
+/// Returns the SST file of the column family which is named by the error of a read.
+fn corrupt_file<'a>(files: &'a [LiveFile], cf: &str, error: &str) -> Option<&'a LiveFile> {
+    files
+        .iter()
+        .find(|file| file.column_family_name == cf && error.contains(file.name.as_str()))
+}
+
+/// Returns the size of the memtables of the column families in bytes.
+fn memtable_size(db: &DB, names: &[String]) -> u64 {
+    names
+        .iter()
+        .filter_map(|name| db.cf_handle(name))
+        .filter_map(|cf| {
+            db.property_int_value_cf(&cf, "rocksdb.cur-size-all-mem-tables")
+                .ok()
+                .flatten()
+        })
+        .sum()
+}
+
+/// Flushes the memtables of the column families, and syncs the write ahead log if `wal` is
+/// true.
+fn flush_all(db: &DB, names: &[String], wal: bool) -> Result<(), rocksdb::Error> {
+    for name in names {
+        if let Some(cf) = db.cf_handle(name) {
+            db.flush_cf(&cf)?;
+        }
+    }
+
+    if wal {
+        db.flush_wal(true)?;
+    }
+
+    Ok(())
+}
+
+fn import_error(e: impl std::fmt::Display) -> PyErr {
+    RocksDBPyException::new_err(format!("Records cannot import. {}", e))
+}
+
+fn copy_error(e: impl std::fmt::Display) -> PyErr {
+    RocksDBPyException::new_err(format!("Range cannot copy. {}", e))
+}
+
+fn entity_error(e: rocksdb::Error) -> PyErr {
+    rocksdb_error(&e, format!("Entity cannot get. {}", e))
+}
+
+/// Removes the key of the column family and its entity as one batch. A missing column family
+/// fails before the batch is written.
+fn delete_with_entity(
+    db: &DB,
+    cf: Option<&str>,
+    key: &[u8],
+) -> PyResult<Result<(), rocksdb::Error>> {
+    let mut wr = WriteBatch::default();
+
+    match cf {
+        Some(cf) => wr.delete_cf(&cf_handle(db, cf)?, key),
+        None => wr.delete(key),
+    }
+
+    delete_entity(db, &mut wr, cf, key);
+
+    Ok(db.write(wr))
+}
+
+/// Estimates the number of keys in the range by the SST files which overlap the range, the
+/// whole database is estimated by the `rocksdb.estimate-num-keys` property.
+fn estimate_count(db: &DB, start: Option<&[u8]>, stop: Option<&[u8]>) -> PyResult<u64> {
+    if start.is_none() && stop.is_none() {
+        return match db.property_int_value("rocksdb.estimate-num-keys") {
+            Ok(value) => Ok(value.unwrap_or(0)),
+            Err(e) => Err(rocksdb_error(&e, format!("Keys cannot estimate. {}", e))),
+        };
+    }
+
+    let files = match db.live_files() {
+        Ok(files) => files,
+        Err(e) => {
+            return Err(rocksdb_error(&e, format!("Keys cannot estimate. {}", e)))
+        }
+    };
+
+    let mut n = 0;
+
+    // the files of the other column families do not hold the keys of the default one
+    for file in files.iter().filter(|f| f.column_family_name == DEFAULT_COLUMN_FAMILY_NAME) {
+        // skip files which end before the start or begin at or after the stop key
+        let before = match (&file.end_key, start) {
+            (Some(end), Some(start)) => end.as_slice() < start,
+            _ => false,
+        };
+
+        let after = match (&file.start_key, stop) {
+            (Some(first), Some(stop)) => first.as_slice() >= stop,
+            _ => false,
+        };
+
+        if !before && !after {
+            n += file.num_entries.saturating_sub(file.num_deletions);
+        }
+    }
+
+    Ok(n)
+}
+
+/// Write stall state which is read from the database properties.
+struct StallState {
+    stopped: bool,
+    delayed_write_rate: u64,
+}
+
+impl StallState {
+    fn read(db: &DB) -> PyResult<StallState> {
+        let property = |name: &str| match db.property_int_value(name) {
+            Ok(value) => Ok(value.unwrap_or(0)),
+            Err(e) => Err(rocksdb_error(&e, format!("Property {} cannot get. {}", name, e))),
+        };
+
+        Ok(StallState {
+            stopped: property("rocksdb.is-write-stopped")? != 0,
+            delayed_write_rate: property("rocksdb.actual-delayed-write-rate")?,
+        })
+    }
+
+    fn stalled(&self) -> bool {
+        self.stopped || self.delayed_write_rate > 0
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let info = PyDict::new(py);
+
+        info.set_item("stopped", self.stopped)?;
+        info.set_item("delayed_write_rate", self.delayed_write_rate)?;
+        info.set_item("stalled", self.stalled())?;
+
+        Ok(info)
     }
 }
@@ -0,0 +1,343 @@
+use crate::base::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+/// Size of the buffers of the dump files.
+const BUFFER_SIZE: usize = 1 << 20;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Format of the dump files. The records are written one by one, so a dump is streamed.
+#[derive(Clone, Copy)]
+pub enum DumpFormat {
+    /// A JSON object per line, the key and the value are base64 strings.
+    Jsonl,
+    /// A CBOR sequence, every record is an array of the key and the value byte strings.
+    Cbor,
+}
+
+impl DumpFormat {
+    pub fn parse(format: Option<&str>) -> PyResult<DumpFormat> {
+        match format.unwrap_or("jsonl") {
+            "jsonl" => Ok(DumpFormat::Jsonl),
+            "cbor" => Ok(DumpFormat::Cbor),
+            other => Err(PyValueError::new_err(format!(
+                "Format is not supported, {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Writes the records of a dump.
+pub struct DumpWriter<'py> {
+    inner: BufWriter<Box<dyn Write + 'py>>,
+    format: DumpFormat,
+}
+
+impl<'py> DumpWriter<'py> {
+    /// Writes into the file at the path, or into the file object by its `write` method.
+    pub fn new(target: &'py PyAny, format: DumpFormat) -> PyResult<Self> {
+        let inner: Box<dyn Write + 'py> = match target.extract::<PathBuf>() {
+            Ok(path) => Box::new(File::create(&path).map_err(dump_error)?),
+            Err(_) => Box::new(FileObject(target)),
+        };
+
+        Ok(DumpWriter {
+            inner: BufWriter::with_capacity(BUFFER_SIZE, inner),
+            format,
+        })
+    }
+
+    pub fn write(&mut self, key: &[u8], value: &[u8]) -> PyResult<()> {
+        let record = match self.format {
+            DumpFormat::Jsonl => format!(
+                "{{\"key\":\"{}\",\"value\":\"{}\"}}\n",
+                encode_base64(key),
+                encode_base64(value)
+            )
+            .into_bytes(),
+            DumpFormat::Cbor => {
+                let mut record = vec![0x82];
+
+                cbor_bytes(&mut record, key);
+                cbor_bytes(&mut record, value);
+
+                record
+            }
+        };
+
+        self.inner.write_all(&record).map_err(dump_error)
+    }
+
+    pub fn finish(mut self) -> PyResult<()> {
+        self.inner.flush().map_err(dump_error)
+    }
+}
+
+/// Reads the records of a dump.
+pub struct DumpReader<'py> {
+    inner: BufReader<Box<dyn Read + 'py>>,
+    format: DumpFormat,
+    py: Python<'py>,
+    line: usize,
+}
+
+impl<'py> DumpReader<'py> {
+    /// Reads from the file at the path, or from the file object by its `read` method.
+    pub fn new(py: Python<'py>, source: &'py PyAny, format: DumpFormat) -> PyResult<Self> {
+        let inner: Box<dyn Read + 'py> = match source.extract::<PathBuf>() {
+            Ok(path) => Box::new(File::open(&path).map_err(load_error)?),
+            Err(_) => Box::new(FileObject(source)),
+        };
+
+        Ok(DumpReader {
+            inner: BufReader::with_capacity(BUFFER_SIZE, inner),
+            format,
+            py,
+            line: 0,
+        })
+    }
+
+    /// Returns the next (key, value) record, or None at the end of the dump.
+    pub fn next(&mut self) -> PyResult<Option<(Vec<u8>, Vec<u8>)>> {
+        match self.format {
+            DumpFormat::Jsonl => self.next_line(),
+            DumpFormat::Cbor => self.next_item(),
+        }
+    }
+
+    fn next_line(&mut self) -> PyResult<Option<(Vec<u8>, Vec<u8>)>> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            self.line += 1;
+
+            if self.inner.read_line(&mut line).map_err(load_error)? == 0 {
+                return Ok(None);
+            }
+
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+
+        let invalid = || {
+            RocksDBPyException::new_err(format!("Dump cannot load, line {} is invalid", self.line))
+        };
+
+        let json = self.py.import("json")?;
+        let record: &PyDict = json
+            .call_method1("loads", (line.as_str(),))
+            .map_err(|_| invalid())?
+            .downcast()
+            .map_err(|_| invalid())?;
+
+        let field = |name: &str| -> PyResult<Vec<u8>> {
+            let value: &str = match record.get_item(name) {
+                Some(value) => value.extract().map_err(|_| invalid())?,
+                None => return Err(invalid()),
+            };
+
+            decode_base64(value).ok_or_else(invalid)
+        };
+
+        Ok(Some((field("key")?, field("value")?)))
+    }
+
+    fn next_item(&mut self) -> PyResult<Option<(Vec<u8>, Vec<u8>)>> {
+        let mut head = [0; 1];
+
+        match self.inner.read(&mut head).map_err(load_error)? {
+            0 => return Ok(None),
+            _ if head[0] == 0x82 => {}
+            _ => {
+                return Err(RocksDBPyException::new_err(
+                    "Dump cannot load, a record is not an array of two byte strings",
+                ))
+            }
+        }
+
+        let key = self.cbor_bytes()?;
+        let value = self.cbor_bytes()?;
+
+        Ok(Some((key, value)))
+    }
+
+    fn cbor_bytes(&mut self) -> PyResult<Vec<u8>> {
+        let head = self.read_exact(1)?[0];
+
+        if head >> 5 != 2 {
+            return Err(RocksDBPyException::new_err(
+                "Dump cannot load, a record is not an array of two byte strings",
+            ));
+        }
+
+        let len = match head & 0x1f {
+            n @ 0..=23 => n as u64,
+            n @ 24..=27 => self
+                .read_exact(1 << (n - 24))?
+                .iter()
+                .fold(0, |len, byte| len << 8 | *byte as u64),
+            _ => {
+                return Err(RocksDBPyException::new_err(
+                    "Dump cannot load, indefinite length byte strings are not supported",
+                ))
+            }
+        };
+
+        // the length is read from the input, so the buffer grows with the bytes which are read
+        // instead of being allocated for the length up front
+        let mut buf = Vec::new();
+
+        match self.inner.by_ref().take(len).read_to_end(&mut buf) {
+            Ok(n) if n as u64 == len => Ok(buf),
+            Ok(_) => Err(RocksDBPyException::new_err("Dump cannot load, it is truncated")),
+            Err(e) => Err(load_error(e)),
+        }
+    }
+
+    fn read_exact(&mut self, len: usize) -> PyResult<Vec<u8>> {
+        let mut buf = vec![0; len];
+
+        match self.inner.read_exact(&mut buf) {
+            Ok(()) => Ok(buf),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(
+                RocksDBPyException::new_err("Dump cannot load, it is truncated"),
+            ),
+            Err(e) => Err(load_error(e)),
+        }
+    }
+}
+
+/// Binary file object of Python, e.g. a file which is opened in `wb` or `rb` mode.
+struct FileObject<'py>(&'py PyAny);
+
+impl<'py> Write for FileObject<'py> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let py = self.0.py();
+
+        match self.0.call_method1("write", (PyBytes::new(py, buf),)) {
+            Ok(_) => Ok(buf.len()),
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'py> Read for FileObject<'py> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk = self
+            .0
+            .call_method1("read", (buf.len(),))
+            .and_then(|chunk| chunk.extract::<&[u8]>());
+
+        match chunk {
+            Ok(chunk) => {
+                let n = chunk.len().min(buf.len());
+
+                buf[..n].copy_from_slice(&chunk[..n]);
+
+                Ok(n)
+            }
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+        }
+    }
+}
+
+fn dump_error(e: io::Error) -> PyErr {
+    RocksDBPyException::new_err(format!("Dump cannot write. {}", e))
+}
+
+fn load_error(e: io::Error) -> PyErr {
+    RocksDBPyException::new_err(format!("Dump cannot load. {}", e))
+}
+
+/// Appends the CBOR head of a byte string and its bytes.
+fn cbor_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    let len = bytes.len() as u64;
+
+    match len {
+        0..=23 => out.push(0x40 | len as u8),
+        24..=0xff => out.extend_from_slice(&[0x58, len as u8]),
+        0x100..=0xffff => {
+            out.push(0x59);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            out.push(0x5a);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(0x5b);
+            out.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+
+    out.extend_from_slice(bytes);
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+
+    for chunk in text.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+
+        let mut n = 0u32;
+
+        for (i, c) in chunk.iter().enumerate() {
+            n |= base64_digit(*c)? << (18 - 6 * i);
+        }
+
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    Some(out)
+}
+
+fn base64_digit(c: u8) -> Option<u32> {
+    let digit = match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    };
+
+    Some(digit as u32)
+}
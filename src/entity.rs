@@ -0,0 +1,248 @@
+use crate::base::*;
+use crate::buffer::*;
+use crate::codec::*;
+use crate::iterator::*;
+use crate::ttl::*;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyString};
+use rocksdb::{Direction, WriteBatch, DEFAULT_COLUMN_FAMILY_NAME};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Prefix of the names of the column families which keep the entities. The entities of a
+/// column family are stored in a column family of their own, so a plain value is never read
+/// as an entity. A plain value is read as an entity which only has the default column, its
+/// name is empty.
+const ENTITY_FAMILY: &str = "rocksdbpy.entities.";
+
+/// Iterator over the (key, columns) entries of the database. The plain values and the
+/// entities are merged by the bytewise order of their keys, a plain value is returned as the
+/// default column.
+#[pyclass(name = "EntityIterator")]
+pub struct EntityIteratorPy {
+    plain: DBRawIterator<'static>,
+    entities: Option<DBRawIterator<'static>>,
+    direction: Direction,
+    expiry: bool,
+    text: bool,
+    _db: Arc<DB>,
+}
+
+#[pymethods]
+impl EntityIteratorPy {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    /// Returns the next (key, columns) entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// key, columns = next(iterator)
+    /// ```
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        let (direction, expiry) = (self.direction, self.expiry);
+
+        skip_expired(&mut self.plain, direction, expiry);
+
+        if let Some(entities) = &mut self.entities {
+            skip_expired(entities, direction, expiry);
+        }
+
+        let plain = current(&self.plain, expiry);
+        let entity = self.entities.as_ref().and_then(|entities| current(entities, expiry));
+
+        // the plain value of a key is written after its entity, `put_entity` removes it
+        let (key, columns, plain_step, entity_step) = match (plain, entity) {
+            (Some((key, value)), Some((other, _))) if before(direction, key, other) => {
+                (key, default_column(py, value)?, true, key == other)
+            }
+            (_, Some((key, value))) => (key, decode_entity(py, value)?, false, true),
+            (Some((key, value)), None) => (key, default_column(py, value)?, true, false),
+            (None, None) => {
+                check(&self.plain)?;
+
+                if let Some(entities) = &self.entities {
+                    check(entities)?;
+                }
+
+                return Ok(None);
+            }
+        };
+
+        let key = decode_key(py, self.text, key)?;
+
+        if plain_step {
+            step(&mut self.plain, direction);
+        }
+
+        if let Some(entities) = self.entities.as_mut().filter(|_| entity_step) {
+            step(entities, direction);
+        }
+
+        Ok(Some((key, columns).into_py(py)))
+    }
+}
+
+impl EntityIteratorPy {
+    /// Creates an iterator over the default column family and its entities, the bounds of
+    /// the options limit both.
+    pub fn new(db: &Arc<DB>, opts: &IteratorOptions, reverse: bool) -> EntityIteratorPy {
+        let mut plain = raw_iterator(db, opts);
+        let mut entities = db
+            .cf_handle(&entity_family(None))
+            .map(|cf| raw_iterator_cf(db, &cf, opts));
+
+        let direction = if reverse {
+            Direction::Reverse
+        } else {
+            Direction::Forward
+        };
+
+        for inner in std::iter::once(&mut plain).chain(entities.as_mut()) {
+            match direction {
+                Direction::Forward => inner.seek_to_first(),
+                Direction::Reverse => inner.seek_to_last(),
+            }
+        }
+
+        EntityIteratorPy {
+            plain,
+            entities,
+            direction,
+            expiry: opts.expiry,
+            text: false,
+            _db: db.clone(),
+        }
+    }
+
+    /// Returns the keys as `str` decoded from UTF-8 if the text mode is enabled.
+    pub fn text(mut self, enabled: bool) -> EntityIteratorPy {
+        self.text = enabled;
+
+        self
+    }
+}
+
+/// Returns the name of the column family which keeps the entities of the column family.
+pub fn entity_family(cf: Option<&str>) -> String {
+    format!("{}{}", ENTITY_FAMILY, cf.unwrap_or(DEFAULT_COLUMN_FAMILY_NAME))
+}
+
+/// Returns true if the column family keeps the entities of another column family.
+pub fn is_entity_family(name: &str) -> bool {
+    name.starts_with(ENTITY_FAMILY)
+}
+
+/// Adds the delete of the entity of the key to the batch, if the column family has entities.
+pub fn delete_entity(db: &DB, wr: &mut WriteBatch, cf: Option<&str>, key: &[u8]) {
+    if let Some(entities) = db.cf_handle(&entity_family(cf)) {
+        wr.delete_cf(&entities, key);
+    }
+}
+
+/// Adds the delete of the entities in the range to the batch, if the column family has
+/// entities.
+pub fn delete_entity_range(db: &DB, wr: &mut WriteBatch, cf: Option<&str>, from: &[u8], to: &[u8]) {
+    if let Some(entities) = db.cf_handle(&entity_family(cf)) {
+        wr.delete_range_cf(&entities, from, to);
+    }
+}
+
+/// Returns the current entry of the iterator without the expiration header. The expired
+/// entries must be skipped first.
+#[inline]
+fn current<'a>(inner: &'a DBRawIterator, expiry: bool) -> Option<(&'a [u8], &'a [u8])> {
+    let (key, value) = inner.item()?;
+
+    Some((key, load(expiry, value)?))
+}
+
+/// Returns true if the key is returned before or together with the other key.
+#[inline]
+fn before(direction: Direction, key: &[u8], other: &[u8]) -> bool {
+    match direction {
+        Direction::Forward => key.cmp(other) != Ordering::Greater,
+        Direction::Reverse => key.cmp(other) != Ordering::Less,
+    }
+}
+
+/// Returns a plain value as the columns of an entity, its only column is the default one.
+pub fn default_column<'py>(py: Python<'py>, value: &[u8]) -> PyResult<&'py PyDict> {
+    let columns = PyDict::new(py);
+
+    columns.set_item(PyBytes::new(py, b""), PyBytes::new(py, value))?;
+
+    Ok(columns)
+}
+
+/// Serializes the columns of an entity, the names are bytes or str and the values are bytes.
+/// The columns are sorted by their names like RocksDB sorts them.
+pub fn encode_entity(columns: &PyDict) -> PyResult<Vec<u8>> {
+    let mut sorted = BTreeMap::new();
+
+    for (name, value) in columns {
+        let name = match name.downcast::<PyString>() {
+            Ok(name) => name.to_str()?.as_bytes().to_vec(),
+            Err(_) => name.extract::<BytesLike>()?.as_bytes().to_vec(),
+        };
+
+        let value = value.extract::<BytesLike>()?.as_bytes().to_vec();
+
+        if sorted.insert(name, value).is_some() {
+            return Err(RocksDBPyException::new_err(
+                "Entity cannot be encoded, a column name is duplicated",
+            ));
+        }
+    }
+
+    let mut entity = Vec::new();
+
+    for (name, value) in &sorted {
+        for field in [name, value] {
+            entity.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            entity.extend_from_slice(field);
+        }
+    }
+
+    Ok(entity)
+}
+
+/// Deserializes the columns of an entity into a dict of bytes.
+pub fn decode_entity<'py>(py: Python<'py>, value: &[u8]) -> PyResult<&'py PyDict> {
+    let columns = PyDict::new(py);
+    let mut rest = value;
+
+    while !rest.is_empty() {
+        let name = take(&mut rest)?;
+        let value = take(&mut rest)?;
+
+        columns.set_item(PyBytes::new(py, name), PyBytes::new(py, value))?;
+    }
+
+    Ok(columns)
+}
+
+/// Takes a length-prefixed field from the serialized columns.
+fn take<'a>(rest: &mut &'a [u8]) -> PyResult<&'a [u8]> {
+    let truncated = || RocksDBPyException::new_err("Entity cannot be decoded, it is truncated");
+
+    if rest.len() < 4 {
+        return Err(truncated());
+    }
+
+    let (len, tail) = rest.split_at(4);
+    let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+
+    if tail.len() < len {
+        return Err(truncated());
+    }
+
+    let (field, tail) = tail.split_at(len);
+
+    *rest = tail;
+
+    Ok(field)
+}
@@ -0,0 +1,293 @@
+use crate::base::*;
+use crate::db::*;
+use crate::iterator::*;
+use crate::ttl::*;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use rocksdb::{Direction, WriteBatch};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Batch writer which indexes its records by key, so the pending writes can be read back
+/// before the batch is written. A deletion is kept as a missing value.
+#[pyclass(name = "WriteBatchWithIndex")]
+pub struct WriteBatchWithIndexPy {
+    index: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+#[pymethods]
+impl WriteBatchWithIndexPy {
+    #[new]
+    fn new() -> Self {
+        WriteBatchWithIndexPy {
+            index: BTreeMap::new(),
+        }
+    }
+
+    /// Append new "key" and "value" in the batch, it replaces the pending write of the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b = WriteBatchWithIndex()
+    ///
+    /// b.add(b'first', b'first_value')
+    /// ```
+    fn add(&mut self, key: &PyBytes, value: &PyBytes) {
+        self.index
+            .insert(key.as_bytes().to_vec(), Some(value.as_bytes().to_vec()));
+    }
+
+    /// Remove "key" from the batch, it replaces the pending write of the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b.delete(b'first')
+    /// ```
+    fn delete(&mut self, key: &PyBytes) {
+        self.index.insert(key.as_bytes().to_vec(), None);
+    }
+
+    /// Returns the pending value of the "key" in the batch, `None` if the key is not written
+    /// or deleted by the batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = b.get_from_batch(b'first')
+    /// ```
+    fn get_from_batch<'py>(&self, py: Python<'py>, key: &PyBytes) -> Option<&'py PyBytes> {
+        match self.index.get(key.as_bytes()) {
+            Some(Some(value)) => Some(PyBytes::new(py, value)),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the "key" with the pending writes of the batch applied on the
+    /// database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b.add(b'first', b'first_value')
+    ///
+    /// value = b.get_from_batch_and_db(db, b'first')
+    /// ```
+    fn get_from_batch_and_db<'py>(
+        &self,
+        py: Python<'py>,
+        db: &DBPy,
+        key: &PyBytes,
+    ) -> PyResult<Option<&'py PyBytes>> {
+        if let Some(pending) = self.index.get(key.as_bytes()) {
+            return Ok(pending.as_deref().map(|value| PyBytes::new(py, value)));
+        }
+
+        let inner = db.handle("Record cannot get")?;
+        let key = key.as_bytes();
+
+        match py.allow_threads(|| inner.get(key)) {
+            Ok(Some(value)) => {
+                Ok(load(db.key_ttl(), &value).map(|value| PyBytes::new(py, value)))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(rocksdb_error(&e, format!("Record cannot get. {}", e))),
+        }
+    }
+
+    /// Returns an iterator over the (key, value) entries of the database with the pending
+    /// writes of the batch applied. The iterator sees the batch as it is when the iterator
+    /// is created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, value in b.iterator(db):
+    ///     print(key, value)
+    ///
+    /// for key, value in b.iterator(db, reverse=True):
+    ///     print(key, value)
+    /// ```
+    #[pyo3(signature = (db, reverse=None))]
+    fn iterator(&self, db: &DBPy, reverse: Option<bool>) -> PyResult<BatchIteratorPy> {
+        let inner = match db.inner()? {
+            Some(inner) => inner,
+            None => {
+                return Err(DBClosedError::new_err(
+                    "Iterator cannot get, database is closed",
+                ))
+            }
+        };
+
+        let direction = match reverse {
+            Some(true) => Direction::Reverse,
+            _ => Direction::Forward,
+        };
+
+        Ok(BatchIteratorPy::new(
+            inner,
+            direction,
+            db.key_ttl(),
+            self.index.clone().into_iter().collect(),
+        ))
+    }
+
+    /// Returns the number of keys written by the batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// size = len(b)
+    /// ```
+    fn __len__(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Clear the batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// b.clear()
+    /// ```
+    fn clear(&mut self) {
+        self.index.clear();
+    }
+}
+
+impl WriteBatchWithIndexPy {
+    /// Returns the batch of the pending writes, the values are wrapped with the expiration
+    /// header if `expiry` is true.
+    pub fn build(&self, expiry: bool) -> WriteBatch {
+        let mut batch = WriteBatch::default();
+
+        for (key, value) in &self.index {
+            match value {
+                Some(value) if expiry => batch.put(key, wrap(value, None)),
+                Some(value) => batch.put(key, value),
+                None => batch.delete(key),
+            }
+        }
+
+        batch
+    }
+}
+
+/// Iterator over the database with the pending writes of a batch applied.
+#[pyclass(name = "BatchIterator")]
+pub struct BatchIteratorPy {
+    inner: DBRawIterator<'static>,
+    direction: Direction,
+    expiry: bool,
+    pending: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    position: usize,
+    _db: Arc<DB>,
+}
+
+#[pymethods]
+impl BatchIteratorPy {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    /// Returns next entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// next(iterator)
+    /// ```
+    fn __next__<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Option<(&'py PyBytes, &'py PyBytes)>> {
+        loop {
+            skip_expired(&mut self.inner, self.direction, self.expiry);
+
+            let stored = self.inner.item();
+            let pending = self.pending.get(self.position);
+
+            // the entry which comes first by the direction is returned, the pending write
+            // replaces the stored entry of the same key
+            let from_batch = match (stored, pending) {
+                (None, None) => {
+                    return match self.inner.status() {
+                        Ok(()) => Ok(None),
+                        Err(e) => Err(rocksdb_error(&e, format!("Iterator cannot move. {}", e))),
+                    }
+                }
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                (Some((key, _)), Some((pending, _))) => match self.direction {
+                    Direction::Forward => pending.as_slice() <= key,
+                    Direction::Reverse => pending.as_slice() >= key,
+                },
+            };
+
+            if !from_batch {
+                let (key, value) = stored.unwrap();
+                let value = if self.expiry { &value[HEADER..] } else { value };
+                let entry = (PyBytes::new(py, key), PyBytes::new(py, value));
+
+                step(&mut self.inner, self.direction);
+
+                return Ok(Some(entry));
+            }
+
+            let (key, value) = &self.pending[self.position];
+
+            self.position += 1;
+
+            if self.inner.key() == Some(key.as_slice()) {
+                step(&mut self.inner, self.direction);
+            }
+
+            if let Some(value) = value {
+                return Ok(Some((PyBytes::new(py, key), PyBytes::new(py, value))));
+            }
+        }
+    }
+}
+
+impl BatchIteratorPy {
+    fn new(
+        db: &Arc<DB>,
+        direction: Direction,
+        expiry: bool,
+        mut pending: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    ) -> BatchIteratorPy {
+        let opts = IteratorOptions {
+            expiry,
+            ..Default::default()
+        };
+
+        let mut inner = raw_iterator(db, &opts);
+
+        match direction {
+            Direction::Forward => inner.seek_to_first(),
+            Direction::Reverse => {
+                inner.seek_to_last();
+                pending.reverse();
+            }
+        }
+
+        BatchIteratorPy {
+            inner,
+            direction,
+            expiry,
+            pending,
+            position: 0,
+            _db: db.clone(),
+        }
+    }
+}
+
+/// Moves the database iterator one entry by the direction.
+#[inline]
+fn step(inner: &mut DBRawIterator, direction: Direction) {
+    match direction {
+        Direction::Forward => inner.next(),
+        Direction::Reverse => inner.prev(),
+    }
+}
@@ -1,12 +1,98 @@
 use crate::base::*;
+use crate::codec::*;
+use crate::pinned::*;
+use crate::timestamp::*;
+use crate::ttl::*;
+use pyo3::exceptions::PyBufferError;
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyTuple};
-use rocksdb::{DBIterator, IteratorMode, DB};
+use pyo3::types::{PyList, PyTuple};
+use rocksdb::{BoundColumnFamily, Direction, ReadOptions};
+use std::sync::Arc;
+
+/// Initial position of a new iterator.
+pub enum IteratorSeek<'a> {
+    /// First key of the database, iterates forward.
+    Start,
+    /// Last key of the database, iterates in reverse.
+    End,
+    /// First key at or after the target for forward, last key at or before the target for
+    /// reverse direction.
+    From(&'a [u8], Direction),
+    /// Last key at or before the target, iterates by the given direction.
+    ForPrev(&'a [u8], Direction),
+}
+
+/// Read options of an iterator, kept to create the same iterator again on refresh.
+#[derive(Clone, Default)]
+pub struct IteratorOptions {
+    pub lower_bound: Option<Vec<u8>>,
+    pub upper_bound: Option<Vec<u8>>,
+    pub tailing: bool,
+    pub readahead_size: Option<usize>,
+    pub auto_readahead_size: Option<bool>,
+    pub async_io: bool,
+    /// Values have an expiration header, the expired entries are skipped.
+    pub expiry: bool,
+    /// Records are read as of the user timestamp.
+    pub timestamp: Option<u64>,
+}
+
+impl IteratorOptions {
+    pub fn read_options(&self) -> ReadOptions {
+        let mut opts = ReadOptions::default();
+
+        // keys outside of the bounds are skipped by RocksDB, the lower bound is inclusive
+        // and the upper bound is exclusive
+        if let Some(lower) = &self.lower_bound {
+            opts.set_iterate_lower_bound(lower.as_slice());
+        }
+
+        if let Some(upper) = &self.upper_bound {
+            opts.set_iterate_upper_bound(upper.as_slice());
+        }
+
+        // tailing iterator sees the entries which are written after its creation
+        opts.set_tailing(self.tailing);
+
+        // large sequential scans read ahead instead of issuing many small reads
+        if let Some(size) = self.readahead_size {
+            opts.set_readahead_size(size);
+        }
+
+        if let Some(enabled) = self.auto_readahead_size {
+            opts.set_auto_readahead_size(enabled);
+        }
+
+        opts.set_async_io(self.async_io);
+
+        if let Some(ts) = self.timestamp {
+            opts.set_timestamp(encode_ts(ts));
+        }
+
+        opts
+    }
+}
 
 /// DB iterator.
 #[pyclass(name = "DBIterator")]
 pub struct IteratorPy {
-    inner: Option<DBIterator<'static>>,
+    inner: Option<DBRawIterator<'static>>,
+    direction: Direction,
+    last: Option<Vec<u8>>,
+    keys: bool,
+    values: bool,
+    pinned: bool,
+    pending: bool,
+    view: Option<PyObject>,
+    /// The slice of the last pinned value, it counts the buffers which are still used.
+    slice: Option<Py<PinnedSlicePy>>,
+    prefix: Vec<u8>,
+    /// Values are decoded by the codec of the database, raw bytes are returned without one.
+    codec: Option<Codec>,
+    /// Keys are returned as `str` in the text mode.
+    text: bool,
+    opts: IteratorOptions,
+    db: Arc<DB>,
 }
 
 #[pymethods]
@@ -23,25 +109,135 @@ impl IteratorPy {
     /// next(iterator)
     /// ```
     fn __next__(mut slf: PyRefMut<Self>) -> PyResult<Option<PyObject>> {
-        if let Some(inner) = &mut slf.inner {
-            match inner.next() {
-                None => Ok(None),
-                Some(kv) => {
-                    match kv {
-                        Ok(kv) => {
-                            let py = slf.py();
-                            let key = PyBytes::new(py, kv.0.as_ref());
-                            let value = PyBytes::new(py, kv.1.as_ref());
-                            return Ok(Some(PyTuple::new(py, &[key, value]).into_py(py)));
+        let py = slf.py();
+        let direction = slf.direction;
+        let this = &mut *slf;
+
+        this.settle(py)?;
+
+        if let Some(inner) = &mut this.inner {
+            // tailing iterator continues after the last returned key to pick up new entries
+            if !inner.valid() && this.opts.tailing {
+                if let Some(last) = &this.last {
+                    resume(inner, direction, last);
+                }
+            }
+
+            // expired entries are skipped until they are removed by a compaction
+            skip_expired(inner, direction, this.opts.expiry);
+
+            // values are not materialized if only keys are requested
+            let (key, value) = match inner.item() {
+                Some((k, v)) => {
+                    let v = if this.opts.expiry { &v[HEADER..] } else { v };
+
+                    let value: Option<&PyAny> = if !this.values {
+                        None
+                    } else if this.pinned {
+                        let slice = Py::new(py, unsafe { PinnedSlicePy::borrowed(v) })?;
+                        let view = memoryview_of(py, &slice)?;
+
+                        this.view = Some(view.clone_ref(py));
+                        this.slice = Some(slice);
+
+                        Some(view.into_ref(py))
+                    } else {
+                        Some(decode_value(py, &this.codec, v)?.into_ref(py))
+                    };
+
+                    (k.to_vec(), value)
+                }
+                None => return check(inner).map(|_| None),
+            };
+
+            let output_key = decode_key(py, this.text, &key[this.prefix.len()..]);
+
+            if this.pinned {
+                // the pinned value is valid until the next move, so the step is deferred
+                this.pending = true;
+                this.last = Some(key);
+            } else {
+                step(inner, direction);
+
+                // keep the position of the exhausted iterator to continue from
+                if !inner.valid() {
+                    this.last = Some(key);
+                }
+            }
+
+            Ok(Some(output(py, this.keys, output_key?, value)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns up to `n` next database entries as a list. The entries are read without holding
+    /// the GIL, an empty list means the iterator is exhausted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// entries = itr.next_batch(1000)
+    /// ```
+    fn next_batch<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<&'py PyList> {
+        self.settle(py)?;
+
+        let direction = self.direction;
+        let tailing = self.opts.tailing;
+        let values = self.values;
+        let expiry = self.opts.expiry;
+        let last = self.last.take();
+
+        if let Some(inner) = &mut self.inner {
+            let items = py.allow_threads(|| {
+                // tailing iterator continues after the last returned key
+                if !inner.valid() && tailing {
+                    if let Some(last) = &last {
+                        resume(inner, direction, last);
+                    }
+                }
+
+                let mut items: Vec<(Vec<u8>, Option<Vec<u8>>)> = Vec::with_capacity(n.min(1024));
+
+                while items.len() < n {
+                    match inner.item() {
+                        Some((k, v)) => match load(expiry, v) {
+                            Some(v) => items.push((k.to_vec(), values.then(|| v.to_vec()))),
+                            None => {}
                         },
-                        Err(_err) => {
-                            Err(RocksDBPyException::new_err("Iterator completed?"))
-                        }
+                        None => break,
                     }
+
+                    step(inner, direction);
                 }
+
+                items
+            });
+
+            check(inner)?;
+
+            // keep the position of the exhausted iterator to continue from
+            self.last = match items.last() {
+                Some((k, _)) if !inner.valid() => Some(k.clone()),
+                _ => last,
+            };
+
+            let list = PyList::empty(py);
+
+            for (k, v) in items.iter() {
+                let value = match v {
+                    Some(v) => Some(decode_value(py, &self.codec, v)?.into_ref(py)),
+                    None => None,
+                };
+
+                let key = decode_key(py, self.text, &k[self.prefix.len()..])?;
+
+                list.append(output(py, self.keys, key, value))?;
             }
+
+            Ok(list)
         } else {
-            Ok(None)
+            Ok(PyList::empty(py))
         }
     }
 
@@ -53,8 +249,14 @@ impl IteratorPy {
     /// len(itr)
     /// ```
     fn __len__(mut slf: PyRefMut<Self>) -> PyResult<usize> {
+        let py = slf.py();
+        let direction = slf.direction;
+        let expiry = slf.opts.expiry;
+
+        slf.settle(py)?;
+
         if let Some(inner) = &mut slf.inner {
-            Ok(inner.count())
+            count(inner, direction, expiry)
         } else {
             Err(RocksDBPyException::new_err("Length cannot get"))
         }
@@ -68,13 +270,60 @@ impl IteratorPy {
     /// count = itr.len()
     /// ```
     fn len(mut slf: PyRefMut<Self>) -> PyResult<usize> {
+        let py = slf.py();
+        let direction = slf.direction;
+        let expiry = slf.opts.expiry;
+
+        slf.settle(py)?;
+
         if let Some(inner) = &mut slf.inner {
-            Ok(inner.count())
+            count(inner, direction, expiry)
         } else {
             Err(RocksDBPyException::new_err("Count cannot get"))
         }
     }
 
+    /// Brings the iterator up to date with the recent writes. The iterator keeps its position,
+    /// so the next entry is the one after the last returned entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// itr.refresh()
+    /// ```
+    fn refresh(mut slf: PyRefMut<Self>) -> PyResult<()> {
+        let py = slf.py();
+        let direction = slf.direction;
+        let this = &mut *slf;
+
+        this.settle(py)?;
+
+        if let Some(inner) = &mut this.inner {
+            check(inner)?;
+
+            let current = inner.key().map(|k| k.to_vec());
+            let mut fresh = raw_iterator(&this.db, &this.opts);
+
+            match (current, &this.last) {
+                (Some(key), _) => match direction {
+                    Direction::Forward => fresh.seek(key),
+                    Direction::Reverse => fresh.seek_for_prev(key),
+                },
+                (None, Some(last)) => resume(&mut fresh, direction, last),
+                (None, None) => match direction {
+                    Direction::Forward => fresh.seek_to_first(),
+                    Direction::Reverse => fresh.seek_to_last(),
+                },
+            }
+
+            *inner = fresh;
+
+            Ok(())
+        } else {
+            Err(RocksDBPyException::new_err("Iterator cannot refresh"))
+        }
+    }
+
     /// Close and destroy active iterator
     ///
     /// # Example
@@ -83,6 +332,9 @@ impl IteratorPy {
     /// itr.close()
     /// ```
     fn close(mut slf: PyRefMut<Self>) -> PyResult<()> {
+        let py = slf.py();
+
+        slf.settle(py)?;
         slf.inner = None;
 
         Ok(())
@@ -90,17 +342,245 @@ impl IteratorPy {
 }
 
 impl IteratorPy {
-    pub fn new(db: &DB, mode: IteratorMode) -> IteratorPy {
-        unsafe {
-            IteratorPy {
-                inner: Some(std::mem::transmute(db.iterator(mode))),
+    pub fn new(db: &Arc<DB>, seek: IteratorSeek, opts: IteratorOptions) -> IteratorPy {
+        let mut inner = raw_iterator(db, &opts);
+
+        let direction = match seek {
+            IteratorSeek::Start => {
+                inner.seek_to_first();
+                Direction::Forward
+            }
+            IteratorSeek::End => {
+                inner.seek_to_last();
+                Direction::Reverse
+            }
+            IteratorSeek::From(key, Direction::Forward) => {
+                inner.seek(key);
+                Direction::Forward
+            }
+            IteratorSeek::From(key, Direction::Reverse) => {
+                inner.seek_for_prev(key);
+                Direction::Reverse
+            }
+            IteratorSeek::ForPrev(key, direction) => {
+                inner.seek_for_prev(key);
+                direction
+            }
+        };
+
+        IteratorPy {
+            inner: Some(inner),
+            direction,
+            last: None,
+            keys: true,
+            values: true,
+            pinned: false,
+            pending: false,
+            view: None,
+            slice: None,
+            prefix: Vec::new(),
+            codec: None,
+            text: false,
+            opts,
+            db: db.clone(),
+        }
+    }
+
+    /// Selects the parts of the entries which are returned. Only keys or only values are
+    /// returned instead of (key, value) tuples if one of them is disabled.
+    pub fn select(mut self, keys: bool, values: bool) -> IteratorPy {
+        self.keys = keys;
+        self.values = values;
+
+        self
+    }
+
+    /// Makes the iterator return values as memoryview objects over the pinned slices of
+    /// RocksDB. A memoryview is released when the iterator moves to the next entry.
+    pub fn pinned(mut self, enabled: bool) -> IteratorPy {
+        self.pinned = enabled;
+
+        self
+    }
+
+    /// Decodes the returned values by the codec, the pinned values are not decoded.
+    pub fn decoded(mut self, codec: Option<Codec>) -> IteratorPy {
+        self.codec = codec;
+
+        self
+    }
+
+    /// Returns the keys as `str` decoded from UTF-8 if the text mode is enabled.
+    pub fn text(mut self, enabled: bool) -> IteratorPy {
+        self.text = enabled;
+
+        self
+    }
+
+    /// Removes the prefix from the returned keys. Every key of the iterator must start with
+    /// the prefix, so the bounds of the iterator have to be limited to the prefix.
+    pub fn strip_prefix(mut self, prefix: &[u8]) -> IteratorPy {
+        self.prefix = prefix.to_vec();
+
+        self
+    }
+
+    /// Releases the memoryview of the last returned value and makes the deferred move. The
+    /// iterator does not move while a view which is derived from the value is still used.
+    fn settle(&mut self, py: Python) -> PyResult<()> {
+        if let Some(view) = self.view.take() {
+            // accessing a released memoryview raises an error instead of reading freed memory
+            if let Err(e) = view.call_method0(py, "release") {
+                self.view = Some(view);
+
+                return Err(e);
             }
         }
+
+        if let Some(slice) = &self.slice {
+            if slice.borrow(py).exported() {
+                return Err(PyBufferError::new_err(
+                    "Iterator cannot move, a view of the pinned value is still used",
+                ));
+            }
+
+            self.slice = None;
+        }
+
+        if self.pending {
+            self.pending = false;
+
+            if let Some(inner) = &mut self.inner {
+                step(inner, self.direction);
+            }
+        }
+
+        Ok(())
     }
 }
 
 impl Drop for IteratorPy {
     fn drop(&mut self) {
-        self.inner = None
+        let inner = self.inner.take();
+
+        if let Some(slice) = self.slice.take() {
+            let view = self.view.take();
+            let db = self.db.clone();
+
+            Python::with_gil(|py| {
+                if let Some(view) = view {
+                    let _ = view.call_method0(py, "release");
+                }
+
+                let mut slice = slice.borrow_mut(py);
+
+                // the views which outlive the iterator keep the value, the iterator and the
+                // database alive until they are released
+                if slice.exported() {
+                    slice.adopt(Box::new((inner, db)));
+                }
+            });
+        }
+    }
+}
+
+/// Converts an entry to the Python object by the selected parts.
+#[inline]
+fn output(py: Python, keys: bool, key: &PyAny, value: Option<&PyAny>) -> PyObject {
+    match (keys, value) {
+        (true, Some(value)) => PyTuple::new(py, &[key, value]).into_py(py),
+        (false, Some(value)) => value.into_py(py),
+        (_, None) => key.into_py(py),
     }
 }
+
+/// Creates a raw iterator which outlives the borrow of the database. The caller must keep a
+/// reference of the database until the iterator is dropped.
+pub fn raw_iterator(db: &DB, opts: &IteratorOptions) -> DBRawIterator<'static> {
+    unsafe { std::mem::transmute(db.raw_iterator_opt(opts.read_options())) }
+}
+
+/// Creates a raw iterator over the column family like `raw_iterator`.
+pub fn raw_iterator_cf(
+    db: &DB,
+    cf: &Arc<BoundColumnFamily>,
+    opts: &IteratorOptions,
+) -> DBRawIterator<'static> {
+    unsafe { std::mem::transmute(db.raw_iterator_cf_opt(cf, opts.read_options())) }
+}
+
+/// Returns the smallest key which is greater than every key starting with the prefix, `None`
+/// if there is no such key (e.g. the prefix is empty or consists of `0xff` bytes).
+pub fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut bound = prefix.to_vec();
+
+    while let Some(last) = bound.pop() {
+        if last < u8::MAX {
+            bound.push(last + 1);
+
+            return Some(bound);
+        }
+    }
+
+    None
+}
+
+/// Moves the iterator one entry by the direction.
+#[inline]
+pub fn step(inner: &mut DBRawIterator, direction: Direction) {
+    match direction {
+        Direction::Forward => inner.next(),
+        Direction::Reverse => inner.prev(),
+    }
+}
+
+/// Positions the iterator to the entry after the given key by the direction.
+fn resume(inner: &mut DBRawIterator, direction: Direction, key: &[u8]) {
+    match direction {
+        Direction::Forward => inner.seek(key),
+        Direction::Reverse => inner.seek_for_prev(key),
+    }
+
+    if inner.key() == Some(key) {
+        step(inner, direction);
+    }
+}
+
+/// Returns an error if the iterator stopped because of a failure.
+#[inline]
+pub fn check(inner: &DBRawIterator) -> PyResult<()> {
+    match inner.status() {
+        Ok(()) => Ok(()),
+        Err(e) => Err(rocksdb_error(&e, format!("Iterator cannot move. {}", e))),
+    }
+}
+
+/// Moves the iterator over the expired entries if `expiry` is true.
+pub fn skip_expired(inner: &mut DBRawIterator, direction: Direction, expiry: bool) {
+    if !expiry {
+        return;
+    }
+
+    while let Some(value) = inner.value() {
+        if !expired(value) {
+            break;
+        }
+
+        step(inner, direction);
+    }
+}
+
+/// Consumes the iterator and returns the number of remaining entries.
+fn count(inner: &mut DBRawIterator, direction: Direction, expiry: bool) -> PyResult<usize> {
+    let mut n = 0;
+
+    while let Some(value) = inner.value() {
+        if !expiry || !expired(value) {
+            n += 1;
+        }
+
+        step(inner, direction);
+    }
+
+    check(inner).map(|_| n)
+}
@@ -0,0 +1,130 @@
+use crate::buffer::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Helpers which encode integers as big-endian keys, so the byte order of the keys matches the
+/// numeric order. Signed integers have their sign bit flipped to sort negative values first.
+#[pyclass(name = "Key")]
+pub struct KeyPy;
+
+#[pymethods]
+impl KeyPy {
+    /// Encodes an unsigned 64-bit integer as 8 big-endian bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// key = Key.u64_be(ts) + b'id'
+    /// ```
+    #[staticmethod]
+    fn u64_be(py: Python, value: u64) -> &PyBytes {
+        PyBytes::new(py, &value.to_be_bytes())
+    }
+
+    /// Encodes a signed 64-bit integer as 8 order-preserving big-endian bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// key = Key.i64_be(-1)
+    /// ```
+    #[staticmethod]
+    fn i64_be(py: Python, value: i64) -> &PyBytes {
+        PyBytes::new(py, &((value as u64) ^ (1 << 63)).to_be_bytes())
+    }
+
+    /// Encodes an unsigned 32-bit integer as 4 big-endian bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// key = Key.u32_be(1)
+    /// ```
+    #[staticmethod]
+    fn u32_be(py: Python, value: u32) -> &PyBytes {
+        PyBytes::new(py, &value.to_be_bytes())
+    }
+
+    /// Encodes a signed 32-bit integer as 4 order-preserving big-endian bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// key = Key.i32_be(-1)
+    /// ```
+    #[staticmethod]
+    fn i32_be(py: Python, value: i32) -> &PyBytes {
+        PyBytes::new(py, &((value as u32) ^ (1 << 31)).to_be_bytes())
+    }
+
+    /// Decodes an unsigned 64-bit integer from 8 big-endian bytes at the offset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// ts = Key.decode_u64_be(key)
+    ///
+    /// id = Key.decode_u64_be(key, 8)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (data, offset=None))]
+    fn decode_u64_be(data: BytesLike, offset: Option<usize>) -> PyResult<u64> {
+        Ok(u64::from_be_bytes(read(&data, offset)?))
+    }
+
+    /// Decodes a signed 64-bit integer encoded by `i64_be`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = Key.decode_i64_be(key)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (data, offset=None))]
+    fn decode_i64_be(data: BytesLike, offset: Option<usize>) -> PyResult<i64> {
+        Ok((u64::from_be_bytes(read(&data, offset)?) ^ (1 << 63)) as i64)
+    }
+
+    /// Decodes an unsigned 32-bit integer from 4 big-endian bytes at the offset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = Key.decode_u32_be(key)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (data, offset=None))]
+    fn decode_u32_be(data: BytesLike, offset: Option<usize>) -> PyResult<u32> {
+        Ok(u32::from_be_bytes(read(&data, offset)?))
+    }
+
+    /// Decodes a signed 32-bit integer encoded by `i32_be`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = Key.decode_i32_be(key)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (data, offset=None))]
+    fn decode_i32_be(data: BytesLike, offset: Option<usize>) -> PyResult<i32> {
+        Ok((u32::from_be_bytes(read(&data, offset)?) ^ (1 << 31)) as i32)
+    }
+}
+
+/// Reads `N` bytes from the data at the offset.
+fn read<const N: usize>(data: &BytesLike, offset: Option<usize>) -> PyResult<[u8; N]> {
+    let start = offset.unwrap_or(0);
+    let bytes = data.as_bytes();
+
+    match bytes.get(start..start.saturating_add(N)) {
+        Some(slice) => Ok(slice.try_into().unwrap()),
+        None => Err(PyValueError::new_err(format!(
+            "Key cannot decode, {} bytes are required at offset {} but the key has {} bytes",
+            N,
+            start,
+            bytes.len()
+        ))),
+    }
+}
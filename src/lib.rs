@@ -1,23 +1,77 @@
+mod aio;
+mod backup;
 mod base;
 mod batch;
+mod buffer;
+mod codec;
+mod comparator;
+mod compaction;
+mod cursor;
 mod db;
+mod dump;
+mod entity;
+mod indexed;
 mod iterator;
+mod key;
+mod lock;
+mod merge;
+mod namespace;
 mod option;
+mod pinned;
+mod pool;
+mod queue;
+mod record;
+mod scan;
+mod sst;
+mod timestamp;
+mod ttl;
+mod worker;
+mod writer;
 
+use crate::aio::*;
+use crate::backup::*;
 use crate::base::*;
 use crate::batch::*;
+use crate::compaction::*;
+use crate::cursor::*;
 use crate::db::*;
+use crate::entity::*;
+use crate::indexed::*;
 use crate::iterator::*;
+use crate::key::*;
+use crate::namespace::*;
 use crate::option::*;
+use crate::pinned::*;
+use crate::pool::*;
+use crate::queue::*;
+use crate::scan::*;
+use crate::sst::*;
+use crate::writer::*;
 use pyo3::prelude::*;
 use pyo3::wrap_pyfunction;
 
 #[pymodule]
-fn rocksdbpy(py: Python, m: &PyModule) -> PyResult<()> {
+fn _rocksdbpy(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<AsyncIteratorPy>()?;
+    m.add_class::<BackupEnginePy>()?;
+    m.add_class::<BatchIteratorPy>()?;
+    m.add_class::<BufferedWriterPy>()?;
+    m.add_class::<CompactionPy>()?;
+    m.add_class::<CursorPy>()?;
     m.add_class::<DBPy>()?;
+    m.add_class::<EntityIteratorPy>()?;
     m.add_class::<IteratorPy>()?;
+    m.add_class::<KeyPy>()?;
+    m.add_class::<NamespacePy>()?;
     m.add_class::<OptionPy>()?;
+    m.add_class::<ParallelScanPy>()?;
+    m.add_class::<PinnedSlicePy>()?;
+    m.add_class::<QueuePy>()?;
+    m.add_class::<ReadPoolPy>()?;
+    m.add_class::<SstFileReaderPy>()?;
+    m.add_class::<SstFileWriterPy>()?;
     m.add_class::<WriteBatchPy>()?;
+    m.add_class::<WriteBatchWithIndexPy>()?;
 
     m.add_function(wrap_pyfunction!(open, m)?).unwrap();
     m.add_function(wrap_pyfunction!(open_default, m)?).unwrap();
@@ -25,8 +79,19 @@ fn rocksdbpy(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(open_for_readonly, m)?).unwrap();
     m.add_function(wrap_pyfunction!(open_as_secondary, m)?).unwrap();
     m.add_function(wrap_pyfunction!(destroy, m)?).unwrap();
+    m.add_function(wrap_pyfunction!(list_column_families, m)?).unwrap();
 
     m.add("RocksDBException", py.get_type::<RocksDBPyException>())?;
+    m.add("DBClosedError", py.get_type::<DBClosedError>())?;
+    m.add("DBForkedError", py.get_type::<DBForkedError>())?;
+    m.add("DBLockedError", py.get_type::<DBLockedError>())?;
+    m.add("DBNotFoundError", py.get_type::<DBNotFoundError>())?;
+    m.add("DBCorruptionError", py.get_type::<DBCorruptionError>())?;
+    m.add("DBIOError", py.get_type::<DBIOError>())?;
+    m.add("DBBusyError", py.get_type::<DBBusyError>())?;
+    m.add("DBTryAgainError", py.get_type::<DBTryAgainError>())?;
+    m.add("DBTimedOutError", py.get_type::<DBTimedOutError>())?;
+    m.add("DBInvalidArgumentError", py.get_type::<DBInvalidArgumentError>())?;
 
     Ok(())
 }
@@ -0,0 +1,52 @@
+use pyo3::prelude::*;
+use std::sync::{
+    Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError,
+    TryLockResult,
+};
+use std::thread;
+use std::time::Duration;
+
+/// Orders the writes of a database in the process. Plain writes share the lock, read-modify-write
+/// operations hold it exclusively, so no write happens between their read and write.
+#[derive(Clone, Default)]
+pub struct WriteLock {
+    inner: Arc<RwLock<()>>,
+}
+
+impl WriteLock {
+    /// Acquires the lock for a plain write.
+    pub fn shared(&self, py: Python) -> RwLockReadGuard<()> {
+        acquire(py, || self.inner.try_read())
+    }
+
+    /// Acquires the lock for a read-modify-write operation.
+    pub fn exclusive(&self, py: Python) -> RwLockWriteGuard<()> {
+        acquire(py, || self.inner.try_write())
+    }
+
+    /// Acquires the lock for a plain write on a thread which does not hold the GIL.
+    pub fn shared_blocking(&self) -> RwLockReadGuard<()> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Locks a mutex which is shared with a thread which does not hold the GIL, the GIL is
+/// released between the attempts like for the write lock.
+pub fn lock_mutex<'a, T>(py: Python, mutex: &'a Mutex<T>) -> MutexGuard<'a, T> {
+    acquire(py, || mutex.try_lock())
+}
+
+/// Tries to acquire the lock until it succeeds. The GIL is released between the attempts, the
+/// holder of the lock may be waiting for the GIL to call Python code.
+fn acquire<G>(py: Python, mut lock: impl FnMut() -> TryLockResult<G>) -> G {
+    loop {
+        match lock() {
+            Ok(guard) => return guard,
+            // the lock only protects the order of writes, so a panic does not invalidate it
+            Err(TryLockError::Poisoned(e)) => return e.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                py.allow_threads(|| thread::sleep(Duration::from_micros(50)))
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::ttl::*;
+use rocksdb::{MergeOperands, Options};
+
+/// Operand which adds a signed 64-bit delta to a counter.
+const ADD: u8 = 1;
+
+/// Operand which appends bytes to a value.
+const APPEND: u8 = 2;
+
+/// Name of the merge operator, RocksDB records it in the OPTIONS file of the database.
+const NAME: &str = "rocksdbpy.merge";
+
+/// Name of the merge operator for the values with an expiration header.
+const NAME_TTL: &str = "rocksdbpy.merge.ttl";
+
+/// Sets the merge operator of the bindings. The operands start with a tag byte which selects
+/// the operation, so the operations of the different methods share one merge operator. If
+/// `expiry` is true, the operands are applied to the values after the expiration header.
+pub fn set_merge_operator(opts: &mut Options, expiry: bool) {
+    if expiry {
+        opts.set_merge_operator(NAME_TTL, full_merge_expiry, partial_merge);
+    } else {
+        opts.set_merge_operator(NAME, full_merge, partial_merge);
+    }
+}
+
+/// Returns the operand which adds the delta to a counter.
+pub fn add_operand(delta: i64) -> Vec<u8> {
+    let mut operand = vec![ADD];
+
+    operand.extend_from_slice(&delta.to_le_bytes());
+
+    operand
+}
+
+/// Returns the operand which appends the data to a value.
+pub fn append_operand(data: &[u8]) -> Vec<u8> {
+    let mut operand = Vec::with_capacity(data.len() + 1);
+
+    operand.push(APPEND);
+    operand.extend_from_slice(data);
+
+    operand
+}
+
+/// Decodes a counter, counters are stored as 8 little-endian bytes like the `uint64add` merge
+/// operator of RocksDB.
+pub fn counter(value: &[u8]) -> Option<i64> {
+    Some(i64::from_le_bytes(value.try_into().ok()?))
+}
+
+/// Applies the operands to the existing value in order.
+fn full_merge(_key: &[u8], existing: Option<&[u8]>, operands: &MergeOperands) -> Option<Vec<u8>> {
+    let mut value = existing.map(|v| v.to_vec());
+
+    for operand in operands.iter() {
+        value = Some(apply(value, operand)?);
+    }
+
+    value
+}
+
+/// Applies the operands to the existing value and keeps its expiration header. Expired values
+/// are merged like missing values, which never expire.
+fn full_merge_expiry(
+    key: &[u8],
+    existing: Option<&[u8]>,
+    operands: &MergeOperands,
+) -> Option<Vec<u8>> {
+    let (mut value, existing) = match existing.and_then(|v| Some((v, unwrap(v)?))) {
+        Some((stored, payload)) => (stored[..HEADER].to_vec(), Some(payload)),
+        None => (vec![0; HEADER], None),
+    };
+
+    value.extend_from_slice(&full_merge(key, existing, operands)?);
+
+    Some(value)
+}
+
+/// Combines the operands if they have the same operation, otherwise RocksDB keeps them until
+/// the full merge.
+fn partial_merge(
+    _key: &[u8],
+    _existing: Option<&[u8]>,
+    operands: &MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut iter = operands.iter();
+    let mut merged = iter.next()?.to_vec();
+    let tag = *merged.first()?;
+
+    for operand in iter {
+        if operand.first() != Some(&tag) {
+            return None;
+        }
+
+        merged = match tag {
+            ADD => {
+                let sum = counter(&merged[1..])?.wrapping_add(counter(&operand[1..])?);
+
+                add_operand(sum)
+            }
+            APPEND => {
+                merged.extend_from_slice(&operand[1..]);
+
+                merged
+            }
+            _ => return None,
+        };
+    }
+
+    Some(merged)
+}
+
+/// Applies an operand to the value, a failed merge is reported as a corruption by RocksDB.
+fn apply(value: Option<Vec<u8>>, operand: &[u8]) -> Option<Vec<u8>> {
+    let (tag, data) = operand.split_first()?;
+
+    match *tag {
+        ADD => {
+            let current = match value {
+                Some(value) => counter(&value)?,
+                None => 0,
+            };
+
+            Some(current.wrapping_add(counter(data)?).to_le_bytes().to_vec())
+        }
+        APPEND => {
+            let mut value = value.unwrap_or_default();
+
+            value.extend_from_slice(data);
+
+            Some(value)
+        }
+        _ => None,
+    }
+}
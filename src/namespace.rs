@@ -0,0 +1,277 @@
+use crate::base::*;
+use crate::db::*;
+use crate::iterator::*;
+use pyo3::exceptions::PyKeyError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// View over the keys of the database which start with a prefix. The prefix is prepended to
+/// the given keys and removed from the keys of the iterators.
+#[pyclass(name = "Namespace")]
+pub struct NamespacePy {
+    db: Py<DBPy>,
+    prefix: Vec<u8>,
+}
+
+#[pymethods]
+impl NamespacePy {
+    /// The prefix of the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// prefix = ns.prefix
+    /// ```
+    #[getter]
+    fn prefix<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        PyBytes::new(py, &self.prefix)
+    }
+
+    /// Return the value associated with a "key" in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = ns.get(b'key')
+    /// ```
+    fn get(&self, py: Python, key: &PyAny) -> PyResult<Option<PyObject>> {
+        let db = self.db.borrow(py);
+
+        db.get(py, self.key(py, &db, key)?, None)
+    }
+
+    /// Sets records by "key" and "value" in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// ns.set(b'key', b'value')
+    ///
+    /// ns.set(b'key', b'value', ttl=3600)
+    /// ```
+    #[pyo3(signature = (key, value, ttl=None))]
+    fn set(&self, py: Python, key: &PyAny, value: &PyAny, ttl: Option<u64>) -> PyResult<()> {
+        let db = self.db.borrow(py);
+        let key = self.key(py, &db, key)?;
+
+        db.set(py, key, value, ttl, None)
+    }
+
+    /// Removes existing records by "key" in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// ns.delete(b'key')
+    /// ```
+    fn delete(&self, py: Python, key: &PyAny) -> PyResult<()> {
+        let db = self.db.borrow(py);
+        let key = self.key(py, &db, key)?;
+
+        db.delete(py, key, None)
+    }
+
+    /// Returns the value associated with a "key", raises `KeyError` if the key is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = ns[b'key']
+    /// ```
+    fn __getitem__(&self, py: Python, key: &PyAny) -> PyResult<PyObject> {
+        match self.get(py, key)? {
+            Some(value) => Ok(value),
+            None => Err(PyKeyError::new_err(key.into_py(py))),
+        }
+    }
+
+    /// Sets records by "key" and "value" in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// ns[b'key'] = b'value'
+    /// ```
+    fn __setitem__(&self, py: Python, key: &PyAny, value: &PyAny) -> PyResult<()> {
+        self.set(py, key, value, None)
+    }
+
+    /// Removes existing records by "key" in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// del ns[b'key']
+    /// ```
+    fn __delitem__(&self, py: Python, key: &PyAny) -> PyResult<()> {
+        self.delete(py, key)
+    }
+
+    /// Returns true if the "key" exists in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if b'key' in ns:
+    ///     ...
+    /// ```
+    fn __contains__(&self, py: Python, key: &PyAny) -> PyResult<bool> {
+        let db = self.db.borrow(py);
+
+        db.__contains__(py, self.key(py, &db, key)?)
+    }
+
+    /// Returns an iterator over the (key, value) entries of the namespace, the prefix is
+    /// removed from the keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, value in ns.iterator():
+    ///     print(key, value)
+    ///
+    /// for key, value in ns.iterator(reverse=True):
+    ///     print(key, value)
+    /// ```
+    #[pyo3(signature = (reverse=None))]
+    fn iterator(&self, py: Python, reverse: Option<bool>) -> PyResult<IteratorPy> {
+        self.range(py, None, None, reverse.unwrap_or(false), true, true)
+    }
+
+    /// Returns an iterator over the (key, value) entries of the range in the namespace. The
+    /// range includes `start` and excludes `stop`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, value in ns.items(b'a', b'b'):
+    ///     print(key, value)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn items(
+        &self,
+        py: Python,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+    ) -> PyResult<IteratorPy> {
+        self.range(py, start, stop, reverse.unwrap_or(false), true, true)
+    }
+
+    /// Returns an iterator over the keys of the range in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key in ns.keys():
+    ///     print(key)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn keys(
+        &self,
+        py: Python,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+    ) -> PyResult<IteratorPy> {
+        self.range(py, start, stop, reverse.unwrap_or(false), true, false)
+    }
+
+    /// Returns an iterator over the values of the range in the namespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for value in ns.values():
+    ///     print(value)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn values(
+        &self,
+        py: Python,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: Option<bool>,
+    ) -> PyResult<IteratorPy> {
+        self.range(py, start, stop, reverse.unwrap_or(false), false, true)
+    }
+
+    /// Returns a namespace inside the namespace, the prefixes are concatenated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// admins = ns.namespace(b'admins:')
+    /// ```
+    fn namespace(&self, py: Python, prefix: &PyAny) -> PyResult<NamespacePy> {
+        let db = self.db.borrow(py);
+        let prefix = db.key(prefix)?;
+
+        Ok(NamespacePy::new(
+            self.db.clone_ref(py),
+            [self.prefix.as_slice(), prefix.as_bytes()].concat(),
+        ))
+    }
+}
+
+impl NamespacePy {
+    pub fn new(db: Py<DBPy>, prefix: Vec<u8>) -> NamespacePy {
+        NamespacePy { db, prefix }
+    }
+
+    /// Prepends the prefix to the key.
+    fn key<'py>(&self, py: Python<'py>, db: &DBPy, key: &PyAny) -> PyResult<&'py PyAny> {
+        let key = db.key(key)?;
+
+        Ok(PyBytes::new(py, &[self.prefix.as_slice(), key.as_bytes()].concat()))
+    }
+
+    /// Returns an iterator over the range of the namespace. The bounds are limited to the
+    /// prefix, so the prefix can be removed from every key.
+    fn range(
+        &self,
+        py: Python,
+        start: Option<&PyAny>,
+        stop: Option<&PyAny>,
+        reverse: bool,
+        keys: bool,
+        values: bool,
+    ) -> PyResult<IteratorPy> {
+        let db = self.db.borrow(py);
+
+        let seek = if reverse {
+            IteratorSeek::End
+        } else {
+            IteratorSeek::Start
+        };
+
+        let lower_bound = match start {
+            Some(start) => [self.prefix.as_slice(), db.key(start)?.as_bytes()].concat(),
+            None => self.prefix.clone(),
+        };
+
+        let upper_bound = match stop {
+            Some(stop) => Some([self.prefix.as_slice(), db.key(stop)?.as_bytes()].concat()),
+            None => prefix_upper_bound(&self.prefix),
+        };
+
+        let opts = IteratorOptions {
+            lower_bound: Some(lower_bound),
+            upper_bound,
+            expiry: db.key_ttl(),
+            ..Default::default()
+        };
+
+        let (codec, text) = (db.value_codec(), db.text_keys());
+
+        if let Some(db) = db.inner()? {
+            Ok(IteratorPy::new(db, seek, opts)
+                .select(keys, values)
+                .strip_prefix(&self.prefix)
+                .decoded(codec)
+                .text(text))
+        } else {
+            Err(DBClosedError::new_err("Iterator cannot get, database is closed"))
+        }
+    }
+}
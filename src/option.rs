@@ -1,10 +1,14 @@
+use crate::comparator::*;
+use crate::timestamp::*;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use rocksdb::{DBCompactionStyle, Options};
+use rocksdb::{DBCompactionStyle, DBCompressionType, DBRecoveryMode, Options};
 
 #[pyclass(name = "Option")]
 #[derive(Clone)]
 pub struct OptionPy {
     pub inner: Options,
+    pub key_ttl: bool,
 }
 
 #[pymethods]
@@ -13,7 +17,82 @@ impl OptionPy {
     pub fn new() -> Self {
         let opts = Options::default();
 
-        OptionPy { inner: opts }
+        OptionPy {
+            inner: opts,
+            key_ttl: false,
+        }
+    }
+
+    /// If true, records can be set with a TTL by `db.set(key, value, ttl=seconds)`. Values
+    /// are stored with an expiration header, reads hide the expired records and compactions
+    /// remove them. It must be enabled when the database is created and kept enabled.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// opts.set_key_ttl(True)
+    /// ```
+    pub fn set_key_ttl(&mut self, enabled: bool) {
+        self.key_ttl = enabled
+    }
+
+    /// Keys carry a user timestamp, records are written by `db.set_with_ts(key, value, ts)`
+    /// and read as of a timestamp by `db.get_with_ts(key, ts)`. The timestamps are unsigned
+    /// 64-bit integers. It must be enabled when the database is created and on every open,
+    /// the reads and writes without a timestamp fail.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// opts.enable_user_timestamp()
+    /// ```
+    pub fn enable_user_timestamp(&mut self) {
+        set_timestamp_comparator(&mut self.inner)
+    }
+
+    /// Orders the keys bytewise from the largest to the smallest, so keys prefixed by a
+    /// big-endian timestamp are iterated from the newest. The bounds of the iterators follow
+    /// this order, the lower bound is the largest key. It must be set on every open.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// opts.set_reverse_comparator()
+    /// ```
+    pub fn set_reverse_comparator(&mut self) {
+        set_reverse_comparator(&mut self.inner)
+    }
+
+    /// Orders the keys by a Python function which returns a negative number, zero or a
+    /// positive number like `cmp`. The comparator must be set with the same name on every
+    /// open, RocksDB refuses a database created by another comparator. The function is called
+    /// with the GIL on every comparison, also by the background compactions, so it is slow.
+    /// Exceptions are printed and the keys are compared bytewise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// opts.set_comparator('casefold', lambda a, b: (a.lower() > b.lower()) - (a.lower() < b.lower()))
+    /// ```
+    pub fn set_comparator(&mut self, name: &str, compare: PyObject) {
+        set_python_comparator(&mut self.inner, name, compare)
+    }
+
+    /// Orders the keys by their components without calling Python. The unsigned integers
+    /// `u16be`, `u16le`, `u32be`, `u32le`, `u64be` and `u64le` are compared by their values,
+    /// the rest of the key is compared bytewise by `bytes` or ignoring the ASCII case by
+    /// `ascii_ci`. A key which is too short for an integer is ordered before the keys which
+    /// have it. The comparator must be set with the same name on every open.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// opts.set_composite_comparator('tenant-name', ['u32le', 'ascii_ci'])
+    /// ```
+    pub fn set_composite_comparator(&mut self, name: &str, components: Vec<String>) -> PyResult<()> {
+        set_composite_comparator(&mut self.inner, name, &components)
     }
 
     /// If true, the database will be created if it is missing.
@@ -988,6 +1067,64 @@ impl OptionPy {
         self.inner.set_max_compaction_bytes(nbytes)
     }
 
+    /// Sets the age in seconds after which the SST files are compacted again, even if no
+    /// compaction picks them. The files of the ranges which are rarely written are rewritten,
+    /// so their tombstones and stale blob references are removed.
+    ///
+    /// Value 0 disables the periodic compactions.
+    ///
+    /// Default: 30 days for the level style compaction with a compaction filter, otherwise `0`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_periodic_compaction_seconds(7 * 24 * 3600)
+    /// ```
+    pub fn set_periodic_compaction_seconds(&mut self, secs: u64) {
+        self.inner.set_periodic_compaction_seconds(secs)
+    }
+
+    /// Sets the compaction TTL in seconds, the SST files whose data is older than the TTL are
+    /// compacted to the next level, so old data reaches the bottommost level. The FIFO
+    /// compaction deletes the files which are older than the TTL. It is not related to the key
+    /// TTL of `open_with_ttl` and `set_key_ttl`.
+    ///
+    /// Value 0 disables the TTL compactions.
+    ///
+    /// Default: 30 days for the level style compaction, otherwise `0`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_ttl(30 * 24 * 3600)
+    /// ```
+    pub fn set_ttl(&mut self, secs: u64) {
+        self.inner.set_ttl(secs)
+    }
+
+    /// Marks the SST files with many deletions for compaction when they are written, so the
+    /// tombstones of a mass deletion are compacted away before they slow down the scans. A file
+    /// is marked if a window of `window_size` consecutive entries contains at least
+    /// `num_dels_trigger` deletions, or if the ratio of the deletions in the whole file is at
+    /// least `deletion_ratio`.
+    ///
+    /// A `deletion_ratio` of 0 disables the ratio trigger.
+    ///
+    /// Examples
+    /// ```
+    /// opts.add_compact_on_deletion_collector_factory(128 * 1024, 64 * 1024, 0.5)
+    /// ```
+    pub fn add_compact_on_deletion_collector_factory(
+        &mut self,
+        window_size: usize,
+        num_dels_trigger: usize,
+        deletion_ratio: f64,
+    ) {
+        self.inner.add_compact_on_deletion_collector_factory(
+            window_size,
+            num_dels_trigger,
+            deletion_ratio,
+        )
+    }
+
     /// Sets the WAL ttl in seconds.
     ///
     /// The following two options affect how archived logs will be deleted.
@@ -1026,6 +1163,40 @@ impl OptionPy {
         self.inner.set_wal_size_limit_mb(size)
     }
 
+    /// Sets how the corrupted records of the WAL are handled on recovery, one of
+    /// `tolerate_corrupted_tail_records`, `absolute_consistency`, `point_in_time` and
+    /// `skip_any_corrupted_records`. A database whose log is damaged is salvaged by opening it
+    /// with `point_in_time`, which recovers up to the first corrupted record, or with
+    /// `skip_any_corrupted_records`, which drops every corrupted record.
+    ///
+    /// The records cannot be filtered one by one, the WAL filter of RocksDB is not available
+    /// through its C API.
+    ///
+    /// Default: `point_in_time`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_wal_recovery_mode('skip_any_corrupted_records')
+    /// ```
+    pub fn set_wal_recovery_mode(&mut self, mode: &str) -> PyResult<()> {
+        let m = match mode {
+            "tolerate_corrupted_tail_records" => DBRecoveryMode::TolerateCorruptedTailRecords,
+            "absolute_consistency" => DBRecoveryMode::AbsoluteConsistency,
+            "point_in_time" => DBRecoveryMode::PointInTime,
+            "skip_any_corrupted_records" => DBRecoveryMode::SkipAnyCorruptedRecord,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "WAL recovery mode is not supported, {}",
+                    mode
+                )))
+            }
+        };
+
+        self.inner.set_wal_recovery_mode(m);
+
+        Ok(())
+    }
+
     /// Sets the number of bytes to preallocate (via fallocate) the manifest files.
     ///
     /// Default is 4MB, which is reasonable to reduce random IO as well as prevent overallocation
@@ -1266,6 +1437,127 @@ impl OptionPy {
         self.inner.set_enable_blob_files(val)
     }
 
+    /// Sets the size of the smallest value which is stored in a blob file when blob files are
+    /// enabled, smaller values are stored in the SST files.
+    ///
+    /// Dynamically changeable through `SetOptions()` API
+    ///
+    /// Default: `0`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_min_blob_size(4096)
+    /// ```
+    pub fn set_min_blob_size(&mut self, val: u64) {
+        self.inner.set_min_blob_size(val)
+    }
+
+    /// Sets the size limit of the blob files.
+    ///
+    /// Dynamically changeable through `SetOptions()` API
+    ///
+    /// Default: `256MB`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_blob_file_size(512 * 1024 * 1024)
+    /// ```
+    pub fn set_blob_file_size(&mut self, val: u64) {
+        self.inner.set_blob_file_size(val)
+    }
+
+    /// Sets the compression of the blob files, one of `none`, `snappy`, `zlib`, `bz2`, `lz4`,
+    /// `lz4hc` and `zstd`.
+    ///
+    /// Dynamically changeable through `SetOptions()` API
+    ///
+    /// Default: `none`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_blob_compression_type('zstd')
+    /// ```
+    pub fn set_blob_compression_type(&mut self, compression: &str) -> PyResult<()> {
+        let c = match compression {
+            "none" => DBCompressionType::None,
+            "snappy" => DBCompressionType::Snappy,
+            "zlib" => DBCompressionType::Zlib,
+            "bz2" => DBCompressionType::Bz2,
+            "lz4" => DBCompressionType::Lz4,
+            "lz4hc" => DBCompressionType::Lz4hc,
+            "zstd" => DBCompressionType::Zstd,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Compression is not supported, {}",
+                    compression
+                )))
+            }
+        };
+
+        self.inner.set_blob_compression_type(c);
+
+        Ok(())
+    }
+
+    /// Enables the garbage collection of the blob files, the valid blobs of the oldest blob
+    /// files are relocated by the compactions.
+    ///
+    /// Dynamically changeable through `SetOptions()` API
+    ///
+    /// Default: `false`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_enable_blob_gc(True)
+    /// ```
+    pub fn set_enable_blob_gc(&mut self, val: bool) {
+        self.inner.set_enable_blob_gc(val)
+    }
+
+    /// Sets the fraction of the oldest blob files which are collected by the garbage
+    /// collection.
+    ///
+    /// Dynamically changeable through `SetOptions()` API
+    ///
+    /// Default: `0.25`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_blob_gc_age_cutoff(0.5)
+    /// ```
+    pub fn set_blob_gc_age_cutoff(&mut self, val: f64) {
+        self.inner.set_blob_gc_age_cutoff(val)
+    }
+
+    /// Sets the ratio of the garbage in the oldest blob files which forces a compaction of
+    /// the SST files which reference them.
+    ///
+    /// Dynamically changeable through `SetOptions()` API
+    ///
+    /// Default: `1.0`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_blob_gc_force_threshold(0.8)
+    /// ```
+    pub fn set_blob_gc_force_threshold(&mut self, val: f64) {
+        self.inner.set_blob_gc_force_threshold(val)
+    }
+
+    /// Sets the readahead size of the blob files which are read by the compactions.
+    ///
+    /// Dynamically changeable through `SetOptions()` API
+    ///
+    /// Default: `0`
+    ///
+    /// Examples
+    /// ```
+    /// opts.set_blob_compaction_readahead_size(2 * 1024 * 1024)
+    /// ```
+    pub fn set_blob_compaction_readahead_size(&mut self, val: u64) {
+        self.inner.set_blob_compaction_readahead_size(val)
+    }
+
     /// Set this option to true during creation of database if you want to be able to ingest behind
     /// (call `IngestExternalFile()` skipping keys that already exist, rather than overwriting
     /// matching keys). Setting this option to true has the following effects:
@@ -0,0 +1,153 @@
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use std::any::Any;
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+/// Read-only buffer over memory which is owned by RocksDB. It is exposed to Python as a
+/// `memoryview` without copying the data.
+#[pyclass(name = "PinnedSlice", unsendable)]
+pub struct PinnedSlicePy {
+    ptr: *const u8,
+    len: usize,
+    owner: Option<Box<dyn Any>>,
+    /// Number of the buffers which are exported and not released yet. The memoryview objects
+    /// which are derived from a view, e.g. slices, share its buffer.
+    exports: Cell<usize>,
+}
+
+#[pymethods]
+impl PinnedSlicePy {
+    unsafe fn __getbuffer__(
+        slf: PyRefMut<Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("Pinned slice is not writable"));
+        }
+
+        ffi::Py_INCREF(slf.as_ptr());
+
+        (*view).obj = slf.as_ptr();
+        (*view).buf = slf.ptr as *mut c_void;
+        (*view).len = slf.len as isize;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+
+        (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+            CStr::from_bytes_with_nul(b"B\0").unwrap().as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+
+        (*view).ndim = 1;
+
+        (*view).shape = if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+            &mut (*view).len
+        } else {
+            ptr::null_mut()
+        };
+
+        (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+            &mut (*view).itemsize
+        } else {
+            ptr::null_mut()
+        };
+
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = ptr::null_mut();
+
+        slf.exports.set(slf.exports.get() + 1);
+
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {
+        self.exports.set(self.exports.get().saturating_sub(1));
+    }
+
+    fn __len__(&self) -> usize {
+        self.len
+    }
+}
+
+impl PinnedSlicePy {
+    /// Creates a buffer over the memory of the owner, `keep` is dropped after the owner.
+    ///
+    /// # Safety
+    ///
+    /// The memory of the owner must not move when the owner is moved, e.g. it is allocated
+    /// on the heap.
+    pub unsafe fn owned<T, K>(owner: T, keep: K) -> PinnedSlicePy
+    where
+        T: AsRef<[u8]> + 'static,
+        K: 'static,
+    {
+        let slice = owner.as_ref();
+        let (ptr, len) = (slice.as_ptr(), slice.len());
+
+        PinnedSlicePy {
+            ptr,
+            len,
+            owner: Some(Box::new((owner, keep))),
+            exports: Cell::new(0),
+        }
+    }
+
+    /// Skips the first `n` bytes of the buffer.
+    pub fn advance(mut self, n: usize) -> PinnedSlicePy {
+        let n = n.min(self.len);
+
+        self.ptr = unsafe { self.ptr.add(n) };
+        self.len -= n;
+
+        self
+    }
+
+    /// Creates a buffer over borrowed memory.
+    ///
+    /// # Safety
+    ///
+    /// The memoryview of the buffer must be released before the slice becomes invalid.
+    pub unsafe fn borrowed(slice: &[u8]) -> PinnedSlicePy {
+        PinnedSlicePy {
+            ptr: slice.as_ptr(),
+            len: slice.len(),
+            owner: None,
+            exports: Cell::new(0),
+        }
+    }
+
+    /// Returns true if a buffer of the slice is still used by Python.
+    pub fn exported(&self) -> bool {
+        self.exports.get() > 0
+    }
+
+    /// Makes the slice own the memory which it borrows, so the buffers which outlive the
+    /// borrow stay valid. The owner is dropped after the last buffer is released.
+    pub fn adopt(&mut self, owner: Box<dyn Any>) {
+        self.owner = Some(owner);
+    }
+}
+
+/// Returns a `memoryview` over the pinned slice.
+pub fn memoryview(py: Python, slice: PinnedSlicePy) -> PyResult<PyObject> {
+    memoryview_of(py, &Py::new(py, slice)?)
+}
+
+/// Returns a `memoryview` over the pinned slice object, the slice counts its exports.
+pub fn memoryview_of(py: Python, buffer: &Py<PinnedSlicePy>) -> PyResult<PyObject> {
+    unsafe {
+        let view: &PyAny = py.from_owned_ptr_or_err(ffi::PyMemoryView_FromObject(buffer.as_ptr()))?;
+
+        Ok(view.into_py(py))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::base::*;
+use crate::db::*;
+use crate::option::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+/// Opens a secondary instance of the primary database in every process which reads from it,
+/// so every worker of a pre-fork server can read while one process owns the writes. The
+/// pool only keeps the settings until `get` is called, so it can be created before fork.
+#[pyclass(name = "ReadPool")]
+pub struct ReadPoolPy {
+    primary: PathBuf,
+    root: PathBuf,
+    interval: Duration,
+    option: Option<OptionPy>,
+    codec: Option<PyObject>,
+    text: bool,
+    handle: Option<(u32, Py<DBPy>)>,
+}
+
+#[pymethods]
+impl ReadPoolPy {
+    #[new]
+    #[pyo3(signature = (
+        primary,
+        secondary_root,
+        catch_up_interval=None,
+        option=None,
+        codec=None,
+        text=None,
+    ))]
+    fn new(
+        primary: PathBuf,
+        secondary_root: PathBuf,
+        catch_up_interval: Option<f64>,
+        option: Option<OptionPy>,
+        codec: Option<PyObject>,
+        text: Option<bool>,
+    ) -> PyResult<Self> {
+        let interval = match Duration::try_from_secs_f64(catch_up_interval.unwrap_or(1.0)) {
+            Ok(interval) if !interval.is_zero() => interval,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "Pool cannot open, catch_up_interval must be positive",
+                ))
+            }
+        };
+
+        Ok(ReadPoolPy {
+            primary,
+            root: secondary_root,
+            interval,
+            option,
+            codec,
+            text: text.unwrap_or(false),
+            handle: None,
+        })
+    }
+
+    /// The directory of the secondary instance of the current process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// path = pool.path
+    /// ```
+    #[getter]
+    fn path(&self) -> PathBuf {
+        self.root.join(process::id().to_string())
+    }
+
+    /// Returns the secondary instance of the current process, it is opened by the first call
+    /// in the process. The instance catches up with the primary every `catch_up_interval`
+    /// seconds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # gunicorn post_fork hook, celery worker_process_init signal, ...
+    /// db = pool.get()
+    ///
+    /// value = db.get(b'key')
+    /// ```
+    fn get(&mut self, py: Python) -> PyResult<Py<DBPy>> {
+        let pid = process::id();
+
+        if let Some((owner, db)) = &self.handle {
+            if *owner == pid && db.borrow(py).db.is_some() {
+                return Ok(db.clone_ref(py));
+            }
+        }
+
+        // the handle inherited from the parent is released without closing its database
+        self.handle = None;
+
+        let codec = self.codec.as_ref().map(|codec| codec.as_ref(py));
+
+        let db = open_as_secondary(
+            self.primary.clone(),
+            self.path(),
+            self.option.clone(),
+            codec,
+            Some(self.text),
+            Some(self.interval.as_secs_f64()),
+        )?;
+
+        let db = Py::new(py, db)?;
+
+        self.handle = Some((pid, db.clone_ref(py)));
+
+        Ok(db)
+    }
+
+    /// Closes the secondary instance of the current process and removes its directory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// pool.close()
+    /// ```
+    fn close(&mut self, py: Python) -> PyResult<()> {
+        let db = match self.handle.take() {
+            Some((owner, db)) if owner == process::id() => db,
+            _ => return Ok(()),
+        };
+
+        db.call_method0(py, "close")?;
+
+        match fs::remove_dir_all(self.path()) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(RocksDBPyException::new_err(
+                format!("Pool cannot remove the secondary directory. {}", e),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
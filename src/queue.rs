@@ -0,0 +1,289 @@
+use crate::base::*;
+use crate::db::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rocksdb::{Direction, ReadOptions};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Sequence number of the first entry of an empty queue, the middle of the key space leaves
+/// room for `push_front`.
+const START: u64 = 1 << 63;
+
+/// Bounds of the live entries of a queue, `None` if a side is not known. The popped entries
+/// leave tombstones behind, the bounds make the reads skip them.
+#[derive(Clone, Copy, Default)]
+pub struct QueueBounds {
+    front: Option<u64>,
+    back: Option<u64>,
+}
+
+/// Bounds of the queues of a database by the names of their column families.
+pub type QueueHints = Arc<Mutex<HashMap<String, QueueBounds>>>;
+
+/// Durable queue stored in a column family. The entries are keyed by big-endian sequence
+/// numbers, so the order of the keys is the order of the queue. Entries can be added and
+/// removed at both ends.
+#[pyclass(name = "Queue")]
+pub struct QueuePy {
+    db: Py<DBPy>,
+    name: String,
+}
+
+#[pymethods]
+impl QueuePy {
+    /// The name of the column family of the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// name = queue.name
+    /// ```
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Appends the "value" to the back of the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// queue.push(b'job')
+    /// ```
+    fn push(&self, py: Python, value: &PyAny) -> PyResult<()> {
+        self.insert(py, value, Direction::Reverse)
+    }
+
+    /// Prepends the "value" to the front of the queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// queue.push_front(b'urgent job')
+    /// ```
+    fn push_front(&self, py: Python, value: &PyAny) -> PyResult<()> {
+        self.insert(py, value, Direction::Forward)
+    }
+
+    /// Removes and returns the value at the front of the queue, `None` if the queue is empty.
+    /// Concurrent pops of the database never return the same entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// job = queue.pop_front()
+    /// ```
+    fn pop_front(&self, py: Python) -> PyResult<Option<PyObject>> {
+        self.remove(py, Direction::Forward)
+    }
+
+    /// Removes and returns the value at the back of the queue, `None` if the queue is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// job = queue.pop_back()
+    /// ```
+    fn pop_back(&self, py: Python) -> PyResult<Option<PyObject>> {
+        self.remove(py, Direction::Reverse)
+    }
+
+    /// Returns the value at the front of the queue without removing it, `None` if the queue
+    /// is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// job = queue.peek()
+    /// ```
+    fn peek(&self, py: Python) -> PyResult<Option<PyObject>> {
+        let db = self.db.borrow(py);
+
+        match end(&db, &self.name, Direction::Forward, self.bounds(&db))? {
+            Some((_, value)) => db.decode(py, &value),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the number of entries of the queue, the column family is scanned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = len(queue)
+    /// ```
+    fn __len__(&self, py: Python) -> PyResult<usize> {
+        let db = self.db.borrow(py);
+        let inner = db.handle("Queue cannot count")?;
+        let cf = db.column_family(inner, &self.name)?;
+
+        let mut iter = inner.raw_iterator_cf_opt(&cf, read_options(self.bounds(&db)));
+        let mut n = 0;
+
+        iter.seek_to_first();
+
+        while iter.valid() {
+            n += 1;
+            iter.next();
+        }
+
+        match iter.status() {
+            Ok(()) => Ok(n),
+            Err(e) => Err(rocksdb_error(&e, format!("Queue cannot count. {}", e))),
+        }
+    }
+}
+
+impl QueuePy {
+    pub fn new(db: Py<DBPy>, name: String) -> QueuePy {
+        QueuePy { db, name }
+    }
+
+    /// Stores the value next to the entry at the end of the queue which is reached by
+    /// iterating in the direction, so `Reverse` appends and `Forward` prepends.
+    fn insert(&self, py: Python, value: &PyAny, direction: Direction) -> PyResult<()> {
+        let db = self.db.borrow(py);
+        let value = db.encode(py, value)?;
+        let inner = db.handle("Queue cannot push")?;
+        db.column_family(inner, &self.name)?;
+
+        let _guard = db.write_lock().exclusive(py);
+
+        let seq = match end(&db, &self.name, direction, self.bounds(&db))? {
+            Some((key, _)) => match direction {
+                Direction::Forward => sequence(&key)?.checked_sub(1),
+                Direction::Reverse => sequence(&key)?.checked_add(1),
+            },
+            None => Some(START),
+        };
+
+        let seq = match seq {
+            Some(seq) => seq,
+            None => {
+                return Err(RocksDBPyException::new_err(
+                    "Queue cannot push, queue is full",
+                ))
+            }
+        };
+
+        let name = self.name.as_str();
+
+        let written = py.allow_threads(|| {
+            cf_handle(inner, name).map(|cf| inner.put_cf(&cf, seq.to_be_bytes(), value))
+        })?;
+
+        if let Err(e) = written {
+            return Err(rocksdb_error(&e, format!("Queue cannot push. {}", e)));
+        }
+
+        // the entry may be outside of the known bounds, e.g. the first entry after the pops
+        self.update(&db, |bounds| {
+            bounds.front = bounds.front.map(|front| front.min(seq));
+            bounds.back = bounds.back.map(|back| back.max(seq));
+        });
+
+        Ok(())
+    }
+
+    /// Removes the entry at the end of the queue which is reached by iterating in the
+    /// direction. No other write of the database happens between the read and the delete.
+    fn remove(&self, py: Python, direction: Direction) -> PyResult<Option<PyObject>> {
+        let db = self.db.borrow(py);
+        let inner = db.handle("Queue cannot pop")?;
+        db.column_family(inner, &self.name)?;
+
+        let _guard = db.write_lock().exclusive(py);
+
+        let (key, value) = match end(&db, &self.name, direction, self.bounds(&db))? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let seq = sequence(&key)?;
+
+        let name = self.name.as_str();
+
+        let deleted =
+            py.allow_threads(|| cf_handle(inner, name).map(|cf| inner.delete_cf(&cf, key)))?;
+
+        if let Err(e) = deleted {
+            return Err(rocksdb_error(&e, format!("Queue cannot pop. {}", e)));
+        }
+
+        // no entry is left before the popped one, the next reads start after its tombstone
+        self.update(&db, |bounds| match direction {
+            Direction::Forward => bounds.front = Some(seq.saturating_add(1)),
+            Direction::Reverse => bounds.back = Some(seq.saturating_sub(1)),
+        });
+
+        db.decode(py, &value)
+    }
+
+    /// Returns the bounds of the live entries of the queue.
+    fn bounds(&self, db: &DBPy) -> QueueBounds {
+        let hints = db.queue_hints().lock().unwrap_or_else(|e| e.into_inner());
+
+        hints.get(&self.name).copied().unwrap_or_default()
+    }
+
+    /// Changes the bounds of the queue, the write lock must be held.
+    fn update(&self, db: &DBPy, change: impl FnOnce(&mut QueueBounds)) {
+        let mut hints = db.queue_hints().lock().unwrap_or_else(|e| e.into_inner());
+
+        change(hints.entry(self.name.clone()).or_default());
+    }
+}
+
+/// Limits the iteration to the bounds of the live entries.
+fn read_options(bounds: QueueBounds) -> ReadOptions {
+    let mut opts = ReadOptions::default();
+
+    if let Some(front) = bounds.front {
+        opts.set_iterate_lower_bound(front.to_be_bytes().to_vec());
+    }
+
+    // the upper bound is exclusive
+    if let Some(upper) = bounds.back.and_then(|back| back.checked_add(1)) {
+        opts.set_iterate_upper_bound(upper.to_be_bytes().to_vec());
+    }
+
+    opts
+}
+
+/// Returns the first entry of the queue reached by iterating in the direction.
+fn end(
+    db: &DBPy,
+    name: &str,
+    direction: Direction,
+    bounds: QueueBounds,
+) -> PyResult<Option<(Vec<u8>, Vec<u8>)>> {
+    let inner = db.handle("Queue cannot read")?;
+    let cf = db.column_family(inner, name)?;
+    let mut iter = inner.raw_iterator_cf_opt(&cf, read_options(bounds));
+
+    match direction {
+        Direction::Forward => iter.seek_to_first(),
+        Direction::Reverse => iter.seek_to_last(),
+    }
+
+    if let Err(e) = iter.status() {
+        return Err(rocksdb_error(&e, format!("Queue cannot read. {}", e)));
+    }
+
+    Ok(iter
+        .item()
+        .map(|(key, value)| (key.to_vec(), value.to_vec())))
+}
+
+/// Decodes the sequence number of a key, the keys of other records of the column family are
+/// rejected.
+fn sequence(key: &[u8]) -> PyResult<u64> {
+    match <[u8; 8]>::try_from(key) {
+        Ok(seq) => Ok(u64::from_be_bytes(seq)),
+        Err(_) => Err(PyValueError::new_err(format!(
+            "Queue cannot read, key {:?} is not a queue entry",
+            key
+        ))),
+    }
+}
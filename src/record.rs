@@ -0,0 +1,149 @@
+/// Size of the header of a serialized batch, the sequence number and the count of records.
+const HEADER: usize = 12;
+
+/// Record types of the serialized batch of RocksDB, see `db/dbformat.h`.
+const DELETION: u8 = 0x0;
+const VALUE: u8 = 0x1;
+const MERGE: u8 = 0x2;
+const LOG_DATA: u8 = 0x3;
+const CF_DELETION: u8 = 0x4;
+const CF_VALUE: u8 = 0x5;
+const CF_MERGE: u8 = 0x6;
+const SINGLE_DELETION: u8 = 0x7;
+const CF_SINGLE_DELETION: u8 = 0x8;
+const BEGIN_PREPARE: u8 = 0x9;
+const END_PREPARE: u8 = 0xA;
+const COMMIT: u8 = 0xB;
+const ROLLBACK: u8 = 0xC;
+const NOOP: u8 = 0xD;
+const CF_RANGE_DELETION: u8 = 0xE;
+const RANGE_DELETION: u8 = 0xF;
+const BEGIN_PERSISTED_PREPARE: u8 = 0x12;
+const BEGIN_UNPREPARE: u8 = 0x13;
+const WIDE_COLUMN_ENTITY: u8 = 0x16;
+const CF_WIDE_COLUMN_ENTITY: u8 = 0x17;
+
+/// Operation of a record of a batch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    Put,
+    Delete,
+    SingleDelete,
+    DeleteRange,
+    Merge,
+    PutEntity,
+}
+
+impl Operation {
+    /// Name of the operation for Python.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Put => "put",
+            Operation::Delete => "delete",
+            Operation::SingleDelete => "single_delete",
+            Operation::DeleteRange => "delete_range",
+            Operation::Merge => "merge",
+            Operation::PutEntity => "put_entity",
+        }
+    }
+}
+
+/// Record of a batch. The value of a range deletion is the end of the range, deletions have
+/// no value.
+pub struct Record<'a> {
+    pub op: Operation,
+    pub cf: u32,
+    pub key: &'a [u8],
+    pub value: Option<&'a [u8]>,
+}
+
+/// Decodes the records of a serialized batch. The markers of transactions and the log data
+/// are skipped.
+pub fn parse(data: &[u8]) -> Result<Vec<Record>, &'static str> {
+    let mut reader = Reader {
+        data: data.get(HEADER..).ok_or("batch is too short")?,
+    };
+
+    let mut records = Vec::new();
+
+    while let Some(tag) = reader.byte() {
+        let cf = match tag {
+            CF_VALUE | CF_DELETION | CF_SINGLE_DELETION | CF_RANGE_DELETION | CF_MERGE
+            | CF_WIDE_COLUMN_ENTITY => reader.varint()?,
+            _ => 0,
+        };
+
+        let (op, with_value) = match tag {
+            VALUE | CF_VALUE => (Operation::Put, true),
+            DELETION | CF_DELETION => (Operation::Delete, false),
+            SINGLE_DELETION | CF_SINGLE_DELETION => (Operation::SingleDelete, false),
+            RANGE_DELETION | CF_RANGE_DELETION => (Operation::DeleteRange, true),
+            MERGE | CF_MERGE => (Operation::Merge, true),
+            WIDE_COLUMN_ENTITY | CF_WIDE_COLUMN_ENTITY => (Operation::PutEntity, true),
+            LOG_DATA | END_PREPARE | COMMIT | ROLLBACK => {
+                reader.slice()?;
+                continue;
+            }
+            NOOP | BEGIN_PREPARE | BEGIN_PERSISTED_PREPARE | BEGIN_UNPREPARE => continue,
+            _ => return Err("unknown record type"),
+        };
+
+        let key = reader.slice()?;
+        let value = if with_value {
+            Some(reader.slice()?)
+        } else {
+            None
+        };
+
+        records.push(Record { op, cf, key, value });
+    }
+
+    Ok(records)
+}
+
+/// Reads the fields of the records.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let (first, rest) = self.data.split_first()?;
+
+        self.data = rest;
+
+        Some(*first)
+    }
+
+    /// Reads a 32-bit integer which is encoded by 7 bits per byte.
+    fn varint(&mut self) -> Result<u32, &'static str> {
+        let mut value: u32 = 0;
+
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte().ok_or("batch is truncated")?;
+
+            value |= ((byte & 0x7f) as u32) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err("varint is too long")
+    }
+
+    /// Reads a slice which is prefixed by its length.
+    fn slice(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = self.varint()? as usize;
+
+        if self.data.len() < len {
+            return Err("batch is truncated");
+        }
+
+        let (slice, rest) = self.data.split_at(len);
+
+        self.data = rest;
+
+        Ok(slice)
+    }
+}
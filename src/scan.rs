@@ -0,0 +1,262 @@
+use crate::base::*;
+use crate::codec::*;
+use crate::iterator::*;
+use crate::ttl::*;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use rocksdb::DEFAULT_COLUMN_FAMILY_NAME;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// Number of the entries which a worker sends at once.
+const SCAN_BATCH: usize = 1024;
+
+/// Entries of a chunk, or the error which stopped the chunk.
+type Batch = Result<Vec<(Vec<u8>, Vec<u8>)>, rocksdb::Error>;
+
+/// Iterator over the (key, value) entries of a range which are read by many threads. The range
+/// is split into chunks and every chunk is read by a worker without the GIL, the entries of a
+/// chunk are in order but the chunks are interleaved.
+#[pyclass(name = "ParallelScan")]
+pub struct ParallelScanPy {
+    receiver: Option<Receiver<Batch>>,
+    buffer: VecDeque<(Vec<u8>, Vec<u8>)>,
+    stop: Arc<AtomicBool>,
+    workers: Vec<JoinHandle<()>>,
+    codec: Option<Codec>,
+    text: bool,
+}
+
+#[pymethods]
+impl ParallelScanPy {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    /// Returns the next entry, it waits for the workers without the GIL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// key, value = next(scan)
+    /// ```
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        loop {
+            if let Some((key, value)) = self.buffer.pop_front() {
+                let key = decode_key(py, self.text, &key)?;
+                let value: &PyAny = decode_value(py, &self.codec, &value)?.into_ref(py);
+
+                return Ok(Some(PyTuple::new(py, &[key, value]).into_py(py)));
+            }
+
+            let receiver = match self.receiver.take() {
+                Some(receiver) => receiver,
+                None => return Ok(None),
+            };
+
+            // the receiver is moved into the closure, it cannot be shared without the GIL
+            let (receiver, batch) = py.allow_threads(move || {
+                let batch = receiver.recv();
+
+                (receiver, batch)
+            });
+
+            match batch {
+                Ok(Ok(entries)) => {
+                    self.receiver = Some(receiver);
+                    self.buffer.extend(entries);
+                }
+                Ok(Err(e)) => {
+                    self.close(py);
+
+                    return Err(rocksdb_error(&e, format!("Range cannot scan. {}", e)));
+                }
+                // every worker is done
+                Err(_) => {
+                    self.close(py);
+
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// Stops the workers, the remaining entries are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// scan.close()
+    /// ```
+    fn close(&mut self, py: Python) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.buffer.clear();
+
+        // the blocked senders return once the receiver is dropped
+        self.receiver = None;
+
+        let workers = std::mem::take(&mut self.workers);
+
+        py.allow_threads(|| {
+            for worker in workers {
+                let _ = worker.join();
+            }
+        });
+    }
+}
+
+impl ParallelScanPy {
+    /// Starts a worker for every chunk of the range, the range is split into `workers` chunks
+    /// at most. The values are decoded by the codec when they are returned.
+    pub fn new(
+        db: &Arc<DB>,
+        start: Option<Vec<u8>>,
+        stop: Option<Vec<u8>>,
+        workers: usize,
+        expiry: bool,
+        codec: Option<Codec>,
+    ) -> PyResult<ParallelScanPy> {
+        let name = DEFAULT_COLUMN_FAMILY_NAME;
+        let points = split_points(db, name, start.as_deref(), stop.as_deref(), workers)?;
+
+        let mut lower = start;
+        let mut chunks = Vec::with_capacity(points.len() + 1);
+
+        for point in points {
+            chunks.push((lower, Some(point.clone())));
+
+            lower = Some(point);
+        }
+
+        chunks.push((lower, stop));
+
+        // a few batches per worker are buffered, so slow consumers bound the memory
+        let (sender, receiver) = mpsc::sync_channel(chunks.len() * 2);
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let workers = chunks
+            .into_iter()
+            .map(|(lower_bound, upper_bound)| {
+                let opts = IteratorOptions {
+                    lower_bound,
+                    upper_bound,
+                    expiry,
+                    ..Default::default()
+                };
+
+                let db = db.clone();
+                let sender = sender.clone();
+                let flag = flag.clone();
+
+                thread::spawn(move || scan_chunk(&db, &opts, &sender, &flag))
+            })
+            .collect();
+
+        Ok(ParallelScanPy {
+            receiver: Some(receiver),
+            buffer: VecDeque::new(),
+            stop: flag,
+            workers,
+            codec,
+            text: false,
+        })
+    }
+
+    /// Returns the keys as `str` decoded from UTF-8 if the text mode is enabled.
+    pub fn text(mut self, enabled: bool) -> ParallelScanPy {
+        self.text = enabled;
+
+        self
+    }
+}
+
+impl Drop for ParallelScanPy {
+    fn drop(&mut self) {
+        // do not wait here, the workers exit when they send the next batch
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Reads the entries of a chunk and sends them in batches until the chunk is done, the scan is
+/// stopped or the receiver is dropped.
+fn scan_chunk(db: &DB, opts: &IteratorOptions, sender: &SyncSender<Batch>, stop: &AtomicBool) {
+    let mut inner = db.raw_iterator_opt(opts.read_options());
+    let mut batch = Vec::with_capacity(SCAN_BATCH);
+
+    inner.seek_to_first();
+
+    while let Some((key, value)) = inner.item() {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // expired entries are skipped until they are removed by a compaction
+        if let Some(value) = load(opts.expiry, value) {
+            batch.push((key.to_vec(), value.to_vec()));
+        }
+
+        if batch.len() == SCAN_BATCH {
+            let full = std::mem::replace(&mut batch, Vec::with_capacity(SCAN_BATCH));
+
+            if sender.send(Ok(full)).is_err() {
+                return;
+            }
+        }
+
+        inner.next();
+    }
+
+    let last = match inner.status() {
+        Ok(()) => Ok(batch),
+        Err(e) => Err(e),
+    };
+
+    let _ = sender.send(last);
+}
+
+/// Returns up to `n - 1` keys which split the range of the column family into chunks of similar
+/// size. The keys are the boundaries of the SST files in the range, so the split is approximate
+/// and the records of the memtables are not considered.
+pub(crate) fn split_points(
+    db: &DB,
+    cf: &str,
+    start: Option<&[u8]>,
+    stop: Option<&[u8]>,
+    n: usize,
+) -> PyResult<Vec<Vec<u8>>> {
+    if n < 2 {
+        return Ok(Vec::new());
+    }
+
+    let files = match db.live_files() {
+        Ok(files) => files,
+        Err(e) => return Err(rocksdb_error(&e, format!("Range cannot split. {}", e))),
+    };
+
+    // the chunk bounds must be strictly inside of the range
+    let inside = |key: &[u8]| {
+        !matches!(start, Some(start) if key <= start) && !matches!(stop, Some(stop) if key >= stop)
+    };
+
+    let mut boundaries: Vec<Vec<u8>> = files
+        .into_iter()
+        .filter(|file| file.column_family_name == cf)
+        .flat_map(|file| [file.start_key, file.end_key])
+        .flatten()
+        .filter(|key| inside(key))
+        .collect();
+
+    boundaries.sort();
+    boundaries.dedup();
+
+    let mut points: Vec<Vec<u8>> = (1..n)
+        .filter_map(|i| boundaries.get(i * boundaries.len() / n).cloned())
+        .collect();
+
+    points.dedup();
+
+    Ok(points)
+}
@@ -0,0 +1,550 @@
+use crate::base::*;
+use crate::buffer::*;
+use crate::iterator::*;
+use crate::merge::*;
+use crate::option::*;
+use crate::ttl::*;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use rocksdb::{Error, IngestExternalFileOptions, Options, SstFileWriter};
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Size of the SST files of a bulk import.
+const SST_FILE_SIZE: u64 = 256 << 20;
+
+/// Number of the readers of the process, it names their scratch databases.
+static READERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes sorted entries into an SST file outside of a database, so a bulk load skips the
+/// memtable and the compactions. The file is added to a database by `ingest_external_file`.
+#[pyclass(name = "SstFileWriter")]
+pub struct SstFileWriterPy {
+    // the writer borrows the options, so it is declared first and dropped before them
+    inner: SstFileWriter<'static>,
+    _opts: Box<Options>,
+    expiry: bool,
+    path: Option<PathBuf>,
+}
+
+#[pymethods]
+impl SstFileWriterPy {
+    /// Creates a writer with the options of the database which ingests the files, so the
+    /// values and merge operands are written in the format of the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// writer = SstFileWriter()
+    ///
+    /// writer = SstFileWriter(opts)
+    /// ```
+    #[new]
+    #[pyo3(signature = (option=None))]
+    fn new(option: Option<OptionPy>) -> Self {
+        let (mut opts, key_ttl) = match option {
+            Some(option) => (option.inner, option.key_ttl),
+            None => (Options::default(), false),
+        };
+
+        prepare(&mut opts, key_ttl);
+
+        let opts = Box::new(opts);
+        let inner = SstFileWriter::create(&opts);
+
+        SstFileWriterPy {
+            // the boxed options keep their address until the writer is dropped
+            inner: unsafe { std::mem::transmute::<SstFileWriter, SstFileWriter<'static>>(inner) },
+            _opts: opts,
+            expiry: key_ttl,
+            path: None,
+        }
+    }
+
+    /// Creates the SST file at the path, the file is overwritten if it exists.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// writer.open('/tmp/data.sst')
+    /// ```
+    fn open(&mut self, py: Python, path: PathBuf) -> PyResult<()> {
+        if let Some(path) = &self.path {
+            return Err(RocksDBPyException::new_err(format!(
+                "SST writer cannot open, {} is not finished",
+                path.display()
+            )));
+        }
+
+        let inner = &self.inner;
+
+        if let Err(e) = py.allow_threads(|| inner.open(&path)) {
+            return Err(rocksdb_error(
+                &e,
+                format!("SST writer cannot open {}. {}", path.display(), e),
+            ));
+        }
+
+        self.path = Some(path);
+
+        Ok(())
+    }
+
+    /// Adds the "key" with the "value", the keys must be added in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// writer.put(b'first', b'first_value')
+    ///
+    /// writer.put(b'first', b'first_value', ttl=3600)
+    /// ```
+    #[pyo3(signature = (key, value, ttl=None))]
+    fn put(&mut self, key: BytesLike, value: BytesLike, ttl: Option<u64>) -> PyResult<()> {
+        if ttl.is_some() && !self.expiry {
+            return Err(RocksDBPyException::new_err(
+                "SST writer cannot put with a TTL, key TTL is not enabled by the options",
+            ));
+        }
+
+        let value = if self.expiry {
+            Cow::Owned(wrap(value.as_bytes(), ttl))
+        } else {
+            Cow::Borrowed(value.as_bytes())
+        };
+
+        let inner = self.writer("put")?;
+
+        match inner.put(key.as_bytes(), value) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("SST writer cannot put. {}", e))),
+        }
+    }
+
+    /// Adds a merge of the "value" into the value of the "key", the value is appended like
+    /// `db.append` when the file is read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// writer.merge(b'events', b'started\n')
+    /// ```
+    fn merge(&mut self, key: BytesLike, value: BytesLike) -> PyResult<()> {
+        let operand = append_operand(value.as_bytes());
+        let inner = self.writer("merge")?;
+
+        match inner.merge(key.as_bytes(), operand) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("SST writer cannot merge. {}", e))),
+        }
+    }
+
+    /// Adds a deletion of the "key", it removes the key of the database which ingests the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// writer.delete(b'first')
+    /// ```
+    fn delete(&mut self, key: BytesLike) -> PyResult<()> {
+        let inner = self.writer("delete")?;
+
+        match inner.delete(key.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("SST writer cannot delete. {}", e))),
+        }
+    }
+
+    /// Completes the SST file, the writer can open another file afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// writer.finish()
+    ///
+    /// db.ingest_external_file(['/tmp/data.sst'])
+    /// ```
+    fn finish(&mut self, py: Python) -> PyResult<()> {
+        let inner = self.writer("finish")?;
+        let finished = py.allow_threads(|| inner.finish());
+
+        self.path = None;
+
+        match finished {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("SST writer cannot finish. {}", e))),
+        }
+    }
+
+    /// The size of the open file in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// size = writer.file_size
+    /// ```
+    #[getter]
+    fn file_size(&self) -> u64 {
+        self.inner.file_size()
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    /// Finishes the open file if the block is completed without an exception.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// with SstFileWriter() as writer:
+    ///     writer.open('/tmp/data.sst')
+    ///     writer.put(b'first', b'first_value')
+    /// ```
+    fn __exit__(
+        &mut self,
+        py: Python,
+        exc_type: Option<&PyAny>,
+        _exc_value: Option<&PyAny>,
+        _traceback: Option<&PyAny>,
+    ) -> PyResult<bool> {
+        if exc_type.is_none() && self.path.is_some() {
+            self.finish(py)?;
+        }
+
+        Ok(false)
+    }
+}
+
+impl SstFileWriterPy {
+    /// Returns the writer of the open file, `action` describes the call in the error.
+    fn writer(&mut self, action: &str) -> PyResult<&mut SstFileWriter<'static>> {
+        if self.path.is_none() {
+            return Err(RocksDBPyException::new_err(format!(
+                "SST writer cannot {}, file is not open",
+                action
+            )));
+        }
+
+        Ok(&mut self.inner)
+    }
+}
+
+/// Reads a standalone SST file, e.g. to validate the files of a bulk load before they are
+/// ingested. RocksDB has no reader of single files in its C API, so the file is copied into a
+/// scratch database in the temporary directory which is removed by `close`. The entries are
+/// read like a database reads them, so the deletions and merges of the file are applied.
+#[pyclass(name = "SstFileReader")]
+pub struct SstFileReaderPy {
+    path: PathBuf,
+    scratch: PathBuf,
+    db: Option<Arc<DB>>,
+    expiry: bool,
+}
+
+#[pymethods]
+impl SstFileReaderPy {
+    /// Opens the SST file with the options of the database which wrote it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// reader = SstFileReader('/tmp/data.sst')
+    ///
+    /// reader = SstFileReader('/tmp/data.sst', opts)
+    /// ```
+    #[new]
+    #[pyo3(signature = (path, option=None))]
+    fn new(py: Python, path: PathBuf, option: Option<OptionPy>) -> PyResult<Self> {
+        let (mut opts, key_ttl) = match option {
+            Some(option) => (option.inner, option.key_ttl),
+            None => (Options::default(), false),
+        };
+
+        opts.create_if_missing(true);
+        prepare(&mut opts, key_ttl);
+
+        let count = READERS.fetch_add(1, Ordering::Relaxed);
+        let scratch = env::temp_dir().join(format!("rocksdbpy-sst-{}-{}", process::id(), count));
+
+        let opened = py.allow_threads(|| {
+            let db = DB::open(&opts, &scratch)?;
+            let mut ingest = IngestExternalFileOptions::default();
+
+            ingest.set_move_files(false);
+            db.ingest_external_file_opts(&ingest, vec![&path])?;
+
+            Ok::<_, rocksdb::Error>(db)
+        });
+
+        match opened {
+            Ok(db) => Ok(SstFileReaderPy {
+                path,
+                scratch,
+                db: Some(Arc::new(db)),
+                expiry: key_ttl,
+            }),
+            Err(e) => {
+                let _ = fs::remove_dir_all(&scratch);
+
+                Err(rocksdb_error(&e, format!("SST file cannot be read {}. {}", path.display(), e)))
+            }
+        }
+    }
+
+    /// The path of the SST file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// path = reader.path
+    /// ```
+    #[getter]
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Returns the properties of the file, `num_entries` and `num_deletions` count the
+    /// records of the file, `size` is the file size in bytes and `smallest_key` and
+    /// `largest_key` are the bounds of its keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// properties = reader.properties()
+    ///
+    /// print(properties['num_entries'], properties['smallest_key'])
+    /// ```
+    fn properties<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let db = self.inner("Properties cannot get")?;
+
+        let files = match db.live_files() {
+            Ok(files) => files,
+            Err(e) => {
+                return Err(rocksdb_error(&e, format!("Properties cannot get. {}", e)))
+            }
+        };
+
+        let file = match files.first() {
+            Some(file) => file,
+            None => {
+                return Err(RocksDBPyException::new_err(
+                    "Properties cannot get, file is empty",
+                ))
+            }
+        };
+
+        let smallest_key = file.start_key.as_deref().map(|key| PyBytes::new(py, key));
+        let largest_key = file.end_key.as_deref().map(|key| PyBytes::new(py, key));
+
+        let properties = PyDict::new(py);
+
+        properties.set_item("num_entries", file.num_entries)?;
+        properties.set_item("num_deletions", file.num_deletions)?;
+        properties.set_item("size", file.size)?;
+        properties.set_item("smallest_key", smallest_key)?;
+        properties.set_item("largest_key", largest_key)?;
+
+        Ok(properties)
+    }
+
+    /// Return the value of the "key" in the file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// value = reader.get(b'first')
+    /// ```
+    fn get(&self, py: Python, key: BytesLike) -> PyResult<Option<PyObject>> {
+        let db = self.inner("Record cannot get")?;
+        let key = key.as_bytes();
+
+        match py.allow_threads(|| db.get(key)) {
+            Ok(Some(value)) => {
+                Ok(load(self.expiry, &value).map(|value| PyBytes::new(py, value).into()))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(rocksdb_error(&e, format!("Record cannot get. {}", e))),
+        }
+    }
+
+    /// Returns an iterator over the (key, value) entries of the file in the range, which
+    /// includes `start` and excludes `stop`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for key, value in reader.items():
+    ///     print(key, value)
+    ///
+    /// for key, value in reader.items(b'a', b'b', reverse=True):
+    ///     print(key, value)
+    /// ```
+    #[pyo3(signature = (start=None, stop=None, reverse=None))]
+    fn items(
+        &self,
+        start: Option<&PyBytes>,
+        stop: Option<&PyBytes>,
+        reverse: Option<bool>,
+    ) -> PyResult<IteratorPy> {
+        let db = self.inner("Iterator cannot get")?;
+
+        let seek = if reverse.unwrap_or(false) {
+            IteratorSeek::End
+        } else {
+            IteratorSeek::Start
+        };
+
+        let opts = IteratorOptions {
+            lower_bound: start.map(|b| b.as_bytes().to_vec()),
+            upper_bound: stop.map(|b| b.as_bytes().to_vec()),
+            expiry: self.expiry,
+            ..Default::default()
+        };
+
+        Ok(IteratorPy::new(db, seek, opts))
+    }
+
+    /// Reads every block of the file and raises an exception if a checksum does not match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// reader.verify_checksum()
+    /// ```
+    fn verify_checksum(&self, py: Python) -> PyResult<()> {
+        let db = self.inner("Checksum cannot verify")?;
+
+        let verified = py.allow_threads(|| {
+            let mut iterator = db.raw_iterator();
+
+            iterator.seek_to_first();
+
+            while iterator.valid() {
+                iterator.next();
+            }
+
+            iterator.status()
+        });
+
+        match verified {
+            Ok(()) => Ok(()),
+            Err(e) => Err(rocksdb_error(&e, format!("Checksum cannot verify. {}", e))),
+        }
+    }
+
+    /// Closes the reader and removes its scratch database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// reader.close()
+    /// ```
+    fn close(&mut self, py: Python) {
+        let db = self.db.take();
+        let scratch = &self.scratch;
+
+        py.allow_threads(|| {
+            drop(db);
+
+            let _ = fs::remove_dir_all(scratch);
+        });
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python,
+        _exc_type: Option<&PyAny>,
+        _exc_value: Option<&PyAny>,
+        _traceback: Option<&PyAny>,
+    ) {
+        self.close(py)
+    }
+}
+
+impl SstFileReaderPy {
+    /// Returns the scratch database, `action` describes the call in the error.
+    fn inner(&self, action: &str) -> PyResult<&Arc<DB>> {
+        match &self.db {
+            Some(db) => Ok(db),
+            None => Err(RocksDBPyException::new_err(format!(
+                "{}, SST file reader is closed",
+                action
+            ))),
+        }
+    }
+}
+
+impl Drop for SstFileReaderPy {
+    fn drop(&mut self) {
+        if self.db.take().is_some() {
+            let _ = fs::remove_dir_all(&self.scratch);
+        }
+    }
+}
+
+/// Writes sorted records into SST files of at most `SST_FILE_SIZE` bytes under a directory,
+/// so a bulk import is ingested without the memtable and the compactions.
+pub(crate) struct SstFiles<'a> {
+    opts: &'a Options,
+    dir: PathBuf,
+    writer: Option<SstFileWriter<'a>>,
+    files: Vec<PathBuf>,
+    count: u64,
+}
+
+impl<'a> SstFiles<'a> {
+    /// Writes the files into the directory, the directory must exist.
+    pub fn new(opts: &'a Options, dir: &Path) -> Self {
+        SstFiles {
+            opts,
+            dir: dir.to_path_buf(),
+            writer: None,
+            files: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Adds the record, the keys must be added in ascending order.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        if self.writer.is_none() {
+            let path = self.dir.join(format!("{:06}.sst", self.files.len()));
+            let writer = SstFileWriter::create(self.opts);
+
+            writer.open(&path)?;
+
+            self.files.push(path);
+            self.writer = Some(writer);
+        }
+
+        if let Some(writer) = &mut self.writer {
+            writer.put(key, value)?;
+            self.count += 1;
+
+            if writer.file_size() >= SST_FILE_SIZE {
+                writer.finish()?;
+                self.writer = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finishes the last file, returns the files and the number of the records.
+    pub fn finish(mut self) -> Result<(Vec<PathBuf>, u64), Error> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.finish()?;
+        }
+
+        Ok((self.files, self.count))
+    }
+}
@@ -0,0 +1,83 @@
+use crate::base::*;
+use pyo3::prelude::*;
+use rocksdb::{Options, ReadOptions};
+use std::cmp::Ordering;
+
+/// Size of the user timestamps, the timestamp is an unsigned 64-bit integer as little-endian
+/// like the builtin timestamp comparator of RocksDB.
+pub const TS_SIZE: usize = 8;
+
+/// Name of the comparator, RocksDB records it in the OPTIONS file of the database. It is the
+/// name of the builtin comparator, so other RocksDB tools can open the database.
+const NAME: &str = "leveldb.BytewiseComparator.u64ts";
+
+/// Sets the comparator which orders the keys bytewise and the timestamps of a key from the
+/// newest to the oldest.
+pub fn set_timestamp_comparator(opts: &mut Options) {
+    opts.set_comparator_with_ts(
+        NAME,
+        TS_SIZE,
+        Box::new(compare),
+        Box::new(compare_ts),
+        Box::new(compare_without_ts),
+    );
+}
+
+/// Encodes the timestamp of the records.
+pub fn encode_ts(ts: u64) -> [u8; TS_SIZE] {
+    ts.to_le_bytes()
+}
+
+/// Decodes the timestamp returned by RocksDB.
+pub fn decode_ts(ts: &[u8]) -> PyResult<u64> {
+    match <[u8; TS_SIZE]>::try_from(ts) {
+        Ok(ts) => Ok(u64::from_le_bytes(ts)),
+        Err(_) => Err(RocksDBPyException::new_err(format!(
+            "Timestamp cannot be decoded, it has {} bytes",
+            ts.len()
+        ))),
+    }
+}
+
+/// Returns the read options which see the records as of the timestamp.
+pub fn read_options_at(ts: u64) -> ReadOptions {
+    let mut opts = ReadOptions::default();
+
+    opts.set_timestamp(encode_ts(ts));
+
+    opts
+}
+
+fn compare(a: &[u8], b: &[u8]) -> Ordering {
+    // the newer records of a key come first
+    compare_without_ts(a, true, b, true).then_with(|| compare_ts(&suffix(a), &suffix(b)).reverse())
+}
+
+fn compare_ts(a: &[u8], b: &[u8]) -> Ordering {
+    u64::from_le_bytes(fixed(a)).cmp(&u64::from_le_bytes(fixed(b)))
+}
+
+fn compare_without_ts(a: &[u8], a_has_ts: bool, b: &[u8], b_has_ts: bool) -> Ordering {
+    strip(a, a_has_ts).cmp(strip(b, b_has_ts))
+}
+
+fn strip(key: &[u8], has_ts: bool) -> &[u8] {
+    if has_ts {
+        &key[..key.len().saturating_sub(TS_SIZE)]
+    } else {
+        key
+    }
+}
+
+fn suffix(key: &[u8]) -> [u8; TS_SIZE] {
+    fixed(&key[key.len().saturating_sub(TS_SIZE)..])
+}
+
+fn fixed(ts: &[u8]) -> [u8; TS_SIZE] {
+    let mut fixed = [0; TS_SIZE];
+    let n = ts.len().min(TS_SIZE);
+
+    fixed[..n].copy_from_slice(&ts[..n]);
+
+    fixed
+}
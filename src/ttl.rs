@@ -0,0 +1,80 @@
+use rocksdb::compaction_filter::Decision;
+use rocksdb::Options;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Size of the expiration header of the values, the header is the expiration time in seconds
+/// since the epoch as big-endian, zero means the value never expires.
+pub const HEADER: usize = 8;
+
+/// Name of the compaction filter, RocksDB records it in the OPTIONS file of the database.
+const NAME: &str = "rocksdbpy.ttl";
+
+/// Sets the compaction filter which drops the expired values.
+pub fn set_expiry_filter(opts: &mut Options) {
+    opts.set_compaction_filter(NAME, filter);
+}
+
+/// Prepends the expiration header to the value.
+pub fn wrap(value: &[u8], ttl: Option<u64>) -> Vec<u8> {
+    let expires = match ttl {
+        Some(ttl) => now().saturating_add(ttl),
+        None => 0,
+    };
+
+    let mut wrapped = Vec::with_capacity(HEADER + value.len());
+
+    wrapped.extend_from_slice(&expires.to_be_bytes());
+    wrapped.extend_from_slice(value);
+
+    wrapped
+}
+
+/// Returns the value without the expiration header, or `None` if the value is expired.
+pub fn unwrap(stored: &[u8]) -> Option<&[u8]> {
+    if expired(stored) {
+        None
+    } else {
+        Some(&stored[HEADER..])
+    }
+}
+
+/// Returns the value without the expiration header if `expiry` is true, otherwise returns
+/// the stored value as it is.
+#[inline]
+pub fn load(expiry: bool, stored: &[u8]) -> Option<&[u8]> {
+    if expiry {
+        unwrap(stored)
+    } else {
+        Some(stored)
+    }
+}
+
+/// Returns true if the value is expired. Values without a header are treated as expired,
+/// they are not written by the bindings.
+pub fn expired(stored: &[u8]) -> bool {
+    match stored.get(..HEADER) {
+        Some(header) => {
+            let expires = u64::from_be_bytes(header.try_into().unwrap());
+
+            expires != 0 && expires <= now()
+        }
+        None => true,
+    }
+}
+
+/// Returns the current time in seconds since the epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Drops the expired values on compaction.
+fn filter(_level: u32, _key: &[u8], value: &[u8]) -> Decision {
+    if expired(value) {
+        Decision::Remove
+    } else {
+        Decision::Keep
+    }
+}
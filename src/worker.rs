@@ -0,0 +1,62 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Background thread which runs a task periodically until it is stopped.
+pub struct Worker {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    /// Spawns a thread which calls `task` every `interval` until `stop` is called or the
+    /// task returns `false`.
+    pub fn spawn<F>(interval: Duration, mut task: F) -> Worker
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+
+        let handle = thread::spawn(move || {
+            // sleep in small steps, so stop requests are not delayed by long intervals
+            let step = Duration::from_millis(10).min(interval);
+
+            while !flag.load(Ordering::Relaxed) {
+                let mut slept = Duration::ZERO;
+
+                while slept < interval && !flag.load(Ordering::Relaxed) {
+                    thread::sleep(step);
+
+                    slept += step;
+                }
+
+                if flag.load(Ordering::Relaxed) || !task() {
+                    break;
+                }
+            }
+        });
+
+        Worker {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stops the thread and waits until the running task is completed.
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // do not wait here, the task may be waiting for the GIL held by the caller
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
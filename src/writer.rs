@@ -0,0 +1,237 @@
+use crate::base::*;
+use crate::db::*;
+use crate::lock::*;
+use crate::worker::*;
+use pyo3::prelude::*;
+use rocksdb::WriteBatch;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Writer which collects the writes in a batch and writes the batch when it reaches a
+/// threshold, when the interval elapses and when the writer is flushed or closed. The
+/// buffered writes are not visible to reads until they are written, and they are lost if
+/// the writer is not closed.
+#[pyclass(name = "BufferedWriter")]
+pub struct BufferedWriterPy {
+    db: Py<DBPy>,
+    buffer: Option<Arc<Mutex<Buffer>>>,
+    max_ops: Option<usize>,
+    max_bytes: Option<usize>,
+    flusher: Option<Worker>,
+}
+
+/// Pending writes, shared with the thread which flushes them by the interval.
+struct Buffer {
+    batch: WriteBatch,
+    db: Arc<DB>,
+    lock: WriteLock,
+    /// Error of the last flush by the interval, raised by the next call of the writer.
+    error: Option<String>,
+}
+
+impl Buffer {
+    /// Writes the pending writes, the caller holds the write lock.
+    fn write(&mut self) -> Result<(), String> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        let batch = std::mem::take(&mut self.batch);
+        let len = batch.len();
+
+        self.db
+            .write(batch)
+            .map_err(|e| format!("Batch cannot write {} elements. {}", len, e))
+    }
+}
+
+#[pymethods]
+impl BufferedWriterPy {
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    /// Writes the pending writes and closes the writer, the writes are kept even if the
+    /// block raised an exception.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// with db.buffered(max_ops=1000) as w:
+    ///     w.set(b'key', b'value')
+    /// ```
+    fn __exit__(
+        &mut self,
+        py: Python,
+        _exc_type: Option<&PyAny>,
+        _exc_value: Option<&PyAny>,
+        _traceback: Option<&PyAny>,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+
+        Ok(false)
+    }
+
+    /// Sets records by "key" and "value" in the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// w.set(b'key', b'value')
+    /// ```
+    fn set(&self, py: Python, key: &PyAny, value: &PyAny) -> PyResult<()> {
+        let db = self.db.borrow(py);
+        let key = db.key(key)?;
+        let value = db.encode(py, value)?;
+
+        self.push(py, |batch| batch.put(key.as_bytes(), value))
+    }
+
+    /// Removes existing records by "key" in the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// w.delete(b'key')
+    /// ```
+    fn delete(&self, py: Python, key: &PyAny) -> PyResult<()> {
+        let db = self.db.borrow(py);
+        let key = db.key(key)?;
+
+        self.push(py, |batch| batch.delete(key.as_bytes()))
+    }
+
+    /// Writes the pending writes to the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// w.flush()
+    /// ```
+    fn flush(&self, py: Python) -> PyResult<()> {
+        let mut buffer = lock_mutex(py, self.buffer()?);
+
+        flush(py, &mut buffer)
+    }
+
+    /// Writes the pending writes and stops the interval flushes, the writer cannot be used
+    /// after it is closed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// w.close()
+    /// ```
+    fn close(&mut self, py: Python) -> PyResult<()> {
+        if let Some(mut flusher) = self.flusher.take() {
+            // the running flush may wait for a write lock held by a thread which waits
+            // for the GIL
+            py.allow_threads(|| flusher.stop());
+        }
+
+        match self.buffer.take() {
+            Some(buffer) => flush(py, &mut lock_mutex(py, &buffer)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the number of pending writes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// n = len(w)
+    /// ```
+    fn __len__(&self, py: Python) -> PyResult<usize> {
+        Ok(lock_mutex(py, self.buffer()?).batch.len())
+    }
+}
+
+impl BufferedWriterPy {
+    /// Creates a writer of the database, the interval flushes run on a background thread.
+    pub fn new(
+        db: Py<DBPy>,
+        inner: Arc<DB>,
+        lock: WriteLock,
+        max_ops: Option<usize>,
+        max_bytes: Option<usize>,
+        flush_interval: Option<Duration>,
+    ) -> BufferedWriterPy {
+        let buffer = Arc::new(Mutex::new(Buffer {
+            batch: WriteBatch::default(),
+            db: inner,
+            lock,
+            error: None,
+        }));
+
+        let flusher = flush_interval.map(|interval| {
+            let buffer = buffer.clone();
+
+            Worker::spawn(interval, move || {
+                let mut buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+
+                // a failed flush is raised once before the next one is attempted
+                if buffer.error.is_none() {
+                    let lock = buffer.lock.clone();
+                    let _guard = lock.shared_blocking();
+
+                    buffer.error = buffer.write().err();
+                }
+
+                true
+            })
+        });
+
+        BufferedWriterPy {
+            db,
+            buffer: Some(buffer),
+            max_ops,
+            max_bytes,
+            flusher,
+        }
+    }
+
+    fn buffer(&self) -> PyResult<&Mutex<Buffer>> {
+        match &self.buffer {
+            Some(buffer) => Ok(buffer),
+            None => Err(RocksDBPyException::new_err(
+                "Writer cannot write, writer is closed",
+            )),
+        }
+    }
+
+    /// Adds a write to the batch and flushes the batch if it reached a threshold.
+    fn push(&self, py: Python, write: impl FnOnce(&mut WriteBatch)) -> PyResult<()> {
+        let mut buffer = lock_mutex(py, self.buffer()?);
+
+        if let Some(e) = buffer.error.take() {
+            return Err(RocksDBPyException::new_err(e));
+        }
+
+        write(&mut buffer.batch);
+
+        let full = self.max_ops.is_some_and(|n| buffer.batch.len() >= n)
+            || self
+                .max_bytes
+                .is_some_and(|n| buffer.batch.size_in_bytes() >= n);
+
+        if full {
+            flush(py, &mut buffer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the pending writes of the buffer, the error of an interval flush is raised first.
+fn flush(py: Python, buffer: &mut Buffer) -> PyResult<()> {
+    if let Some(e) = buffer.error.take() {
+        return Err(RocksDBPyException::new_err(e));
+    }
+
+    let lock = buffer.lock.clone();
+    let _guard = lock.shared(py);
+
+    py.allow_threads(|| buffer.write())
+        .map_err(RocksDBPyException::new_err)
+}
@@ -1,11 +1,54 @@
+from .aio import TestAsync
+from .backup import TestBackupEngine
 from .basic import TestBasic
 from .batch import TestBatch
+from .bench import TestBench
+from .changes import TestChanges
+from .cli import TestCli
+from .compaction import TestCompaction
+from .cursor import TestCursor
+from .dump import TestDump
+from .importer import TestImport
+from .indexed import TestWriteBatchWithIndex
 from .iterator import TestIterator
+from .key import TestKey
+from .namespace import TestNamespace
 from .option import TestOption
+from .pool import TestReadPool
+from .queue import TestQueue
+from .scan import TestParallelScan
+from .secondary import TestSecondary
+from .sst import TestSstFileReader, TestSstFileWriter
+from .timestamp import TestTimestamp
+from .ttl import TestTTL
+from .verify import TestVerify
+from .writer import TestBufferedWriter
 
 __all__ = [
+    'TestAsync',
+    'TestBackupEngine',
     'TestBasic',
     'TestBatch',
+    'TestBench',
+    'TestBufferedWriter',
+    'TestChanges',
+    'TestCli',
+    'TestCompaction',
+    'TestCursor',
+    'TestDump',
+    'TestImport',
     'TestIterator',
+    'TestKey',
+    'TestNamespace',
     'TestOption',
+    'TestParallelScan',
+    'TestQueue',
+    'TestReadPool',
+    'TestSecondary',
+    'TestSstFileReader',
+    'TestSstFileWriter',
+    'TestTTL',
+    'TestTimestamp',
+    'TestVerify',
+    'TestWriteBatchWithIndex',
 ]
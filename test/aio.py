@@ -0,0 +1,69 @@
+import asyncio
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestAsync(unittest.IsolatedAsyncioTestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.db = rocksdbpy.open_default(self.temp)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    async def test_get_set_delete(self):
+        await self.db.aset(b'test_key', b'test_value')
+
+        self.assertEqual(await self.db.aget(b'test_key'), b'test_value')
+
+        await self.db.adelete(b'test_key')
+
+        self.assertIsNone(await self.db.aget(b'test_key'))
+
+    async def test_multi_get(self):
+        await asyncio.gather(*(self.db.aset(f'test_{i}'.encode(), b'value') for i in range(10)))
+
+        values = await self.db.amulti_get([b'test_0', b'test_9', b'missing'], True)
+
+        self.assertEqual(values, [b'value', b'value', None])
+
+    async def test_write(self):
+        b = rocksdbpy.WriteBatch()
+
+        b.add(b'first', b'first_value')
+        b.add(b'second', b'second_value')
+
+        await self.db.awrite(b)
+
+        self.assertEqual(self.db.get(b'second'), b'second_value')
+
+    def test_without_loop(self):
+        with self.assertRaises(RuntimeError):
+            self.db.aget(b'test_key')
+
+    async def test_aiter(self):
+        for i in range(25):
+            self.db.set(f'test_{i:02}'.encode(), b'value')
+
+        entries = [entry async for entry in self.db.aiter(batch_size=10)]
+
+        self.assertEqual(len(entries), 25)
+        self.assertEqual(entries[0], (b'test_00', b'value'))
+        self.assertEqual(entries[-1], (b'test_24', b'value'))
+
+    async def test_aiter_range(self):
+        for i in range(5):
+            self.db.set(f'test_{i}'.encode(), b'value')
+
+        keys = [key async for key, _ in self.db.aiter(b'test_1', b'test_4', reverse=True, batch_size=2)]
+
+        self.assertEqual(keys, [b'test_3', b'test_2', b'test_1'])
+
+    async def test_aiter_empty(self):
+        entries = [entry async for entry in self.db.aiter()]
+
+        self.assertEqual(entries, [])
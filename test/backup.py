@@ -0,0 +1,210 @@
+import io
+import os
+import unittest
+import rocksdbpy
+import shutil
+import tarfile
+import tempfile
+
+
+class TestBackupEngine(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.backup = tempfile.mkdtemp()
+        self.restore = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+        self.engine = rocksdbpy.BackupEngine(self.backup)
+
+    def tearDown(self):
+        self.engine.close()
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+        shutil.rmtree(self.backup)
+        shutil.rmtree(self.restore)
+
+    def test_create_backup(self):
+        self.db.set(b'test_key', b'test_value1')
+
+        first = self.engine.create_backup(self.db)
+
+        self.db.set(b'test_key', b'test_value2')
+
+        second = self.engine.create_backup(self.db, flush_before_backup=False)
+
+        info = self.engine.get_backup_info()
+
+        self.assertEqual([backup['backup_id'] for backup in info], [first, second])
+        self.assertTrue(all(backup['size'] > 0 for backup in info))
+        self.assertTrue(all(backup['num_files'] > 0 for backup in info))
+        self.assertTrue(all(backup['timestamp'] > 0 for backup in info))
+
+        self.engine.verify_backup(first)
+        self.engine.verify_backup(second)
+
+    def test_progress(self):
+        for i in range(1000):
+            self.db.set(f'test_key{i}'.encode(), os.urandom(100))
+
+        calls = []
+
+        self.engine.create_backup(self.db, progress=lambda copied, total: calls.append((copied, total)))
+
+        self.assertGreater(len(calls), 0)
+        self.assertTrue(all(copied <= total for copied, total in calls))
+
+        copied, total = calls[-1]
+
+        self.assertEqual(copied, total)
+        self.assertGreater(total, 0)
+
+        # the second backup only copies the new table file
+        self.db.set(b'test_key', b'test_value')
+
+        calls.clear()
+
+        self.engine.create_backup(self.db, progress=lambda copied, total: calls.append((copied, total)))
+
+        self.assertLess(calls[-1][1], total)
+
+    def test_progress_error(self):
+        def progress(copied, total):
+            raise RuntimeError('progress')
+
+        with self.assertRaises(RuntimeError):
+            self.engine.create_backup(self.db, progress=progress)
+
+        self.assertEqual(len(self.engine.get_backup_info()), 1)
+
+    def test_purge_old_backups(self):
+        ids = []
+
+        for i in range(4):
+            self.db.set(b'test_key', str(i).encode())
+
+            ids.append(self.engine.create_backup(self.db))
+
+        self.engine.purge_old_backups(2)
+
+        self.assertEqual([info['backup_id'] for info in self.engine.get_backup_info()], ids[2:])
+
+        self.engine.verify_backup(ids[2])
+        self.engine.verify_backup(ids[3])
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.engine.verify_backup(ids[0])
+
+        self.engine.restore_from_backup(ids[2], self.restore)
+
+        db = rocksdbpy.open_default(self.restore)
+
+        self.assertEqual(db.get(b'test_key'), b'2')
+
+        db.close()
+
+    def test_verify_missing(self):
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.engine.verify_backup(1)
+
+    def test_restore_from_backup(self):
+        self.db.set(b'test_key', b'test_value1')
+
+        first = self.engine.create_backup(self.db)
+
+        self.db.set(b'test_key', b'test_value2')
+        self.engine.create_backup(self.db)
+
+        self.engine.restore_from_backup(first, self.restore)
+
+        db = rocksdbpy.open_default(self.restore)
+
+        self.assertEqual(db.get(b'test_key'), b'test_value1')
+
+        db.close()
+
+    def test_restore_from_latest_backup(self):
+        self.db.set(b'test_key', b'test_value1')
+        self.engine.create_backup(self.db)
+
+        self.db.set(b'test_key', b'test_value2')
+        self.engine.create_backup(self.db)
+
+        self.engine.restore_from_latest_backup(self.restore)
+
+        db = rocksdbpy.open_default(self.restore)
+
+        self.assertEqual(db.get(b'test_key'), b'test_value2')
+
+        db.close()
+
+    def test_keep_log_files(self):
+        self.db.set(b'test_key1', b'test_value')
+        self.engine.create_backup(self.db, flush_before_backup=False)
+
+        self.db.set(b'test_key2', b'test_value')
+        self.db.close()
+
+        for name in os.listdir(self.temp):
+            if name.endswith('.sst'):
+                os.remove(os.path.join(self.temp, name))
+
+        self.engine.restore_from_latest_backup(self.temp, keep_log_files=True)
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        self.assertEqual(self.db.get(b'test_key1'), b'test_value')
+        self.assertEqual(self.db.get(b'test_key2'), b'test_value')
+
+    def test_stream_checkpoint(self):
+        for i in range(100):
+            self.db.set(f'test_key{i}'.encode(), b'test_value')
+
+        stream = io.BytesIO()
+
+        self.db.stream_checkpoint(stream)
+
+        # the checkpoint next to the database is removed
+        self.assertFalse(any('.stream-' in name for name in os.listdir(os.path.dirname(self.temp))))
+
+        stream.seek(0)
+
+        shutil.rmtree(self.restore)
+
+        rocksdbpy.RocksDB.restore_from_stream(stream, self.restore)
+
+        db = rocksdbpy.open_default(self.restore)
+
+        self.assertEqual(db.get(b'test_key99'), b'test_value')
+
+        db.close()
+
+    def test_restore_from_stream_not_empty(self):
+        stream = io.BytesIO()
+
+        self.db.stream_checkpoint(stream)
+        stream.seek(0)
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            rocksdbpy.RocksDB.restore_from_stream(stream, self.temp)
+
+    def test_restore_from_stream_invalid(self):
+        stream = io.BytesIO()
+
+        with tarfile.open(fileobj=stream, mode='w') as tar:
+            info = tarfile.TarInfo('../escape')
+            tar.addfile(info, io.BytesIO())
+
+        stream.seek(0)
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            rocksdbpy.RocksDB.restore_from_stream(stream, os.path.join(self.restore, 'db'))
+
+        self.assertFalse(os.path.exists(os.path.join(self.restore, 'db')))
+
+    def test_closed(self):
+        with rocksdbpy.BackupEngine(self.backup) as engine:
+            engine.create_backup(self.db)
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            engine.create_backup(self.db)
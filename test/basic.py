@@ -1,7 +1,12 @@
+import array
+import os
+import pathlib
 import unittest
 import rocksdbpy
 import shutil
 import tempfile
+import threading
+import time
 
 
 class TestBasic(unittest.TestCase):
@@ -15,10 +20,165 @@ class TestBasic(unittest.TestCase):
 
         shutil.rmtree(self.temp)
 
+    def test_path_like(self):
+        path = pathlib.Path(self.temp) / 'path_like'
+
+        db = rocksdbpy.open_default(path)
+        db.set(b'test_path', b'test_value')
+        db.close()
+
+        db = rocksdbpy.open_for_readonly(path)
+
+        self.assertEqual(db.get(b'test_path'), b'test_value')
+
+        db.close()
+
+        rocksdbpy.destroy(path)
+
+    def test_properties(self):
+        self.assertEqual(self.db.path, self.temp)
+        self.assertTrue(self.db.is_open)
+        self.assertFalse(self.db.read_only)
+
+        db = rocksdbpy.open_for_readonly(self.temp)
+
+        self.assertTrue(db.read_only)
+
+        db.close()
+
+        self.assertFalse(db.is_open)
+
+    def test_open_for_read_only(self):
+        self.db.set(b'test_key', b'test_value')
+        self.db.flush()
+
+        # the database is not locked, so it can be opened while the writer is open
+        db = rocksdbpy.RocksDB.open_for_read_only(self.temp)
+
+        self.assertTrue(db.read_only)
+        self.assertEqual(db.get(b'test_key'), b'test_value')
+
+        db.close()
+
+    def test_open_for_read_only_log_file(self):
+        # the write is only in the write-ahead log
+        self.db.set(b'test_key', b'test_value')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            rocksdbpy.RocksDB.open_for_read_only(self.temp, error_if_log_file_exists=True)
+
+    def test_closed(self):
+        db = rocksdbpy.open_default(f'{self.temp}/closed')
+        db.close()
+
+        self.assertRaises(rocksdbpy.DBClosedError, db.get, b'key')
+        self.assertRaises(rocksdbpy.DBClosedError, db.set, b'key', b'value')
+        self.assertRaises(rocksdbpy.DBClosedError, db.multi_get, [b'key'])
+        self.assertRaises(rocksdbpy.DBClosedError, db.iterator)
+
+        # closed error is a database error as well
+        self.assertRaises(rocksdbpy.RocksDBException, db.flush)
+
+    def test_close_flush(self):
+        path = f'{self.temp}/graceful'
+
+        db = rocksdbpy.open_default(path)
+        db.set(b'key', b'value')
+        db.close(flush=True, wait=True)
+
+        self.assertRaises(rocksdbpy.DBClosedError, db.get, b'key')
+        self.assertIsNone(db.close(flush=True))
+
+        # the lock is released, the database opens again at once
+        db = rocksdbpy.open_default(path)
+
+        self.assertEqual(db.get(b'key'), b'value')
+        self.assertEqual(db.property('rocksdb.num-files-at-level0'), '1')
+
+        db.close()
+
+    def test_close_flush_column_family(self):
+        path = f'{self.temp}/graceful_cf'
+
+        db = rocksdbpy.open_default(path)
+        db.create_column_family('users')
+        db.set(b'key', b'value', cf='users')
+        db.close(flush=True)
+
+        # a read only database does not flush the replayed log, so the file is from the close
+        db = rocksdbpy.open_for_readonly(path)
+
+        self.assertEqual(db.property('rocksdb.num-files-at-level0', cf='users'), '1')
+        self.assertEqual(db.get(b'key', cf='users'), b'value')
+
+        db.close()
+
+    def test_close_iterator(self):
+        path = f'{self.temp}/iterated'
+
+        db = rocksdbpy.open_default(path)
+        db.set(b'key', b'value')
+
+        itr = db.iterator()
+        db.close()
+
+        # the iterator keeps the database and its lock
+        self.assertRaises(rocksdbpy.DBLockedError, rocksdbpy.open_default, path)
+
+        del itr
+
+        rocksdbpy.open_default(path).close()
+
+    def test_auto_flush(self):
+        self.db.auto_flush(interval=0.05, max_bytes=1, wal=True)
+        self.db.set(b'key', b'value')
+
+        deadline = time.monotonic() + 5
+
+        while self.db.property('rocksdb.num-files-at-level0') == '0' and time.monotonic() < deadline:
+            time.sleep(0.05)
+
+        self.assertEqual(self.db.property('rocksdb.num-files-at-level0'), '1')
+
+        self.db.auto_flush()
+
+        self.assertRaises(ValueError, self.db.auto_flush, 0)
+
+    def test_close_flush_read_only(self):
+        self.db.set(b'key', b'value')
+        self.db.flush()
+
+        db = rocksdbpy.open_for_readonly(self.temp)
+        db.close(flush=True, wait=True)
+
+        self.assertFalse(db.is_open)
+
     def test_set(self):
         # set new key and value
         self.db.set(b'test_set', b'test_value')
 
+    def test_column_family(self):
+        self.db.create_column_family('users')
+
+        self.db.set(b'test_cf', b'test_value', cf='users')
+
+        self.assertIsNone(self.db.get(b'test_cf'))
+        self.assertEqual(self.db.get(b'test_cf', cf='users'), b'test_value')
+
+        # the column families are opened with the database
+        self.db.close()
+        self.db = rocksdbpy.open_default(self.temp)
+
+        self.assertEqual(self.db.get(b'test_cf', cf='users'), b'test_value')
+
+        self.db.delete(b'test_cf', cf='users')
+
+        self.assertIsNone(self.db.get(b'test_cf', cf='users'))
+
+        self.db.drop_column_family('users')
+
+        self.assertRaises(rocksdbpy.RocksDBException, self.db.get, b'test_cf', 'users')
+
     def test_get(self):
         key = b'test_get'
         value = b'test_value'
@@ -33,6 +193,627 @@ class TestBasic(unittest.TestCase):
 
         self.assertIsNone(empty)
 
+    def test_buffer(self):
+        self.db.set(bytearray(b'test_buffer'), memoryview(b'test_value'))
+
+        self.assertEqual(self.db.get(b'test_buffer'), b'test_value')
+        self.assertEqual(self.db.get(memoryview(b'test_buffer')), b'test_value')
+
+        # numeric buffers are stored by their raw bytes
+        values = array.array('d', [1.0, 2.0])
+
+        self.db.set(b'test_array', values)
+
+        self.assertEqual(self.db.get(b'test_array'), values.tobytes())
+
+        self.db.delete(bytearray(b'test_buffer'))
+
+        self.assertIsNone(self.db.get(b'test_buffer'))
+
+        self.assertRaises(TypeError, self.db.set, 'test_buffer', b'test_value')
+
+    def test_codec(self):
+        db = rocksdbpy.open_default(f'{self.temp}/json', codec='json')
+        db.set(b'test_json', {'name': 'test', 'values': [1, 2]})
+
+        self.assertEqual(db.get(b'test_json'), {'name': 'test', 'values': [1, 2]})
+        self.assertEqual(db.get_pinned(b'test_json').tobytes(), b'{"name": "test", "values": [1, 2]}')
+        self.assertEqual(db.multi_get_dict([b'test_json', b'missing']), {b'test_json': {'name': 'test', 'values': [1, 2]}, b'missing': None})
+
+        db.close()
+
+        db = rocksdbpy.open_default(f'{self.temp}/pickle', codec='pickle')
+        db[b'test_pickle'] = {1, 2}
+
+        self.assertEqual(db[b'test_pickle'], {1, 2})
+
+        db.close()
+
+        db = rocksdbpy.open_default(f'{self.temp}/custom', codec=(lambda v: str(v).encode(), lambda v: int(v)))
+        db.put_many({b'first': 1, b'second': 2})
+
+        self.assertEqual(db.multi_get([b'first', b'second']), [1, 2])
+
+        db.close()
+
+        self.assertRaises(ValueError, rocksdbpy.open_default, f'{self.temp}/unknown', codec='unknown')
+
+    def test_codec_iterator(self):
+        db = rocksdbpy.open_default(f'{self.temp}/json_iterator', codec='json')
+        db.set(b'first', {'a': 1})
+
+        with db.write_batch() as b:
+            b.add(b'second', [1, 2])
+
+        self.assertEqual(db.get(b'second'), [1, 2])
+        self.assertEqual(list(db.iterator()), [(b'first', {'a': 1}), (b'second', [1, 2])])
+        self.assertEqual(list(db.items(b'second')), [(b'second', [1, 2])])
+        self.assertEqual(list(db.values()), [{'a': 1}, [1, 2]])
+        self.assertEqual(list(db.prefix_iterator(b'fir')), [(b'first', {'a': 1})])
+        self.assertEqual(db.iterator().next_batch(10), [(b'first', {'a': 1}), (b'second', [1, 2])])
+        self.assertEqual(sorted(db.parallel_scan(workers=2)), [(b'first', {'a': 1}), (b'second', [1, 2])])
+        self.assertEqual(list(db.namespace(b'fir').items()), [(b'st', {'a': 1})])
+
+        cursor = db.cursor()
+        cursor.seek_to_first()
+
+        self.assertEqual(cursor.value(), {'a': 1})
+
+        # raw values cannot be decoded by the codec
+        self.assertRaises(ValueError, db.iterator, pinned=True)
+
+        b = rocksdbpy.WriteBatch()
+        b.add(b'third', b'3')
+
+        self.assertRaises(rocksdbpy.RocksDBException, db.write, b)
+
+        b = rocksdbpy.WriteBatchWithIndex()
+        b.add(b'third', b'3')
+
+        self.assertRaises(rocksdbpy.RocksDBException, db.write, b)
+        self.assertIsNone(db.get(b'third'))
+
+        db.close()
+
+    def test_text(self):
+        db = rocksdbpy.open_default(f'{self.temp}/text', text=True)
+        db.set('test_text', 'value')
+
+        self.assertEqual(db.get('test_text'), 'value')
+        self.assertEqual(db.get(b'test_text'), 'value')
+        self.assertEqual(db['test_text'], 'value')
+        self.assertTrue('test_text' in db)
+        self.assertEqual(db.multi_get_dict(['test_text']), {'test_text': 'value'})
+
+        db.delete('test_text')
+
+        self.assertIsNone(db.get('test_text'))
+
+        # text mode accepts str keys with a codec as well
+        db.close()
+
+        db = rocksdbpy.open_default(f'{self.temp}/text_json', codec='json', text=True)
+        db['test_text'] = [1, 2]
+
+        self.assertEqual(db['test_text'], [1, 2])
+
+        db.close()
+
+    def test_text_iterator(self):
+        db = rocksdbpy.open_default(f'{self.temp}/text_iterator', text=True)
+        db.set('first', 'one')
+
+        with db.write_batch() as b:
+            b.add('second', 'two')
+            b.delete('first')
+
+        db.set('third', 'three')
+
+        self.assertEqual(list(db.iterator()), [('second', 'two'), ('third', 'three')])
+        self.assertEqual(list(db.iterator(mode='from', key='third')), [('third', 'three')])
+        self.assertEqual(list(db.items('second', 'third')), [('second', 'two')])
+        self.assertEqual(list(db.keys('t')), ['third'])
+        self.assertEqual(list(db.prefix_iterator('sec')), [('second', 'two')])
+        self.assertEqual(db.count('a', 'z'), 2)
+        self.assertEqual(sorted(db.parallel_scan('a')), [('second', 'two'), ('third', 'three')])
+
+        cursor = db.cursor()
+        cursor.seek('third')
+
+        self.assertEqual((cursor.key(), cursor.value()), ('third', 'three'))
+
+        db.close()
+
+    def test_pop(self):
+        self.db.set(b'test_pop', b'test_value')
+
+        self.assertEqual(self.db.pop(b'test_pop'), b'test_value')
+        self.assertIsNone(self.db.get(b'test_pop'))
+        self.assertIsNone(self.db.pop(b'test_pop'))
+        self.assertEqual(self.db.pop(b'test_pop', b'default'), b'default')
+
+    def test_pop_concurrent(self):
+        keys = [f'test_pop_{i}'.encode() for i in range(100)]
+
+        self.db.put_many([(key, b'value') for key in keys])
+
+        popped = []
+
+        def worker():
+            for key in keys:
+                if self.db.pop(key) is not None:
+                    popped.append(key)
+
+        threads = [threading.Thread(target=worker) for _ in range(4)]
+
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        # every key is popped exactly once
+        self.assertEqual(sorted(popped), sorted(keys))
+
+    def test_get_or_set(self):
+        self.assertEqual(self.db.get_or_set(b'test_default', b'first'), b'first')
+        self.assertEqual(self.db.get_or_set(b'test_default', b'second'), b'first')
+        self.assertEqual(self.db.setdefault(b'test_default', b'third'), b'first')
+
+        self.assertEqual(self.db.get(b'test_default'), b'first')
+
+    def test_get_or_set_concurrent(self):
+        results = []
+
+        def worker(value):
+            results.append(self.db.get_or_set(b'test_init', value))
+
+        threads = [threading.Thread(target=worker, args=(f'{i}'.encode(),)) for i in range(8)]
+
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        # every worker sees the value of the first one
+        self.assertEqual(len(set(results)), 1)
+        self.assertEqual(self.db.get(b'test_init'), results[0])
+
+    def test_compare_and_swap(self):
+        self.assertEqual(self.db.compare_and_swap(b'test_cas', None, b'first'), (True, None))
+        self.assertEqual(self.db.compare_and_swap(b'test_cas', None, b'second'), (False, b'first'))
+        self.assertEqual(self.db.compare_and_swap(b'test_cas', b'other', b'second'), (False, b'first'))
+        self.assertEqual(self.db.compare_and_swap(b'test_cas', b'first', b'second'), (True, b'first'))
+
+        self.assertEqual(self.db.get(b'test_cas'), b'second')
+
+        # none as the new value removes the key
+        self.assertEqual(self.db.compare_and_swap(b'test_cas', b'second', None), (True, b'second'))
+        self.assertIsNone(self.db.get(b'test_cas'))
+
+        self.db.create_column_family('users')
+
+        self.assertEqual(self.db.compare_and_swap(b'test_cas', None, b'first', cf='users'), (True, None))
+        self.assertEqual(self.db.get(b'test_cas', cf='users'), b'first')
+        self.assertIsNone(self.db.get(b'test_cas'))
+
+    def test_compare_and_swap_concurrent(self):
+        self.db.set(b'test_counter', b'0')
+
+        def worker():
+            for _ in range(50):
+                while True:
+                    current = self.db.get(b'test_counter')
+                    swapped, _ = self.db.compare_and_swap(b'test_counter', current, str(int(current) + 1).encode())
+
+                    if swapped:
+                        break
+
+        threads = [threading.Thread(target=worker) for _ in range(4)]
+
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        self.assertEqual(self.db.get(b'test_counter'), b'200')
+
+    def test_update(self):
+        self.assertEqual(self.db.update(b'test_update', lambda v: (v or b'') + b'a'), b'a')
+        self.assertEqual(self.db.update(b'test_update', lambda v: v + b'b'), b'ab')
+        self.assertEqual(self.db.get(b'test_update'), b'ab')
+
+        self.assertIsNone(self.db.update(b'test_update', lambda v: None))
+        self.assertIsNone(self.db.get(b'test_update'))
+
+        self.db.create_column_family('users')
+
+        self.assertEqual(self.db.update(b'test_update', lambda v: b'user', cf='users'), b'user')
+        self.assertEqual(self.db.get(b'test_update', cf='users'), b'user')
+        self.assertIsNone(self.db.get(b'test_update'))
+
+        # the function is not called if the ttl cannot be set
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.update(b'test_update', lambda v: self.fail(), ttl=1)
+
+    def test_update_concurrent(self):
+        self.db.set(b'test_counter', b'0')
+
+        def worker():
+            for _ in range(50):
+                self.db.update(b'test_counter', lambda v: str(int(v) + 1).encode())
+
+        threads = [threading.Thread(target=worker) for _ in range(4)]
+
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        self.assertEqual(self.db.get(b'test_counter'), b'200')
+
+    def test_concurrent_set_get(self):
+        def worker(n):
+            for i in range(100):
+                key = f'test_{n}_{i}'.encode()
+
+                self.db[key] = key
+                self.assertEqual(self.db.get(key), key)
+
+                del self.db[key]
+                self.assertFalse(key in self.db)
+
+        threads = [threading.Thread(target=worker, args=(n,)) for n in range(4)]
+
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        self.assertEqual(self.db.exact_len(), 0)
+
+    def test_clone_handle(self):
+        handle = self.db.clone_handle()
+
+        handle.set(b'test_key', b'test_value')
+
+        self.assertEqual(self.db.get(b'test_key'), b'test_value')
+
+        # the database stays open until every handle is closed
+        handle.close()
+
+        self.assertFalse(handle.is_open)
+        self.assertEqual(self.db.get(b'test_key'), b'test_value')
+
+        with self.assertRaises(rocksdbpy.DBClosedError):
+            handle.clone_handle()
+
+    def test_clone_handle_threads(self):
+        def worker(handle, n):
+            for i in range(100):
+                handle.incr(b'test_counter')
+
+            handle.close()
+
+        threads = [threading.Thread(target=worker, args=(self.db.clone_handle(), n)) for n in range(4)]
+
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        self.assertEqual(int.from_bytes(self.db.get(b'test_counter'), 'little', signed=True), 400)
+
+    @unittest.skipUnless(hasattr(os, 'fork'), 'requires os.fork')
+    def test_fork(self):
+        self.db.set(b'test_key', b'test_value')
+
+        pid = os.fork()
+
+        if pid == 0:
+            try:
+                self.db.get(b'test_key')
+
+                os._exit(1)
+            except rocksdbpy.DBForkedError:
+                # the inherited handle is released without closing the database
+                self.db.close()
+
+                os._exit(0)
+
+        _, status = os.waitpid(pid, 0)
+
+        self.assertEqual(os.waitstatus_to_exitcode(status), 0)
+        self.assertEqual(self.db.get(b'test_key'), b'test_value')
+
+    def test_locked(self):
+        with self.assertRaises(rocksdbpy.DBLockedError) as context:
+            rocksdbpy.open_default(self.temp)
+
+        self.assertTrue(context.exception.held_by_current_process)
+        self.assertEqual(context.exception.lock_path, os.path.join(self.temp, 'LOCK'))
+        self.assertEqual(context.exception.code, 5)
+
+        with self.assertRaises(rocksdbpy.DBLockedError):
+            rocksdbpy.open_default(self.temp, wait_for_lock=0.2)
+
+    def test_typed_exceptions(self):
+        path = os.path.join(self.temp, 'missing')
+
+        with self.assertRaises(rocksdbpy.DBInvalidArgumentError) as context:
+            rocksdbpy.open(path, rocksdbpy.Option())
+
+        self.assertIsInstance(context.exception, rocksdbpy.RocksDBException)
+        self.assertEqual(context.exception.code, 4)
+        self.assertEqual(context.exception.subcode, 0)
+
+        with self.assertRaises(rocksdbpy.DBIOError) as context:
+            rocksdbpy.open_for_readonly(path)
+
+        self.assertEqual(context.exception.code, 5)
+        self.assertTrue(issubclass(rocksdbpy.DBTryAgainError, rocksdbpy.DBBusyError))
+
+    def test_wait_for_lock(self):
+        self.db.set(b'test_key', b'test_value')
+
+        timer = threading.Timer(0.2, self.db.close)
+        timer.start()
+
+        db = rocksdbpy.open_default(self.temp, wait_for_lock=10)
+        timer.join()
+
+        self.assertEqual(db.get(b'test_key'), b'test_value')
+
+        db.close()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+    def test_entity(self):
+        self.db.put_entity(b'test_user1', {'name': b'Ada', b'email': bytearray(b'ada@example.com')})
+        self.db.put_entity(b'test_user2', {})
+        self.db.set(b'test_user3', b'test_value')
+
+        self.assertEqual(self.db.get_entity(b'test_user1'), {b'name': b'Ada', b'email': b'ada@example.com'})
+        self.assertEqual(self.db.get_entity(b'test_user2'), {})
+        self.assertEqual(self.db.get_entity(b'test_user3'), {b'': b'test_value'})
+        self.assertIsNone(self.db.get_entity(b'test_missing'))
+
+        entities = list(self.db.entities(b'test_user', b'test_user3'))
+
+        self.assertEqual(entities, [
+            (b'test_user1', {b'email': b'ada@example.com', b'name': b'Ada'}),
+            (b'test_user2', {}),
+        ])
+
+        self.assertEqual([key for key, _ in self.db.entities(b'test_user', b'test_userz', reverse=True)],
+                         [b'test_user3', b'test_user2', b'test_user1'])
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.put_entity(b'test_user4', {'name': b'Ada', b'name': b'Ada'})
+
+        with self.assertRaises(TypeError):
+            self.db.put_entity(b'test_user4', {'name': 'Ada'})
+
+    def test_entity_plain(self):
+        # a plain value which looks like a serialized entity is still a plain value
+        self.db.set(b'test_user1', b'\x00\xffentity\x01\x04\x00\x00\x00name')
+
+        self.assertEqual(self.db.get_entity(b'test_user1'), {b'': b'\x00\xffentity\x01\x04\x00\x00\x00name'})
+
+        # the entity replaces the plain value, and a newer plain value replaces the entity
+        self.db.put_entity(b'test_user1', {'name': b'Ada'})
+
+        self.assertIsNone(self.db.get(b'test_user1'))
+        self.assertEqual(self.db.get_entity(b'test_user1'), {b'name': b'Ada'})
+
+        self.db.set(b'test_user1', b'test_value')
+
+        self.assertEqual(self.db.get_entity(b'test_user1'), {b'': b'test_value'})
+        self.assertEqual(list(self.db.entities()), [(b'test_user1', {b'': b'test_value'})])
+
+        self.db.put_entity(b'test_user1', {'name': b'Ada'})
+        self.db.delete(b'test_user1')
+
+        self.assertIsNone(self.db.get_entity(b'test_user1'))
+        self.assertEqual(list(self.db.entities()), [])
+
+    def test_entity_delete(self):
+        self.db.put_entity(b'test_user1', {'name': b'Ada'})
+        self.db.put_entity(b'test_user2', {'name': b'Grace'})
+        self.db.put_entity(b'test_item1', {'name': b'Book'})
+
+        self.db.delete_many([b'test_user1'])
+
+        self.assertIsNone(self.db.get_entity(b'test_user1'))
+
+        self.db.delete_prefix(b'test_user')
+
+        self.assertIsNone(self.db.get_entity(b'test_user2'))
+        self.assertEqual(list(self.db.entities()), [(b'test_item1', {b'name': b'Book'})])
+
+        self.assertIsNone(self.db.pop(b'test_item1'))
+        self.assertEqual(list(self.db.entities()), [])
+
+    def test_comparator(self):
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_comparator('casefold', lambda a, b: (a.lower() > b.lower()) - (a.lower() < b.lower()))
+
+        path = os.path.join(self.temp, 'comparator')
+        db = rocksdbpy.open(path, opts)
+
+        for key in [b'b', b'A', b'C']:
+            db.set(key, b'test_value')
+
+        self.assertEqual([key for key, _ in db.iterator()], [b'A', b'b', b'C'])
+
+        db.close()
+
+        opts = rocksdbpy.Option()
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            rocksdbpy.open(path, opts)
+
+    def test_reverse_comparator(self):
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_reverse_comparator()
+
+        db = rocksdbpy.open(os.path.join(self.temp, 'reverse'), opts)
+
+        for ts in [1, 3, 2]:
+            db.set(ts.to_bytes(8, 'big') + b':event', b'test_value')
+
+        keys = [key for key, _ in db.iterator()]
+
+        self.assertEqual([int.from_bytes(key[:8], 'big') for key in keys], [3, 2, 1])
+
+        keys = [key for key, _ in db.iterator(lower_bound=(2).to_bytes(8, 'big') + b'\xff')]
+
+        self.assertEqual([int.from_bytes(key[:8], 'big') for key in keys], [2, 1])
+
+        db.close()
+
+    def test_composite_comparator(self):
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_composite_comparator('tenant', ['u16le', 'ascii_ci'])
+
+        db = rocksdbpy.open(os.path.join(self.temp, 'composite'), opts)
+
+        for key in [b'\x00\x01b', b'\x01\x00B', b'\x01\x00a', b'\x02\x00']:
+            db.set(key, b'test_value')
+
+        self.assertEqual([key for key, _ in db.iterator()], [b'\x01\x00a', b'\x01\x00B', b'\x02\x00', b'\x00\x01b'])
+
+        db.close()
+
+    def test_composite_comparator_short_keys(self):
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_composite_comparator('id', ['u32le', 'bytes'])
+
+        db = rocksdbpy.open(os.path.join(self.temp, 'composite'), opts)
+
+        keys = [b'\x00\x01\x00\x00', b'\x01', b'\x01\x00\x00\x00', b'', b'\x00\x01', b'\x01\x00\x00\x00a']
+
+        for key in keys:
+            db.set(key, b'test_value')
+
+        # the keys shorter than the integer come first in bytewise order, then the integers
+        self.assertEqual([key for key, _ in db.iterator()],
+                         [b'', b'\x00\x01', b'\x01', b'\x01\x00\x00\x00', b'\x01\x00\x00\x00a', b'\x00\x01\x00\x00'])
+        self.assertEqual([key for key, _ in db.iterator(mode='end')][::-1], [key for key, _ in db.iterator()])
+
+        db.close()
+
+    def test_blob_files(self):
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_enable_blob_files(True)
+        opts.set_min_blob_size(1024)
+        opts.set_enable_blob_gc(True)
+
+        path = os.path.join(self.temp, 'blob')
+        db = rocksdbpy.open(path, opts)
+
+        value = os.urandom(100 * 1024)
+
+        db.set(b'test_large', value)
+        db.set(b'test_small', b'test_value')
+        db.flush()
+
+        self.assertTrue(any(name.endswith('.blob') for name in os.listdir(path)))
+        self.assertEqual(db.get(b'test_large'), value)
+        self.assertEqual(db.get(b'test_small'), b'test_value')
+
+        db.close()
+
+    def test_checkpoint(self):
+        path = f'{self.temp}_checkpoint'
+
+        self.db.set(b'test_key', b'test_value')
+        self.db.create_checkpoint(path)
+
+        # the checkpoint does not see the writes after it is created
+        self.db.set(b'test_key', b'new_value')
+
+        db = rocksdbpy.open_default(path)
+
+        self.assertEqual(db.get(b'test_key'), b'test_value')
+
+        db.close()
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.create_checkpoint(path)
+
+        shutil.rmtree(path)
+
+    def test_incr(self):
+        self.assertEqual(self.db.incr(b'test_counter'), 1)
+        self.assertEqual(self.db.incr(b'test_counter', 10), 11)
+        self.assertEqual(self.db.decr(b'test_counter', 20), -9)
+
+        # counters are stored as 8 little-endian bytes
+        self.assertEqual(int.from_bytes(self.db.get(b'test_counter'), 'little', signed=True), -9)
+
+    def test_incr_not_counter(self):
+        self.db.set(b'test_counter', b'abc')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.incr(b'test_counter')
+
+        # the value is left as it is, so the key is still readable
+        self.assertEqual(self.db.get(b'test_counter'), b'abc')
+
+    def test_incr_concurrent(self):
+        def worker():
+            for _ in range(100):
+                self.db.incr(b'test_counter')
+
+        threads = [threading.Thread(target=worker) for _ in range(4)]
+
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        self.assertEqual(self.db.incr(b'test_counter', 0), 400)
+
+    def test_append(self):
+        self.db.append(b'test_append', b'first,')
+        self.db.append(b'test_append', bytearray(b'second,'))
+
+        self.assertEqual(self.db.get(b'test_append'), b'first,second,')
+
+        # values which are set by set can be appended as well
+        self.db.set(b'test_append', b'reset,')
+        self.db.append(b'test_append', b'third')
+
+        self.assertEqual(self.db.get(b'test_append'), b'reset,third')
+
+        self.db.flush()
+
+        self.assertEqual(self.db.get(b'test_append'), b'reset,third')
+
+    def test_get_pinned(self):
+        self.db.set(b'test_pinned', b'test_value')
+
+        value = self.db.get_pinned(b'test_pinned')
+
+        self.assertIsInstance(value, memoryview)
+        self.assertTrue(value.readonly)
+        self.assertEqual(value.tobytes(), b'test_value')
+        self.assertEqual(bytes(value[:4]), b'test')
+
+        self.assertIsNone(self.db.get_pinned(b'not_exist_key'))
+
     def test_multi_get(self):
         keys = [b'test_mget_1', b'test_mget_2']
         values = [b'test_value_1', b'test_value_2']
@@ -56,6 +837,84 @@ class TestBasic(unittest.TestCase):
 
         self.assertEqual(got, values)
 
+    def test_multi_get_many(self):
+        keys = [f'test_mget_{i:03}'.encode('ascii') for i in range(200)]
+
+        self.db.put_many([(k, k) for k in keys[::2]])
+
+        got = self.db.multi_get(list(reversed(keys)))
+
+        expected = [None if i % 2 else k for i, k in enumerate(keys)]
+
+        self.assertEqual(got, list(reversed(expected)))
+
+    def test_multi_get_dict(self):
+        self.db.set(b'test_mget_1', b'test_value_1')
+        self.db.set(b'test_mget_2', b'test_value_2')
+
+        got = self.db.multi_get_dict([b'test_mget_1', b'key_not_exist', b'test_mget_2'])
+
+        expected = {
+            b'test_mget_1': b'test_value_1',
+            b'key_not_exist': None,
+            b'test_mget_2': b'test_value_2',
+        }
+
+        self.assertEqual(got, expected)
+
+    def test_dict(self):
+        self.db[b'test_dict'] = b'test_value'
+
+        self.assertEqual(self.db[b'test_dict'], b'test_value')
+        self.assertIn(b'test_dict', self.db)
+        self.assertNotIn(b'not_exist_key', self.db)
+
+        with self.assertRaises(KeyError):
+            self.db[b'not_exist_key']
+
+        del self.db[b'test_dict']
+
+        self.assertNotIn(b'test_dict', self.db)
+
+    def test_len(self):
+        self.db.set(b'test_len_1', b'test_value')
+        self.db.set(b'test_len_2', b'test_value')
+
+        self.assertEqual(self.db.exact_len(), 2)
+        self.assertGreaterEqual(len(self.db), 0)
+
+    def test_disk_usage(self):
+        self.assertEqual(self.db.disk_usage()['files'], 0)
+
+        self.db.set(b'test_disk_usage', b'test_value')
+        self.db.flush()
+
+        usage = self.db.disk_usage()
+
+        self.assertEqual(usage['files'], 1)
+        self.assertGreater(usage['size'], 0)
+        self.assertEqual(usage['column_families']['default']['size'], usage['size'])
+
+    def test_stall_info(self):
+        info = self.db.stall_info()
+
+        self.assertFalse(info['stopped'])
+        self.assertFalse(info['stalled'])
+        self.assertEqual(info['delayed_write_rate'], 0)
+
+    def test_on_write_stall(self):
+        calls = []
+
+        with self.assertRaises(ValueError):
+            self.db.on_write_stall(calls.append, 0)
+
+        self.assertIsNone(self.db.on_write_stall(calls.append, 0.01))
+
+        self.db.set(b'test_stall', b'test_value')
+
+        self.assertIsNone(self.db.on_write_stall(None))
+        self.assertEqual(calls, [])
+
     def remove(self):
         self.db.set(b'test_remove', b'test_value')
 
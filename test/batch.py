@@ -38,6 +38,94 @@ class TestBatch(unittest.TestCase):
 
         self.assertEqual(wb.len(), 0)
 
+    def test_size(self):
+        wb = WriteBatch()
+
+        empty = wb.size_in_bytes()
+
+        wb.add(b'test_add_1', b'test_value')
+
+        self.assertEqual(len(wb), 1)
+        self.assertGreater(wb.size_in_bytes(), empty)
+
+    def test_savepoint(self):
+        wb = WriteBatch()
+
+        wb.add(b'test_add_1', b'test_value')
+        wb.set_savepoint()
+        wb.add(b'test_add_2', b'test_value')
+        wb.set_savepoint()
+        wb.delete(b'test_add_1')
+
+        wb.rollback_to_savepoint()
+        self.assertEqual(wb.len(), 2)
+
+        wb.rollback_to_savepoint()
+        self.assertEqual(wb.len(), 1)
+
+        self.assertRaises(rocksdbpy.RocksDBException, wb.rollback_to_savepoint)
+
+        self.db.write(wb)
+
+        self.assertEqual(self.db.get(b'test_add_1'), b'test_value')
+        self.assertIsNone(self.db.get(b'test_add_2'))
+
+    def test_data(self):
+        wb = WriteBatch()
+
+        wb.add(b'test_add_1', b'test_value')
+        wb.delete(b'test_add_2')
+
+        copy = WriteBatch.from_data(wb.data())
+
+        self.assertEqual(copy.len(), 2)
+        self.assertEqual(copy.entries(), wb.entries())
+
+        self.db.write(copy)
+
+        self.assertEqual(self.db.get(b'test_add_1'), b'test_value')
+        self.assertRaises(rocksdbpy.RocksDBException, WriteBatch.from_data, b'invalid')
+
+    def test_entries(self):
+        wb = WriteBatch()
+
+        wb.add(b'test_add_1', b'test_value')
+        wb.delete(b'test_add_2')
+        wb.delete_range(b'a', b'b')
+        wb.merge(b'test_merge', b'first')
+
+        entries = list(wb)
+
+        self.assertEqual(entries[0], ('put', 0, b'test_add_1', b'test_value'))
+        self.assertEqual(entries[1], ('delete', 0, b'test_add_2', None))
+        self.assertEqual(entries[2], ('delete_range', 0, b'a', b'b'))
+        self.assertEqual(entries[3][:3], ('merge', 0, b'test_merge'))
+        self.assertEqual(wb.entries(), entries)
+
+        self.db.create_column_family('users')
+
+        with self.db.write_batch() as wb:
+            wb.add(b'test_add_1', b'test_value', cf='users')
+
+            op, cf, key, value = wb.entries()[0]
+
+            self.assertEqual((op, key, value), ('put', b'test_add_1', b'test_value'))
+            self.assertNotEqual(cf, 0)
+
+    def test_reuse(self):
+        wb = WriteBatch()
+
+        wb.add(b'test_add_1', b'test_value')
+        self.db.write(wb)
+
+        self.assertRaises(rocksdbpy.RocksDBException, wb.add, b'test_add_2', b'test_value')
+
+        wb.clear()
+        wb.add(b'test_add_2', b'test_value')
+        self.db.write(wb)
+
+        self.assertEqual(self.db.get(b'test_add_2'), b'test_value')
+
     def test_write(self):
         self.assertIsNone(self.db.get(b'test_add_1'))
 
@@ -52,3 +140,117 @@ class TestBatch(unittest.TestCase):
         self.db.write(wb)
 
         self.assertEqual(self.db.get(b'test_add_1'), b'test_value')
+
+    def test_write_options(self):
+        wb = WriteBatch()
+        wb.add(b'test_add_1', b'test_value')
+
+        self.db.write(wb, sync=True)
+
+        wb.clear()
+        wb.add(b'test_add_2', b'test_value')
+
+        self.db.write(wb, disable_wal=True, no_slowdown=True)
+
+        self.assertEqual(self.db.get(b'test_add_1'), b'test_value')
+        self.assertEqual(self.db.get(b'test_add_2'), b'test_value')
+
+    def test_context(self):
+        with self.db.write_batch() as wb:
+            wb.add(b'test_add_1', b'test_value')
+            wb.add(b'test_add_2', b'test_value')
+
+            # nothing is written before the block is completed
+            self.assertIsNone(self.db.get(b'test_add_1'))
+
+        self.assertEqual(self.db.get(b'test_add_1'), b'test_value')
+
+    def test_context_error(self):
+        with self.assertRaises(ValueError):
+            with self.db.write_batch() as wb:
+                wb.add(b'test_add_1', b'test_value')
+
+                raise ValueError()
+
+        self.assertIsNone(self.db.get(b'test_add_1'))
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            with WriteBatch():
+                pass
+
+    def test_delete_range(self):
+        self.db.put_many({b'a1': b'test_value', b'a2': b'test_value', b'b1': b'test_value'})
+
+        with self.db.write_batch() as wb:
+            wb.delete_range(b'a', b'b')
+
+        self.assertIsNone(self.db.get(b'a1'))
+        self.assertIsNone(self.db.get(b'a2'))
+        self.assertEqual(self.db.get(b'b1'), b'test_value')
+
+    def test_merge(self):
+        with self.db.write_batch() as wb:
+            wb.merge(b'test_merge', b'first,')
+            wb.merge(b'test_merge', b'second')
+
+        self.assertEqual(self.db.get(b'test_merge'), b'first,second')
+
+    def test_column_family(self):
+        self.db.create_column_family('users')
+
+        with self.db.write_batch() as wb:
+            wb.add(b'test_add_1', b'test_value', cf='users')
+            wb.merge(b'test_merge', b'first', cf='users')
+
+        self.assertIsNone(self.db.get(b'test_add_1'))
+        self.assertEqual(self.db.get(b'test_add_1', cf='users'), b'test_value')
+        self.assertEqual(self.db.get(b'test_merge', cf='users'), b'first')
+
+        with self.db.write_batch() as wb:
+            wb.delete(b'test_add_1', cf='users')
+
+        self.assertIsNone(self.db.get(b'test_add_1', cf='users'))
+
+        self.assertRaises(rocksdbpy.RocksDBException, WriteBatch().add, b'key', b'value', None, 'users')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            with self.db.write_batch() as wb:
+                wb.add(b'key', b'value', cf='missing')
+
+    def test_put_many(self):
+        self.db.put_many({b'test_add_1': b'test_value_1', b'test_add_2': b'test_value_2'})
+
+        self.assertEqual(self.db.get(b'test_add_1'), b'test_value_1')
+        self.assertEqual(self.db.get(b'test_add_2'), b'test_value_2')
+
+        self.db.put_many([(b'test_add_3', b'test_value_3')])
+
+        self.assertEqual(self.db.get(b'test_add_3'), b'test_value_3')
+
+        self.assertRaises(TypeError, self.db.put_many, [(b'test_add_4', 'test_value')])
+
+        self.assertIsNone(self.db.get(b'test_add_4'))
+
+    def test_delete_many(self):
+        self.db.put_many({b'test_add_1': b'test_value', b'test_add_2': b'test_value'})
+
+        self.db.delete_many([b'test_add_1', b'test_add_2'])
+
+        self.assertIsNone(self.db.get(b'test_add_1'))
+        self.assertIsNone(self.db.get(b'test_add_2'))
+
+    def test_delete_prefix(self):
+        self.db.put_many({b'test_add_1': b'test_value', b'test_add_2': b'test_value', b'test_b': b'test_value'})
+
+        self.db.delete_prefix(b'test_add_')
+
+        self.assertIsNone(self.db.get(b'test_add_1'))
+        self.assertIsNone(self.db.get(b'test_add_2'))
+        self.assertEqual(self.db.get(b'test_b'), b'test_value')
+
+        self.db.put_many({b'\xff\xff1': b'test_value', b'\xff\xff2': b'test_value'})
+
+        self.db.delete_prefix(b'\xff\xff')
+
+        self.assertIsNone(self.db.get(b'\xff\xff1'))
+        self.assertEqual(self.db.get(b'test_b'), b'test_value')
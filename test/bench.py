@@ -0,0 +1,55 @@
+import contextlib
+import io
+import os
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+from rocksdbpy import bench
+
+
+class TestBench(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.path = os.path.join(self.temp, 'bench')
+
+    def tearDown(self):
+        shutil.rmtree(self.temp)
+
+    def test_run(self):
+        results = bench.run(self.path, num=200, key_size=8, value_size=10, threads=2)
+
+        self.assertEqual([result['name'] for result in results], list(bench.BENCHMARKS))
+        self.assertTrue(all(result['ops'] == 200 for result in results))
+        self.assertEqual(results[2]['found'], 200)
+        self.assertEqual(results[3]['found'], 200)
+        self.assertTrue(all(result['ops_per_sec'] > 0 for result in results))
+        self.assertTrue(all(result['p50'] <= result['p99'] <= result['p99.9'] for result in results))
+        self.assertFalse(os.path.exists(self.path))
+
+    def test_options(self):
+        opts = rocksdbpy.Option()
+        opts.set_max_write_buffer_number(4)
+
+        results = bench.run(self.path, ['fillrandom', 'readrandom'], num=100, opts=opts)
+
+        self.assertEqual([result['name'] for result in results], ['fillrandom', 'readrandom'])
+        self.assertLessEqual(results[1]['found'], 100)
+
+    def test_unknown(self):
+        db = rocksdbpy.open_default(self.path)
+
+        with self.assertRaises(ValueError):
+            bench.run_benchmark(db, 'overwrite')
+
+        db.close()
+
+    def test_main(self):
+        out = io.StringIO()
+
+        with contextlib.redirect_stdout(out):
+            code = bench.main([self.path, '--benchmarks', 'fillseq,readseq', '--num', '50'])
+
+        self.assertEqual(code, 0)
+        self.assertIn('fillseq', out.getvalue())
+        self.assertIn('(50 of 50 found)', out.getvalue())
@@ -0,0 +1,102 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestChanges(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_wal_ttl_seconds(3600)
+
+        self.db = rocksdbpy.open(self.temp, opts)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_changes(self):
+        self.db.set(b'old', b'value')
+
+        seq = self.db.latest_sequence_number()
+
+        self.db.set(b'b', b'first')
+        self.db.set(b'a', b'value')
+        self.db.set(b'b', b'second')
+        self.db.set(b'c', b'value')
+        self.db.delete(b'c')
+        self.db.delete(b'old')
+
+        result = self.db.changes_since(seq)
+
+        self.assertEqual(result['sequence'], self.db.latest_sequence_number())
+        self.assertEqual(result['changes'], [(b'a', b'value'), (b'b', b'second'), (b'c', None), (b'old', None)])
+        self.assertEqual(result['deleted_ranges'], [])
+
+    def test_no_changes(self):
+        self.db.set(b'a', b'value')
+
+        seq = self.db.latest_sequence_number()
+        result = self.db.changes_since(seq)
+
+        self.assertEqual(result['sequence'], seq)
+        self.assertEqual(result['changes'], [])
+
+    def test_incremental(self):
+        seq = self.db.latest_sequence_number()
+
+        self.db.set(b'a', b'1')
+
+        result = self.db.changes_since(seq)
+
+        self.assertEqual(result['changes'], [(b'a', b'1')])
+
+        self.db.set(b'b', b'2')
+
+        # only the writes after the previous sync are returned
+        result = self.db.changes_since(result['sequence'])
+
+        self.assertEqual(result['changes'], [(b'b', b'2')])
+
+    def test_batch(self):
+        seq = self.db.latest_sequence_number()
+
+        batch = rocksdbpy.WriteBatch()
+        batch.add(b'a', b'1')
+        batch.add(b'b', b'2')
+        batch.delete_range(b'x', b'z')
+
+        self.db.write(batch)
+        self.db.flush()
+
+        result = self.db.changes_since(seq)
+
+        self.assertEqual(result['changes'], [(b'a', b'1'), (b'b', b'2')])
+        self.assertEqual(result['deleted_ranges'], [(b'x', b'z')])
+
+    def test_decoded(self):
+        self.db.close()
+
+        opts = rocksdbpy.Option()
+        opts.set_wal_ttl_seconds(3600)
+
+        self.db = rocksdbpy.open(self.temp, opts, codec='json', text=True)
+
+        seq = self.db.latest_sequence_number()
+
+        self.db.set('a', {'n': 1})
+        self.db.delete('b')
+
+        result = self.db.changes_since(seq)
+
+        # the keys and the values have the types of get
+        self.assertEqual(result['changes'], [('a', {'n': 1}), ('b', None)])
+
+    def test_closed(self):
+        self.db.close()
+
+        self.assertRaises(rocksdbpy.DBClosedError, self.db.changes_since, 0)
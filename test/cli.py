@@ -0,0 +1,81 @@
+import contextlib
+import io
+import os
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+from rocksdbpy.__main__ import main
+
+
+class TestCli(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.path = os.path.join(self.temp, 'db')
+
+        db = rocksdbpy.open_default(self.path)
+        db.set(b'a', b'first')
+        db.set(b'b', b'second')
+        db.set(b'c', b'\xff')
+        db.create_column_family('users')
+        db.close()
+
+    def tearDown(self):
+        shutil.rmtree(self.temp)
+
+    def run_cli(self, *argv):
+        out = io.StringIO()
+
+        with contextlib.redirect_stdout(out), contextlib.redirect_stderr(io.StringIO()):
+            code = main([self.path, *argv])
+
+        return code, out.getvalue().splitlines()
+
+    def test_scan(self):
+        self.assertEqual(self.run_cli('scan'), (0, ['a ==> first', 'b ==> second', 'c ==> \\xff']))
+        self.assertEqual(self.run_cli('scan', '--start', 'b', '--limit', '1'), (0, ['b ==> second']))
+        self.assertEqual(self.run_cli('scan', '--stop', 'c', '--keys-only'), (0, ['a', 'b']))
+        self.assertEqual(self.run_cli('--hex', 'scan', '--start', '63'), (0, ['63 ==> ff']))
+
+    def test_get_put_delete(self):
+        self.assertEqual(self.run_cli('get', 'a'), (0, ['first']))
+        self.assertEqual(self.run_cli('put', 'd', 'fourth'), (0, []))
+        self.assertEqual(self.run_cli('get', 'd'), (0, ['fourth']))
+        self.assertEqual(self.run_cli('delete', 'd'), (0, []))
+        self.assertEqual(self.run_cli('get', 'd'), (1, []))
+        self.assertEqual(self.run_cli('put', 'e', 'fifth', '--cf', 'users'), (0, []))
+        self.assertEqual(self.run_cli('get', 'e', '--cf', 'users'), (0, ['fifth']))
+
+    def test_list_cf(self):
+        self.assertEqual(self.run_cli('list_cf'), (0, ['default', 'users']))
+
+    def test_properties(self):
+        code, lines = self.run_cli('properties', 'rocksdb.estimate-num-keys', 'rocksdb.unknown')
+
+        self.assertEqual(code, 0)
+        self.assertEqual(lines, ['rocksdb.estimate-num-keys: 3', 'rocksdb.unknown: not supported'])
+
+    def test_compact(self):
+        self.assertEqual(self.run_cli('compact', '--start', 'a', '--stop', 'c'), (0, []))
+        self.assertEqual(self.run_cli('get', 'b'), (0, ['second']))
+
+    def test_backup_restore(self):
+        backup = os.path.join(self.temp, 'backup')
+        restore = os.path.join(self.temp, 'restore')
+
+        self.assertEqual(self.run_cli('backup', backup), (0, ['1']))
+
+        self.path = restore
+
+        self.assertEqual(self.run_cli('restore', backup), (0, []))
+
+        db = rocksdbpy.open_default(restore)
+
+        self.assertEqual(db.get(b'a'), b'first')
+
+        db.close()
+
+    def test_missing(self):
+        self.path = os.path.join(self.temp, 'missing')
+
+        self.assertEqual(self.run_cli('scan'), (1, []))
@@ -0,0 +1,67 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestCompaction(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        # several SST files give the compaction chunks
+        for part in range(4):
+            self.db.put_many({b'key_%05d' % i: b'value_%d' % i for i in range(part * 2500, (part + 1) * 2500)})
+            self.db.flush()
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_compact(self):
+        calls = []
+
+        compaction = self.db.compact_range_async(progress=lambda done, total: calls.append((done, total)))
+
+        self.assertTrue(compaction.wait(timeout=30))
+        self.assertTrue(compaction.done())
+        self.assertFalse(compaction.canceled)
+
+        done, total = compaction.progress()
+
+        self.assertEqual(done, total)
+        self.assertEqual(calls[-1], (total, total))
+        self.assertEqual(self.db.get(b'key_05000'), b'value_5000')
+        self.assertEqual(self.db.count(), 10000)
+
+    def test_range(self):
+        compaction = self.db.compact_range_async(b'key_01000', b'key_09000')
+
+        self.assertTrue(compaction.wait(timeout=30))
+        self.assertEqual(self.db.get(b'key_01000'), b'value_1000')
+
+    def test_cancel(self):
+        compaction = self.db.compact_range_async()
+        compaction.cancel()
+
+        self.assertTrue(compaction.wait(timeout=30))
+        self.assertTrue(compaction.canceled)
+
+        done, total = compaction.progress()
+
+        self.assertLessEqual(done, total)
+
+    def test_column_family(self):
+        self.db.create_column_family('users')
+
+        compaction = self.db.compact_range_async(cf='users')
+
+        self.assertTrue(compaction.wait(timeout=30))
+        self.assertRaises(rocksdbpy.RocksDBException, self.db.compact_range_async, cf='missing')
+
+    def test_closed(self):
+        self.db.close()
+
+        self.assertRaises(rocksdbpy.DBClosedError, self.db.compact_range_async)
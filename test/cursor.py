@@ -0,0 +1,78 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+from rocksdbpy import WriteBatch
+
+
+class TestCursor(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        wb = WriteBatch()
+
+        # add couple of keys and values
+        wb.add(b'test_add_1', b'test_value_1')
+        wb.add(b'test_add_2', b'test_value_2')
+        wb.add(b'test_add_3', b'test_value_3')
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        self.db.write(wb)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_seek(self):
+        cursor = self.db.cursor()
+
+        cursor.seek(b'test_add_2')
+
+        self.assertTrue(cursor.valid())
+        self.assertEqual(b'test_add_2', cursor.key())
+        self.assertEqual(b'test_value_2', cursor.value())
+
+        # the same cursor can be repositioned
+        cursor.seek_for_prev(b'test_add_25')
+
+        self.assertEqual(b'test_add_2', cursor.key())
+
+        cursor.seek(b'test_add_4')
+
+        self.assertFalse(cursor.valid())
+        self.assertIsNone(cursor.key())
+
+    def test_next_prev(self):
+        cursor = self.db.cursor()
+
+        cursor.seek_to_first()
+
+        keys = []
+
+        while cursor.valid():
+            keys.append(cursor.key())
+
+            cursor.next()
+
+        self.assertEqual([b'test_add_1', b'test_add_2', b'test_add_3'], keys)
+
+        cursor.seek_to_last()
+        cursor.prev()
+
+        self.assertEqual(b'test_add_2', cursor.key())
+
+    def test_bounds(self):
+        cursor = self.db.cursor(upper_bound=b'test_add_2')
+
+        cursor.seek_to_last()
+
+        self.assertEqual(b'test_add_1', cursor.key())
+
+    def test_close(self):
+        cursor = self.db.cursor()
+
+        cursor.close()
+
+        self.assertRaises(rocksdbpy.RocksDBException, cursor.seek_to_first)
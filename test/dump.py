@@ -0,0 +1,91 @@
+import base64
+import io
+import json
+import os
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestDump(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(os.path.join(self.temp, 'source'))
+        self.target = rocksdbpy.open_default(os.path.join(self.temp, 'target'))
+
+        self.records = [(b'a', b''), (b'b', b'\x00\xff' * 20), (b'c', b'x' * 70000)]
+
+        for key, value in self.records:
+            self.db.set(key, value)
+
+    def tearDown(self):
+        self.db.close()
+        self.target.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_jsonl(self):
+        path = os.path.join(self.temp, 'dump.jsonl')
+
+        self.assertEqual(self.db.dump(path), 3)
+
+        with open(path) as f:
+            lines = [json.loads(line) for line in f]
+
+        self.assertEqual([(base64.b64decode(line['key']), base64.b64decode(line['value'])) for line in lines],
+                         self.records)
+
+        self.assertEqual(self.target.load(path), 3)
+        self.assertEqual(list(self.target.iterator()), self.records)
+
+    def test_cbor(self):
+        f = io.BytesIO()
+
+        self.assertEqual(self.db.dump(f, format='cbor'), 3)
+
+        self.assertEqual(f.getvalue()[:3], b'\x82\x41a')
+
+        f.seek(0)
+
+        self.assertEqual(self.target.load(f, format='cbor'), 3)
+        self.assertEqual(list(self.target.iterator()), self.records)
+
+    def test_range(self):
+        f = io.BytesIO()
+
+        self.assertEqual(self.db.dump(f, start=b'b', stop=b'c'), 1)
+
+        f.seek(0)
+
+        self.target.create_column_family('users')
+
+        self.assertEqual(self.target.load(f, cf='users'), 1)
+        self.assertEqual(self.target.get(b'b', cf='users'), self.records[1][1])
+        self.assertIsNone(self.target.get(b'b'))
+
+    def test_invalid(self):
+        with self.assertRaises(ValueError):
+            self.db.dump(io.BytesIO(), format='xml')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.target.load(io.BytesIO(b'{"key": "YQ=="}\n'))
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.target.load(io.BytesIO(b'\x82\x41a\x45abc'), format='cbor')
+
+        # the length of a corrupt byte string is not allocated up front
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.target.load(io.BytesIO(b'\x82\x41a\x5b\xff\xff\xff\xff\xff\xff\xff\xffabc'), format='cbor')
+
+    def test_text_range(self):
+        db = rocksdbpy.open_default(os.path.join(self.temp, 'text'), text=True)
+        db.set('a', b'1')
+        db.set('b', b'2')
+
+        f = io.BytesIO()
+
+        self.assertEqual(db.dump(f, start='b', stop=bytearray(b'c')), 1)
+
+        db.close()
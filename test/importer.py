@@ -0,0 +1,119 @@
+import os
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestImport(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(os.path.join(self.temp, 'target'))
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_ingest_records(self):
+        self.db.set(b'b', b'old')
+
+        records = ((b'%03d' % i, b'value%d' % i) for i in range(1000))
+
+        self.assertEqual(self.db.ingest_records(records), 1000)
+        self.assertEqual(self.db.get(b'042'), b'value42')
+        self.assertEqual(self.db.exact_len(), 1001)
+        self.assertEqual(self.db.ingest_records([]), 0)
+
+        self.assertEqual(os.listdir(self.temp), ['target'])
+
+    def test_ingest_records_cf(self):
+        self.db.create_column_family('users')
+
+        self.assertEqual(self.db.ingest_records([(b'a', b'first')], cf='users'), 1)
+        self.assertEqual(self.db.get(b'a', cf='users'), b'first')
+        self.assertIsNone(self.db.get(b'a'))
+
+    def test_ingest_records_unsorted(self):
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.ingest_records([(b'b', b'second'), (b'a', b'first')])
+
+        self.assertIsNone(self.db.get(b'b'))
+        self.assertEqual(os.listdir(self.temp), ['target'])
+
+    def test_import_from(self):
+        source = rocksdbpy.open_default(os.path.join(self.temp, 'source'))
+
+        source.set(b'a', b'first')
+        source.set(b'b', b'second')
+        source.create_column_family('users')
+        source.set(b'c', b'third', cf='users')
+        source.close()
+
+        self.assertEqual(self.db.import_from(os.path.join(self.temp, 'source')), 2)
+        self.assertEqual(list(self.db.iterator()), [(b'a', b'first'), (b'b', b'second')])
+
+        self.assertEqual(self.db.import_from(os.path.join(self.temp, 'source'), source_cf='users'), 1)
+        self.assertEqual(self.db.get(b'c'), b'third')
+
+    def test_import_from_missing(self):
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.import_from(os.path.join(self.temp, 'missing'))
+
+    def test_copy_range(self):
+        source = rocksdbpy.open_default(os.path.join(self.temp, 'source'))
+
+        source.put_many({b'%03d' % i: b'value%d' % i for i in range(100)})
+        self.db.set(b'050', b'old')
+
+        self.assertEqual(source.copy_range(self.db, b'010', b'060'), 50)
+        self.assertEqual(self.db.get(b'050'), b'value50')
+        self.assertEqual(self.db.exact_len(), 50)
+        self.assertIsNone(self.db.get(b'060'))
+
+        self.assertEqual(source.copy_range(self.db, b'200'), 0)
+
+        source.close()
+
+        self.assertEqual(sorted(os.listdir(self.temp)), ['source', 'target'])
+
+    def test_copy_range_cf(self):
+        self.db.set(b'a', b'first')
+        self.db.set(b'b', b'second')
+        self.db.create_column_family('archive')
+
+        self.assertEqual(self.db.copy_range('archive', stop=b'b'), 1)
+        self.assertEqual(self.db.get(b'a', cf='archive'), b'first')
+        self.assertIsNone(self.db.get(b'b', cf='archive'))
+
+        self.assertRaises(ValueError, self.db.copy_range, 'archive', dst_cf='archive')
+        self.assertRaises(rocksdbpy.RocksDBException, self.db.copy_range, 'missing')
+
+    def test_copy_range_text(self):
+        source = rocksdbpy.open_default(os.path.join(self.temp, 'source'), text=True)
+
+        source.put_many({'a': b'1', 'b': b'2', 'n': b'3'})
+
+        # the bounds are keys like the bounds of items
+        self.assertEqual(source.copy_range(self.db, 'a', 'm'), 2)
+        self.assertEqual(self.db.get(b'b'), b'2')
+        self.assertIsNone(self.db.get(b'n'))
+
+        source.close()
+
+    def test_copy_range_ttl(self):
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_key_ttl(True)
+
+        source = rocksdbpy.open(os.path.join(self.temp, 'source'), opts)
+
+        source.set(b'a', b'first')
+        source.set(b'b', b'second', ttl=3600)
+
+        # the expiration headers are removed for a database without the key TTL
+        self.assertEqual(source.copy_range(self.db), 2)
+        self.assertEqual(list(self.db.iterator()), [(b'a', b'first'), (b'b', b'second')])
+
+        source.close()
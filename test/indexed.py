@@ -0,0 +1,63 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+from rocksdbpy import WriteBatchWithIndex
+
+
+class TestWriteBatchWithIndex(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        self.db.set(b'a', b'stored')
+        self.db.set(b'b', b'stored')
+        self.db.set(b'd', b'stored')
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_get(self):
+        wb = WriteBatchWithIndex()
+
+        wb.add(b'a', b'pending')
+        wb.delete(b'b')
+        wb.add(b'c', b'pending')
+
+        self.assertEqual(len(wb), 3)
+        self.assertEqual(wb.get_from_batch(b'a'), b'pending')
+        self.assertIsNone(wb.get_from_batch(b'd'))
+        self.assertEqual(wb.get_from_batch_and_db(self.db, b'a'), b'pending')
+        self.assertIsNone(wb.get_from_batch_and_db(self.db, b'b'))
+        self.assertEqual(wb.get_from_batch_and_db(self.db, b'd'), b'stored')
+
+        # nothing is written before the batch
+        self.assertEqual(self.db.get(b'a'), b'stored')
+
+    def test_iterator(self):
+        wb = WriteBatchWithIndex()
+
+        wb.add(b'a', b'pending')
+        wb.delete(b'b')
+        wb.add(b'c', b'pending')
+        wb.add(b'e', b'pending')
+
+        expected = [(b'a', b'pending'), (b'c', b'pending'), (b'd', b'stored'), (b'e', b'pending')]
+
+        self.assertEqual(list(wb.iterator(self.db)), expected)
+        self.assertEqual(list(wb.iterator(self.db, reverse=True)), expected[::-1])
+
+    def test_write(self):
+        wb = WriteBatchWithIndex()
+
+        wb.add(b'a', b'pending')
+        wb.delete(b'b')
+
+        self.db.write(wb)
+
+        self.assertEqual(self.db.get(b'a'), b'pending')
+        self.assertIsNone(self.db.get(b'b'))
+        self.assertRaises(TypeError, self.db.write, b'batch')
@@ -1,3 +1,4 @@
+import inspect
 import unittest
 import rocksdbpy
 import shutil
@@ -73,8 +74,237 @@ class TestIterator(unittest.TestCase):
 
             i -= 1
 
+    def test_for_prev(self):
+        # get iterator which starts from the last key before the target
+        itr = self.db.iterator(mode='for_prev', key=b'test_add_25')
+
+        k, v = next(itr)
+
+        self.assertEqual(b'test_add_2', k)
+        self.assertEqual(b'test_value', v)
+
+        k, _ = next(itr)
+
+        self.assertEqual(b'test_add_3', k)
+
+        # exact match is returned as well
+        itr = self.db.iterator(mode='for_prev', key=b'test_add_3', direction=-1)
+
+        keys = [k for k, _ in itr]
+
+        self.assertEqual([b'test_add_3', b'test_add_2', b'test_add_1'], keys)
+
+    def test_bounds(self):
+        # get iterator which stops at the upper bound
+        itr = self.db.iterator(lower_bound=b'test_add_2', upper_bound=b'test_add_3')
+
+        self.assertEqual([b'test_add_2'], [k for k, _ in itr])
+
+        # reverse iterator starts from the upper bound
+        itr = self.db.iterator(mode='end', upper_bound=b'test_add_3')
+
+        self.assertEqual([b'test_add_2', b'test_add_1'], [k for k, _ in itr])
+
+    def test_prefix(self):
+        self.db.set(b'test_b', b'test_value')
+        self.db.set(b'tesu', b'test_value')
+
+        itr = self.db.prefix_iterator(b'test_add_')
+
+        self.assertEqual([b'test_add_1', b'test_add_2', b'test_add_3'], [k for k, _ in itr])
+
+        itr = self.db.prefix_iterator(b'test_add_2')
+
+        self.assertEqual([b'test_add_2'], [k for k, _ in itr])
+
+        itr = self.db.prefix_iterator(b'missing')
+
+        self.assertEqual(0, itr.len())
+
+    def test_tailing(self):
+        itr = self.db.iterator(tailing=True)
+
+        self.assertEqual(3, len([k for k, _ in itr]))
+
+        # new entries are picked up after the iterator reached the end
+        self.db.set(b'test_add_4', b'test_value')
+
+        self.assertEqual([b'test_add_4'], [k for k, _ in itr])
+        self.assertEqual([], [k for k, _ in itr])
+
+    def test_refresh(self):
+        itr = self.db.iterator()
+
+        k, _ = next(itr)
+
+        self.assertEqual(b'test_add_1', k)
+
+        self.db.set(b'test_add_25', b'test_value')
+
+        # iterator keeps its position and sees the new entry
+        itr.refresh()
+
+        keys = [k for k, _ in itr]
+
+        self.assertEqual([b'test_add_2', b'test_add_25', b'test_add_3'], keys)
+
+        self.db.set(b'test_add_4', b'test_value')
+
+        itr.refresh()
+
+        self.assertEqual([b'test_add_4'], [k for k, _ in itr])
+
+    def test_readahead(self):
+        itr = self.db.iterator(readahead_size=1024 * 1024, auto_readahead_size=False, async_io=True)
+
+        self.assertEqual(3, len([k for k, _ in itr]))
+
+        self.assertRaises(OverflowError, self.db.iterator, readahead_size=-1)
+
+    def test_next_batch(self):
+        itr = self.db.iterator()
+
+        batch = itr.next_batch(2)
+
+        self.assertEqual([(b'test_add_1', b'test_value'), (b'test_add_2', b'test_value')], batch)
+
+        batch = itr.next_batch(2)
+
+        self.assertEqual([(b'test_add_3', b'test_value')], batch)
+        self.assertEqual([], itr.next_batch(2))
+
+    def test_keys_values(self):
+        itr = self.db.iterator(values=False)
+
+        self.assertEqual([b'test_add_1', b'test_add_2', b'test_add_3'], list(itr))
+
+        itr = self.db.iterator(mode='end', keys=False)
+
+        self.assertEqual([b'test_value'] * 3, list(itr))
+
+        itr = self.db.iterator(values=False)
+
+        self.assertEqual([b'test_add_1', b'test_add_2'], itr.next_batch(2))
+
+        self.assertRaises(rocksdbpy.RocksDBException, self.db.iterator, keys=False, values=False)
+
+    def test_items(self):
+        items = list(self.db.items())
+
+        self.assertEqual(3, len(items))
+        self.assertEqual((b'test_add_1', b'test_value'), items[0])
+
+        items = list(self.db.items(b'test_add_2'))
+
+        self.assertEqual([(b'test_add_2', b'test_value'), (b'test_add_3', b'test_value')], items)
+
+        keys = list(self.db.keys(b'test_add_1', b'test_add_3'))
+
+        self.assertEqual([b'test_add_1', b'test_add_2'], keys)
+
+        values = list(self.db.values(stop=b'test_add_2'))
+
+        self.assertEqual([b'test_value'], values)
+
+    def test_items_reverse(self):
+        keys = list(self.db.keys(b'test_add_1', b'test_add_3', reverse=True))
+
+        self.assertEqual([b'test_add_2', b'test_add_1'], keys)
+
+        # stop key between the existing keys
+        keys = list(self.db.keys(b'test_add_2', b'test_add_25', reverse=True))
+
+        self.assertEqual([b'test_add_2'], keys)
+
+        items = list(self.db.items(reverse=True))
+
+        self.assertEqual(b'test_add_3', items[0][0])
+
+        self.assertEqual([], list(self.db.values(b'test_add_4', reverse=True)))
+
+    def test_db_count(self):
+        self.assertEqual(3, self.db.count())
+        self.assertEqual(2, self.db.count(b'test_add_2'))
+        self.assertEqual(1, self.db.count(b'test_add_2', b'test_add_3'))
+        self.assertEqual(0, self.db.count(stop=b'test_add_1'))
+
+        self.db.flush()
+
+        self.assertGreaterEqual(self.db.count(b'test_add_2', b'test_add_3', approximate=True), 1)
+        self.assertEqual(3, self.db.count(approximate=True))
+
+    def test_db_count_column_family(self):
+        self.db.create_column_family('users')
+
+        for i in range(100):
+            self.db.set(b'test_add_2_%03d' % i, b'value', cf='users')
+
+        self.db.flush()
+        self.db.compact_range(cf='users')
+
+        # the files of the other column family are not counted
+        self.assertEqual(1, self.db.count(b'test_add_2', b'test_add_3', approximate=True))
+
+    def test_pinned(self):
+        itr = self.db.iterator(pinned=True)
+
+        k, v = next(itr)
+
+        self.assertEqual(b'test_add_1', k)
+        self.assertIsInstance(v, memoryview)
+        self.assertEqual(b'test_value', v.tobytes())
+
+        next(itr)
+
+        # the previous value is released when the iterator moves
+        self.assertRaises(ValueError, v.tobytes)
+
+        self.assertEqual([b'test_add_3'], [k for k, _ in itr])
+
+    def test_pinned_derived(self):
+        itr = self.db.iterator(pinned=True)
+
+        _, v = next(itr)
+        part = v[5:]
+
+        # the derived view shares the buffer, so the iterator does not move under it
+        self.assertRaises(BufferError, next, itr)
+        self.assertEqual(b'value', bytes(part))
+
+        part.release()
+
+        self.assertEqual(b'test_add_2', next(itr)[0])
+
+    def test_pinned_drop(self):
+        itr = self.db.iterator(pinned=True)
+
+        _, v = next(itr)
+        part = memoryview(v)[5:]
+
+        del itr
+
+        # the view of the iterator is released, the derived view keeps the value alive
+        self.assertRaises(ValueError, bytes, v)
+        self.assertEqual(b'value', bytes(part))
+
     def test_count(self):
         # get random iterator
         itr = self.db.iterator(mode='from', direction=-1)
 
         self.assertEqual(3, itr.len())
+
+    def test_keyword_only(self):
+        itr = self.db.iterator('from', b'test_add_2', 1)
+
+        self.assertEqual(b'test_add_2', next(itr)[0])
+
+        # the read options cannot be passed by position
+        with self.assertRaises(TypeError):
+            self.db.iterator('start', None, 1, b'test_add_2')
+
+    def test_signature(self):
+        signature = inspect.signature(self.db.iterator)
+
+        self.assertEqual(signature.parameters['mode'].default, None)
+        self.assertEqual(signature.parameters['lower_bound'].kind, inspect.Parameter.KEYWORD_ONLY)
+        self.assertEqual(str(inspect.signature(self.db.get)), '(key, cf=None)')
@@ -0,0 +1,51 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+from rocksdbpy import Key
+
+
+class TestKey(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_u64_be(self):
+        key = Key.u64_be(256) + b'id'
+
+        self.assertEqual(key, b'\x00\x00\x00\x00\x00\x00\x01\x00id')
+        self.assertEqual(Key.decode_u64_be(key), 256)
+
+        self.assertRaises(OverflowError, Key.u64_be, -1)
+
+    def test_i64_be(self):
+        values = [-2 ** 63, -1, 0, 1, 2 ** 63 - 1]
+        keys = [Key.i64_be(v) for v in values]
+
+        self.assertEqual(sorted(keys), keys)
+        self.assertEqual([Key.decode_i64_be(k) for k in keys], values)
+
+    def test_u32_be(self):
+        self.assertEqual(Key.u32_be(1), b'\x00\x00\x00\x01')
+        self.assertEqual(Key.decode_u32_be(b'id' + Key.u32_be(7), 2), 7)
+
+        self.assertEqual(sorted([Key.i32_be(1), Key.i32_be(-1)]), [Key.i32_be(-1), Key.i32_be(1)])
+        self.assertEqual(Key.decode_i32_be(Key.i32_be(-5)), -5)
+
+    def test_decode_short(self):
+        self.assertRaises(ValueError, Key.decode_u64_be, b'\x00\x01')
+        self.assertRaises(ValueError, Key.decode_u32_be, Key.u32_be(1), 1)
+
+    def test_range(self):
+        for ts in [1, 255, 256, 65536]:
+            self.db.set(Key.u64_be(ts) + b'id', b'value')
+
+        keys = [Key.decode_u64_be(k) for k in self.db.keys(Key.u64_be(200), Key.u64_be(70000))]
+
+        self.assertEqual(keys, [255, 256, 65536])
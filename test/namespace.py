@@ -0,0 +1,67 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestNamespace(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        self.db.set(b'a', b'outside')
+        self.db.set(b'users;', b'outside')
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_get_set(self):
+        ns = self.db.namespace(b'users:')
+
+        ns.set(b'1', b'first')
+        ns[b'2'] = b'second'
+
+        self.assertEqual(ns.prefix, b'users:')
+        self.assertEqual(ns.get(b'1'), b'first')
+        self.assertEqual(ns[b'2'], b'second')
+        self.assertEqual(self.db.get(b'users:1'), b'first')
+        self.assertTrue(b'1' in ns)
+        self.assertFalse(b'a' in ns)
+
+        ns.delete(b'1')
+        del ns[b'2']
+
+        self.assertIsNone(ns.get(b'1'))
+        self.assertRaises(KeyError, ns.__getitem__, b'2')
+
+    def test_iterator(self):
+        ns = self.db.namespace(b'users:')
+
+        ns.set(b'1', b'first')
+        ns.set(b'2', b'second')
+        ns.set(b'3', b'third')
+
+        self.assertEqual(list(ns.iterator()), [(b'1', b'first'), (b'2', b'second'), (b'3', b'third')])
+        self.assertEqual(list(ns.iterator(reverse=True))[0], (b'3', b'third'))
+        self.assertEqual(list(ns.keys(b'2')), [b'2', b'3'])
+        self.assertEqual(list(ns.values(stop=b'2')), [b'first'])
+
+    def test_nested(self):
+        ns = self.db.namespace(b'users:').namespace(b'admins:')
+
+        ns.set(b'1', b'first')
+
+        self.assertEqual(ns.prefix, b'users:admins:')
+        self.assertEqual(self.db.get(b'users:admins:1'), b'first')
+        self.assertEqual(list(ns.keys()), [b'1'])
+
+    def test_closed(self):
+        ns = self.db.namespace(b'users:')
+
+        self.db.close()
+
+        self.assertRaises(rocksdbpy.DBClosedError, ns.get, b'1')
+        self.assertRaises(rocksdbpy.DBClosedError, ns.iterator)
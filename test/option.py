@@ -366,6 +366,26 @@ class TestOption(unittest.TestCase):
         self.assertIsNone(o.set_max_compaction_bytes(0))
         self.assertRaises(TypeError, o.set_max_compaction_bytes, None)
 
+    def test_set_periodic_compaction_seconds(self):
+        o = Option()
+
+        self.assertIsNone(o.set_periodic_compaction_seconds(7 * 24 * 3600))
+        self.assertRaises(TypeError, o.set_periodic_compaction_seconds, None)
+
+    def test_set_ttl(self):
+        o = Option()
+
+        self.assertIsNone(o.set_ttl(30 * 24 * 3600))
+        self.assertRaises(TypeError, o.set_ttl, None)
+
+    def test_add_compact_on_deletion_collector_factory(self):
+        o = Option()
+
+        self.assertIsNone(o.add_compact_on_deletion_collector_factory(128 * 1024, 64 * 1024, 0.5))
+        self.assertIsNone(o.add_compact_on_deletion_collector_factory(1000, 100, 0))
+        self.assertRaises(TypeError, o.add_compact_on_deletion_collector_factory, 1000, 100)
+        self.assertRaises(OverflowError, o.add_compact_on_deletion_collector_factory, -1, 100, 0.5)
+
     def test_set_wal_ttl_seconds(self):
         o = Option()
 
@@ -378,6 +398,14 @@ class TestOption(unittest.TestCase):
         self.assertIsNone(o.set_wal_size_limit_mb(64))
         self.assertRaises(TypeError, o.set_wal_size_limit_mb, None)
 
+    def test_set_wal_recovery_mode(self):
+        o = Option()
+
+        self.assertIsNone(o.set_wal_recovery_mode('point_in_time'))
+        self.assertIsNone(o.set_wal_recovery_mode('skip_any_corrupted_records'))
+        self.assertRaises(ValueError, o.set_wal_recovery_mode, 'unknown')
+        self.assertRaises(TypeError, o.set_wal_recovery_mode, None)
+
     def test_set_manifest_preallocation_size(self):
         o = Option()
 
@@ -515,3 +543,29 @@ class TestOption(unittest.TestCase):
 
         self.assertIsNotNone(o.get_write_dbid_to_manifest())
         self.assertRaises(TypeError, o.get_write_dbid_to_manifest, None)
+
+    def test_blob_files(self):
+        o = Option()
+
+        self.assertIsNone(o.set_enable_blob_files(True))
+        self.assertIsNone(o.set_min_blob_size(4096))
+        self.assertIsNone(o.set_blob_file_size(512 * 1024 * 1024))
+        self.assertIsNone(o.set_blob_compression_type('zstd'))
+        self.assertIsNone(o.set_enable_blob_gc(True))
+        self.assertIsNone(o.set_blob_gc_age_cutoff(0.5))
+        self.assertIsNone(o.set_blob_gc_force_threshold(0.8))
+        self.assertIsNone(o.set_blob_compaction_readahead_size(2 * 1024 * 1024))
+        self.assertRaises(ValueError, o.set_blob_compression_type, 'unknown')
+
+    def test_comparator(self):
+        o = Option()
+
+        self.assertIsNone(o.set_comparator('reverse', lambda a, b: (a < b) - (a > b)))
+        self.assertIsNone(o.set_composite_comparator('tenant', ['u32be', 'ascii_ci']))
+        self.assertRaises(ValueError, o.set_composite_comparator, 'tenant', ['i32be'])
+        self.assertRaises(ValueError, o.set_composite_comparator, 'tenant', ['bytes', 'u32be'])
+
+    def test_reverse_comparator(self):
+        o = Option()
+
+        self.assertIsNone(o.set_reverse_comparator())
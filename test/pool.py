@@ -0,0 +1,71 @@
+import os
+import time
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestReadPool(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.secondary = tempfile.mkdtemp()
+        self.db = rocksdbpy.open_default(self.temp)
+        self.pool = rocksdbpy.ReadPool(self.temp, self.secondary, catch_up_interval=0.05)
+
+    def tearDown(self):
+        self.pool.close()
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+        shutil.rmtree(self.secondary)
+
+    def wait_for(self, db, key):
+        for _ in range(100):
+            value = db.get(key)
+
+            if value is not None:
+                return value
+
+            time.sleep(0.02)
+
+        return None
+
+    def test_get(self):
+        reader = self.pool.get()
+
+        self.assertTrue(reader.read_only)
+        self.assertIs(self.pool.get(), reader)
+        self.assertEqual(self.pool.path, os.path.join(self.secondary, str(os.getpid())))
+
+        # the secondary catches up with the primary in the background
+        self.db.set(b'test_key', b'test_value')
+
+        self.assertEqual(self.wait_for(reader, b'test_key'), b'test_value')
+
+    def test_close(self):
+        reader = self.pool.get()
+
+        self.pool.close()
+
+        self.assertFalse(reader.is_open)
+        self.assertFalse(os.path.exists(self.pool.path))
+
+    @unittest.skipUnless(hasattr(os, 'fork'), 'requires os.fork')
+    def test_fork(self):
+        self.pool.get()
+        self.db.set(b'test_key', b'test_value')
+
+        pid = os.fork()
+
+        if pid == 0:
+            # the child opens its own secondary instead of the inherited one
+            value = self.wait_for(self.pool.get(), b'test_key')
+
+            self.pool.close()
+
+            os._exit(0 if value == b'test_value' else 1)
+
+        _, status = os.waitpid(pid, 0)
+
+        self.assertEqual(os.waitstatus_to_exitcode(status), 0)
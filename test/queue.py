@@ -0,0 +1,105 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+import threading
+
+
+class TestQueue(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.db = rocksdbpy.open_default(self.temp)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_push_pop(self):
+        queue = self.db.queue()
+
+        queue.push(b'first')
+        queue.push(b'second')
+        queue.push_front(b'zero')
+
+        self.assertEqual(queue.name, 'queue')
+        self.assertEqual(len(queue), 3)
+        self.assertEqual(queue.peek(), b'zero')
+        self.assertEqual(queue.pop_front(), b'zero')
+        self.assertEqual(queue.pop_back(), b'second')
+        self.assertEqual(queue.pop_front(), b'first')
+        self.assertIsNone(queue.pop_front())
+        self.assertIsNone(queue.peek())
+
+    def test_separate(self):
+        jobs = self.db.queue('jobs')
+
+        jobs.push(b'job')
+        self.db.set(b'key', b'value')
+
+        self.assertIsNone(self.db.queue('emails').pop_front())
+        self.assertEqual(list(self.db.iterator()), [(b'key', b'value')])
+        self.assertEqual(jobs.pop_front(), b'job')
+
+    def test_shared_bounds(self):
+        first = self.db.queue()
+        second = self.db.queue()
+
+        first.push(b'a')
+        first.push(b'b')
+
+        self.assertEqual(first.pop_front(), b'a')
+
+        # the bounds of the popped entries are shared by the queues of the same column family
+        second.push_front(b'z')
+
+        self.assertEqual(first.pop_front(), b'z')
+        self.assertEqual(first.pop_back(), b'b')
+        self.assertIsNone(second.pop_front())
+
+        second.push(b'c')
+
+        self.assertEqual(len(first), 1)
+        self.assertEqual(first.peek(), b'c')
+
+    def test_foreign_key(self):
+        queue = self.db.queue()
+
+        self.db.set(b'abc', b'value', cf='queue')
+
+        self.assertRaises(ValueError, queue.push, b'job')
+        self.assertRaises(ValueError, queue.pop_front)
+
+    def test_reopen(self):
+        self.db.queue().push(b'first')
+        self.db.close()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        self.assertEqual(self.db.queue().pop_front(), b'first')
+
+    def test_pop_concurrent(self):
+        queue = self.db.queue()
+        popped = []
+
+        for i in range(100):
+            queue.push(str(i).encode())
+
+        def worker():
+            while True:
+                value = queue.pop_front()
+
+                if value is None:
+                    return
+
+                popped.append(value)
+
+        threads = [threading.Thread(target=worker) for _ in range(4)]
+
+        for t in threads:
+            t.start()
+
+        for t in threads:
+            t.join()
+
+        self.assertEqual(sorted(popped), sorted(str(i).encode() for i in range(100)))
@@ -0,0 +1,57 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestParallelScan(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        # several SST files give the scan split points
+        for part in range(4):
+            self.db.put_many({b'key_%05d' % i: b'value_%d' % i for i in range(part * 2500, (part + 1) * 2500)})
+            self.db.flush()
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_scan(self):
+        entries = list(self.db.parallel_scan(workers=4))
+
+        self.assertEqual(len(entries), 10000)
+        self.assertEqual(sorted(entries), list(self.db.items()))
+
+    def test_range(self):
+        entries = sorted(self.db.parallel_scan(b'key_01000', b'key_09000', workers=3))
+
+        self.assertEqual(entries, list(self.db.items(b'key_01000', b'key_09000')))
+        self.assertEqual(entries[0], (b'key_01000', b'value_1000'))
+        self.assertEqual(entries[-1], (b'key_08999', b'value_8999'))
+
+    def test_single_worker(self):
+        self.db.set(b'key_99999', b'memtable')
+
+        entries = list(self.db.parallel_scan(workers=1))
+
+        # a single chunk keeps the key order
+        self.assertEqual(entries, list(self.db.items()))
+
+    def test_close(self):
+        scan = self.db.parallel_scan(workers=4)
+
+        next(scan)
+        scan.close()
+
+        self.assertRaises(StopIteration, next, scan)
+
+    def test_errors(self):
+        self.assertRaises(ValueError, self.db.parallel_scan, workers=0)
+
+        self.db.close()
+
+        self.assertRaises(rocksdbpy.DBClosedError, self.db.parallel_scan)
@@ -0,0 +1,73 @@
+import time
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestSecondary(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.secondary = tempfile.mkdtemp()
+        self.db = rocksdbpy.open_default(self.temp)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+        shutil.rmtree(self.secondary)
+
+    def test_catch_up(self):
+        db = rocksdbpy.RocksDB.open_as_secondary(self.temp, self.secondary)
+
+        self.assertTrue(db.read_only)
+
+        self.db.set(b'test_key', b'test_value')
+
+        self.assertIsNone(db.get(b'test_key'))
+
+        db.try_catch_up_with_primary()
+
+        self.assertEqual(db.get(b'test_key'), b'test_value')
+
+        db.close()
+
+    def test_catch_up_interval(self):
+        db = rocksdbpy.RocksDB.open_as_secondary(self.temp, self.secondary, catch_up_interval=0.05)
+
+        self.db.set(b'test_key', b'test_value')
+
+        for _ in range(100):
+            if db.get(b'test_key') is not None:
+                break
+
+            time.sleep(0.02)
+
+        self.assertEqual(db.get(b'test_key'), b'test_value')
+
+        db.close()
+
+    def test_catch_up_lag(self):
+        db = rocksdbpy.RocksDB.open_as_secondary(self.temp, self.secondary)
+
+        self.db.set(b'test_key1', b'test_value')
+        self.db.set(b'test_key2', b'test_value')
+
+        info = db.catch_up_lag()
+
+        self.assertEqual(info['lag'], info['primary_sequence'] - info['secondary_sequence'])
+        self.assertGreaterEqual(info['lag'], 2)
+
+        db.try_catch_up_with_primary()
+
+        self.assertEqual(db.catch_up_lag()['lag'], 0)
+
+        db.close()
+
+    def test_catch_up_lag_primary(self):
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.catch_up_lag()
+
+    def test_catch_up_interval_invalid(self):
+        with self.assertRaises(ValueError):
+            rocksdbpy.RocksDB.open_as_secondary(self.temp, self.secondary, catch_up_interval=0)
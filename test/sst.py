@@ -0,0 +1,201 @@
+import os
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestSstFileWriter(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.path = os.path.join(self.temp, 'data.sst')
+
+    def tearDown(self):
+        shutil.rmtree(self.temp)
+
+    def test_write(self):
+        writer = rocksdbpy.SstFileWriter()
+        writer.open(self.path)
+
+        writer.put(b'test_key1', b'test_value')
+        writer.merge(b'test_key2', b'test_value')
+        writer.delete(b'test_key3')
+
+        self.assertGreater(writer.file_size, 0)
+
+        writer.finish()
+
+        self.assertGreater(os.path.getsize(self.path), 0)
+
+    def test_ingest_external_file(self):
+        with rocksdbpy.SstFileWriter() as writer:
+            writer.open(self.path)
+
+            for i in range(100):
+                writer.put(f'test_key{i:03}'.encode(), b'test_value')
+
+            writer.delete(b'test_key200')
+            writer.merge(b'test_key300', b'test_value')
+
+        db = rocksdbpy.open_default(os.path.join(self.temp, 'db'))
+        db.set(b'test_key200', b'test_value')
+
+        db.ingest_external_file([self.path])
+
+        self.assertFalse(os.path.exists(self.path))
+        self.assertEqual(db.get(b'test_key099'), b'test_value')
+        self.assertIsNone(db.get(b'test_key200'))
+        self.assertEqual(db.get(b'test_key300'), b'test_value')
+
+        db.close()
+
+    def test_ingest_external_file_copy_cf(self):
+        with rocksdbpy.SstFileWriter() as writer:
+            writer.open(self.path)
+            writer.put(b'test_key', b'test_value')
+
+        db = rocksdbpy.open_default(os.path.join(self.temp, 'db'))
+        db.create_column_family('users')
+
+        db.ingest_external_file([self.path], move_files=False, cf='users')
+
+        self.assertTrue(os.path.exists(self.path))
+        self.assertEqual(db.get(b'test_key', cf='users'), b'test_value')
+        self.assertIsNone(db.get(b'test_key'))
+
+        db.close()
+
+    def test_ingest_behind(self):
+        with rocksdbpy.SstFileWriter() as writer:
+            writer.open(self.path)
+            writer.put(b'test_key1', b'old_value')
+            writer.put(b'test_key2', b'old_value')
+
+        opts = rocksdbpy.Option()
+        opts.create_if_missing(True)
+        opts.set_allow_ingest_behind(True)
+        opts.set_compaction_style('universal')
+
+        db = rocksdbpy.open(os.path.join(self.temp, 'db'), opts)
+        db.set(b'test_key1', b'new_value')
+
+        db.ingest_external_file([self.path], ingest_behind=True)
+
+        self.assertEqual(db.get(b'test_key1'), b'new_value')
+        self.assertEqual(db.get(b'test_key2'), b'old_value')
+
+        db.close()
+
+    def test_ingest_missing(self):
+        db = rocksdbpy.open_default(os.path.join(self.temp, 'db'))
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            db.ingest_external_file([self.path])
+
+        db.close()
+
+    def test_context_manager(self):
+        with rocksdbpy.SstFileWriter() as writer:
+            writer.open(self.path)
+            writer.put(b'test_key', b'test_value')
+
+        # the file is finished, so another one can be opened
+        writer.open(os.path.join(self.temp, 'other.sst'))
+        writer.put(b'test_key', b'test_value')
+        writer.finish()
+
+        self.assertTrue(os.path.exists(self.path))
+
+    def test_unordered(self):
+        writer = rocksdbpy.SstFileWriter()
+        writer.open(self.path)
+        writer.put(b'test_key2', b'test_value')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            writer.put(b'test_key1', b'test_value')
+
+    def test_not_open(self):
+        writer = rocksdbpy.SstFileWriter()
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            writer.put(b'test_key', b'test_value')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            writer.finish()
+
+    def test_ttl(self):
+        writer = rocksdbpy.SstFileWriter()
+        writer.open(self.path)
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            writer.put(b'test_key', b'test_value', ttl=60)
+
+        opts = rocksdbpy.Option()
+        opts.set_key_ttl(True)
+
+        writer = rocksdbpy.SstFileWriter(opts)
+        writer.open(self.path)
+        writer.put(b'test_key', b'test_value', ttl=60)
+        writer.finish()
+
+
+class TestSstFileReader(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+        self.path = os.path.join(self.temp, 'data.sst')
+
+        with rocksdbpy.SstFileWriter() as writer:
+            writer.open(self.path)
+
+            for i in range(10):
+                writer.put(f'test_key{i}'.encode(), b'test_value')
+
+            writer.delete(b'test_key_deleted')
+
+    def tearDown(self):
+        shutil.rmtree(self.temp)
+
+    def test_items(self):
+        with rocksdbpy.SstFileReader(self.path) as reader:
+            items = list(reader.items())
+
+            self.assertEqual(len(items), 10)
+            self.assertEqual(items[0], (b'test_key0', b'test_value'))
+
+            keys = [key for key, _ in reader.items(b'test_key2', b'test_key5', reverse=True)]
+
+            self.assertEqual(keys, [b'test_key4', b'test_key3', b'test_key2'])
+
+            self.assertEqual(reader.get(b'test_key3'), b'test_value')
+            self.assertIsNone(reader.get(b'test_key_deleted'))
+
+        # the file is copied, so it is kept
+        self.assertTrue(os.path.exists(self.path))
+
+    def test_properties(self):
+        with rocksdbpy.SstFileReader(self.path) as reader:
+            properties = reader.properties()
+
+            self.assertEqual(properties['num_entries'], 11)
+            self.assertEqual(properties['num_deletions'], 1)
+            self.assertGreater(properties['size'], 0)
+            self.assertEqual(properties['smallest_key'], b'test_key0')
+            self.assertEqual(properties['largest_key'], b'test_key_deleted')
+
+            reader.verify_checksum()
+
+    def test_closed(self):
+        reader = rocksdbpy.SstFileReader(self.path)
+        reader.close()
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            reader.get(b'test_key0')
+
+    def test_invalid(self):
+        path = os.path.join(self.temp, 'invalid.sst')
+
+        with open(path, 'wb') as f:
+            f.write(b'invalid')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            rocksdbpy.SstFileReader(path)
@@ -0,0 +1,78 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+from rocksdbpy import Option
+
+
+class TestTimestamp(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        opts = Option()
+        opts.create_if_missing(True)
+        opts.enable_user_timestamp()
+
+        self.db = rocksdbpy.open(self.temp, opts)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_get_as_of(self):
+        self.db.set_with_ts(b'key', b'first', 10)
+        self.db.set_with_ts(b'key', b'second', 20)
+
+        self.assertIsNone(self.db.get_with_ts(b'key', 5))
+        self.assertEqual(self.db.get_with_ts(b'key', 10), b'first')
+        self.assertEqual(self.db.get_with_ts(b'key', 15), b'first')
+        self.assertEqual(self.db.get_with_ts(b'key', 20), b'second')
+        self.assertEqual(self.db.get_with_ts(b'key', 2 ** 64 - 1), b'second')
+
+    def test_delete(self):
+        self.db.set_with_ts(b'key', b'value', 10)
+        self.db.delete_with_ts(b'key', 20)
+
+        self.assertEqual(self.db.get_with_ts(b'key', 15), b'value')
+        self.assertIsNone(self.db.get_with_ts(b'key', 20))
+
+    def test_iterator(self):
+        self.db.set_with_ts(b'a', b'first', 10)
+        self.db.set_with_ts(b'b', b'first', 10)
+        self.db.set_with_ts(b'a', b'second', 20)
+        self.db.set_with_ts(b'c', b'second', 20)
+
+        self.assertEqual(list(self.db.iterator(timestamp=15)), [(b'a', b'first'), (b'b', b'first')])
+        self.assertEqual(list(self.db.iterator(timestamp=20)),
+                         [(b'a', b'second'), (b'b', b'first'), (b'c', b'second')])
+
+    def test_without_timestamp(self):
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.set(b'key', b'value')
+
+    def test_full_history_ts_low(self):
+        self.assertEqual(self.db.get_full_history_ts_low(), 0)
+
+        self.db.set_with_ts(b'key', b'value', 10)
+        self.db.increase_full_history_ts_low(15)
+
+        self.assertEqual(self.db.get_full_history_ts_low(), 15)
+        self.assertEqual(self.db.get_with_ts(b'key', 20), b'value')
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.get_with_ts(b'key', 12)
+
+        with self.assertRaises(rocksdbpy.RocksDBException):
+            self.db.increase_full_history_ts_low(5)
+
+    def test_column_family(self):
+        self.db.create_column_family('users')
+        self.db.set_with_ts(b'key', b'value', 10, cf='users')
+
+        self.assertEqual(self.db.get_with_ts(b'key', 10, cf='users'), b'value')
+        self.assertIsNone(self.db.get_with_ts(b'key', 10))
+
+        self.db.increase_full_history_ts_low(5, cf='users')
+
+        self.assertEqual(self.db.get_full_history_ts_low(cf='users'), 5)
@@ -0,0 +1,118 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+import time
+from rocksdbpy import Option
+
+
+class TestTTL(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        opts = Option()
+        opts.create_if_missing(True)
+        opts.set_key_ttl(True)
+
+        self.db = rocksdbpy.open(self.temp, opts)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_expire(self):
+        self.db.set(b'expiring', b'first', ttl=1)
+        self.db.set(b'persistent', b'second')
+
+        self.assertEqual(self.db.get(b'expiring'), b'first')
+
+        time.sleep(2)
+
+        self.assertIsNone(self.db.get(b'expiring'))
+        self.assertFalse(b'expiring' in self.db)
+        self.assertEqual(self.db.get(b'persistent'), b'second')
+        self.assertEqual(list(self.db.iterator()), [(b'persistent', b'second')])
+
+    def test_batch(self):
+        with self.db.write_batch() as b:
+            b.add(b'expiring', b'first', ttl=1)
+            b.add(b'persistent', b'second')
+
+        time.sleep(2)
+
+        self.assertIsNone(self.db.get(b'expiring'))
+        self.assertEqual(self.db.get(b'persistent'), b'second')
+
+    def test_cursor(self):
+        self.db.set(b'expiring', b'first', ttl=1)
+        self.db.set(b'persistent', b'second')
+
+        cursor = self.db.cursor()
+        cursor.seek_to_first()
+
+        self.assertEqual(cursor.value(), b'first')
+
+        time.sleep(2)
+
+        # the record expired after the cursor moved to it
+        self.assertRaises(rocksdbpy.RocksDBException, cursor.value)
+
+        cursor.seek_to_first()
+
+        self.assertEqual((cursor.key(), cursor.value()), (b'persistent', b'second'))
+
+    def test_compare_and_swap(self):
+        self.db.set(b'expiring', b'first', ttl=1)
+
+        self.assertEqual(self.db.compare_and_swap(b'expiring', b'first', b'second', ttl=1), (True, b'first'))
+
+        time.sleep(2)
+
+        # the new value expires like a value which is set with a ttl
+        self.assertIsNone(self.db.get(b'expiring'))
+
+    def test_update(self):
+        self.db.set(b'expiring', b'first', ttl=1)
+
+        self.assertEqual(self.db.update(b'expiring', lambda v: v + b'!', ttl=1), b'first!')
+
+        time.sleep(2)
+
+        self.assertIsNone(self.db.get(b'expiring'))
+
+    def test_counter(self):
+        self.db.incr(b'counter')
+        self.db.append(b'events', b'first,')
+
+        self.assertEqual(self.db.incr(b'counter', 2), 3)
+        self.assertEqual(self.db.get(b'events'), b'first,')
+
+    def test_disabled(self):
+        temp = tempfile.mkdtemp()
+        db = rocksdbpy.open_default(temp)
+
+        self.assertRaises(rocksdbpy.RocksDBException, db.set, b'key', b'value', 1)
+
+        db.close()
+        shutil.rmtree(temp)
+
+    def test_open_with_ttl(self):
+        path = f'{self.temp}/with_ttl'
+
+        db = rocksdbpy.RocksDB.open_with_ttl(path, 3600, cf_ttls={'sessions': 60})
+
+        db.set(b'test_key', b'test_value')
+        db.set(b'test_key', b'test_session', cf='sessions')
+
+        self.assertEqual(db.get(b'test_key'), b'test_value')
+        self.assertEqual(db.get(b'test_key', cf='sessions'), b'test_session')
+
+        db.close()
+
+        # the column family exists after it is created by the TTL
+        db = rocksdbpy.RocksDB.open_with_ttl(path, 3600)
+
+        self.assertEqual(db.get(b'test_key', cf='sessions'), b'test_session')
+
+        db.close()
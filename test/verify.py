@@ -0,0 +1,90 @@
+import os
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+
+
+class TestVerify(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_verify(self):
+        calls = []
+
+        self.db.put_many({b'key_%04d' % i: b'value_%d' % i for i in range(3000)})
+        self.db.flush()
+        self.db.set(b'memtable', b'value')
+
+        report = self.db.verify(progress=lambda done, total: calls.append((done, total)))
+
+        self.assertTrue(report['ok'])
+        self.assertEqual(report['keys'], 3001)
+        self.assertEqual(report['corrupt_files'], [])
+        self.assertEqual(report['corrupt_keys'], [])
+        self.assertEqual(calls[-1][0], 3001)
+
+    def test_secondary(self):
+        self.db.create_column_family('users')
+        self.db.set(b'key', b'value')
+        self.db.set(b'user', b'value', cf='users')
+        self.db.flush()
+
+        # the secondary lists the column families of the primary, not of its own directory
+        db = rocksdbpy.RocksDB.open_as_secondary(self.temp, os.path.join(self.temp, 'secondary'))
+
+        report = db.verify()
+
+        self.assertTrue(report['ok'])
+        self.assertEqual(report['keys'], 2)
+
+        db.close()
+
+    def test_corrupt_file(self):
+        # two files with separate key ranges, only the first one is damaged
+        self.db.put_many({b'a_%04d' % i: os.urandom(100) for i in range(1000)})
+        self.db.flush()
+        self.db.put_many({b'b_%04d' % i: os.urandom(100) for i in range(1000)})
+        self.db.flush()
+        self.db.close()
+
+        first = min(name for name in os.listdir(self.temp) if name.endswith('.sst'))
+
+        with open(os.path.join(self.temp, first), 'r+b') as f:
+            f.seek(100)
+            f.write(b'\xff' * 100)
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+        report = self.db.verify()
+
+        self.assertFalse(report['ok'])
+        self.assertEqual(len(report['corrupt_files']), 1)
+        self.assertEqual(report['corrupt_files'][0]['name'], '/' + first)
+        self.assertEqual(report['corrupt_files'][0]['column_family'], 'default')
+        self.assertGreaterEqual(report['keys'], 1000)
+
+    def test_corrupt_key(self):
+        self.db.set(b'broken', b'not json')
+        self.db.close()
+
+        self.db = rocksdbpy.open_default(self.temp, codec='json')
+        self.db.set(b'valid', {'a': 1})
+
+        report = self.db.verify()
+
+        self.assertFalse(report['ok'])
+        self.assertEqual(report['keys'], 2)
+        self.assertEqual([key['key'] for key in report['corrupt_keys']], [b'broken'])
+
+    def test_closed(self):
+        self.db.close()
+
+        self.assertRaises(rocksdbpy.DBClosedError, self.db.verify)
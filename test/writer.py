@@ -0,0 +1,74 @@
+import unittest
+import rocksdbpy
+import shutil
+import tempfile
+import time
+
+
+class TestBufferedWriter(unittest.TestCase):
+    def setUp(self):
+        self.temp = tempfile.mkdtemp()
+
+        self.db = rocksdbpy.open_default(self.temp)
+
+    def tearDown(self):
+        self.db.close()
+
+        shutil.rmtree(self.temp)
+
+    def test_context(self):
+        with self.db.buffered() as w:
+            w.set(b'first', b'value')
+            w.set(b'second', b'value')
+            w.delete(b'second')
+
+            self.assertEqual(len(w), 3)
+            self.assertIsNone(self.db.get(b'first'))
+
+        self.assertEqual(self.db.get(b'first'), b'value')
+        self.assertIsNone(self.db.get(b'second'))
+        self.assertRaises(rocksdbpy.RocksDBException, w.set, b'third', b'value')
+
+    def test_max_ops(self):
+        w = self.db.buffered(max_ops=2)
+
+        w.set(b'first', b'value')
+        self.assertIsNone(self.db.get(b'first'))
+
+        w.set(b'second', b'value')
+        self.assertEqual(self.db.get(b'first'), b'value')
+        self.assertEqual(len(w), 0)
+
+        w.close()
+
+    def test_max_bytes(self):
+        w = self.db.buffered(max_bytes=100)
+
+        w.set(b'first', b'x' * 200)
+
+        self.assertEqual(self.db.get(b'first'), b'x' * 200)
+
+        w.close()
+
+    def test_flush_interval(self):
+        w = self.db.buffered(flush_interval=0.05)
+
+        w.set(b'first', b'value')
+
+        time.sleep(0.5)
+
+        self.assertEqual(self.db.get(b'first'), b'value')
+
+        w.close()
+
+        self.assertRaises(ValueError, self.db.buffered, flush_interval=0)
+
+    def test_flush(self):
+        w = self.db.buffered()
+
+        w.set(b'first', b'value')
+        w.flush()
+
+        self.assertEqual(self.db.get(b'first'), b'value')
+
+        w.close()